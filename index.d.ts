@@ -18,6 +18,11 @@ interface DataMeta {
     loc: Location;
     comment?: string;
     children?: DataMeta[];
+
+    /**
+     * 值为数组时，数组中对象元素的字段
+     */
+    items?: DataMeta[];
 }
 
 interface PropertyMeta {
@@ -61,10 +66,25 @@ interface ComponentMeta {
      */
     properties: PropertyMeta[];
 
+    /**
+     * 计算属性定义
+     */
+    computed: MethodMeta[];
+
     /**
      * 绑定事件定义
      */
     events?: EventMeta[];
+
+    /**
+     * behaviors 中的内置 behavior 和引用的变量名，例如 `swan://form-field`
+     */
+    behaviors: string[];
+
+    /**
+     * externalClasses 中声明的外部样式类
+     */
+    externalClasses: string[];
 }
 
 interface ClassNameMeta {
@@ -85,6 +105,608 @@ interface CssMeta {
     imports: string[];
 }
 
+interface ParseError {
+
+    /**
+     * 错误类型：文件不存在、语法错误、未找到 Page 或 Component 调用、嵌套过深、解析器内部错误
+     */
+    kind: 'NotFound' | 'SyntaxError' | 'ComponentNotFound' | 'NestingTooDeep' | 'InternalError';
+    message: string;
+
+    /**
+     * 语法错误的位置
+     */
+    loc?: Location;
+}
+
+type FileResult<T> =
+    | {status: 'ok'; meta: T; elapsed: number}
+    | {status: 'error'; error: ParseError; elapsed: number};
+
+interface BatchResult<T> {
+
+    /**
+     * 每个文件的解析结果，`elapsed` 为单个文件解析耗时，单位毫秒
+     */
+    files: Record<string, FileResult<T>>;
+
+    /**
+     * 解析统计，`elapsed` 为总耗时，单位毫秒
+     */
+    summary: {
+        total: number;
+        succeeded: number;
+        failed: number;
+        elapsed: number;
+    };
+}
+
+interface Diagnostic {
+    severity: 'Error' | 'Warning';
+    message: string;
+    loc: Location;
+
+    /**
+     * 可能的修正，例如拼写错误时相近的名称
+     */
+    suggestion?: string;
+}
+
+interface UsingComponentMeta {
+    name: string;
+    path: string;
+
+    /**
+     * 自定义标签名的位置
+     */
+    loc: Location;
+
+    /**
+     * 组件路径的位置
+     */
+    valueLoc: Location;
+}
+
+interface ConfigMeta {
+    component: boolean;
+    usingComponents: Record<string, UsingComponentMeta>;
+    componentGenerics: Record<string, {name: string; default: string | null; loc: Location}>;
+    navigationBarTitleText: string | null;
+
+    /**
+     * 所有顶层配置项的位置，用于跳转到定义
+     */
+    keys: {name: string; loc: Location}[];
+
+    /**
+     * 未知或无效的配置项
+     */
+    diagnostics: Diagnostic[];
+}
+
+interface InterpolationMeta {
+
+    /**
+     * 去掉定界符后的表达式源码
+     */
+    expression: string;
+
+    /**
+     * `{= =}` 双向绑定
+     */
+    twoWay: boolean;
+
+    /**
+     * 包含定界符的位置
+     */
+    loc: Location;
+    expressionLoc: Location;
+}
+
+type ValuePart =
+    | {type: 'Text'; value: string; loc: Location}
+    | ({type: 'Interpolation'} & InterpolationMeta);
+
+interface AttributeMeta {
+    name: string;
+
+    /**
+     * 引号内的原始值，没有值的属性为 null
+     */
+    value: string | null;
+    loc: Location;
+    nameLoc: Location;
+    valueLoc: Location | null;
+
+    /**
+     * 按插值拆分的属性值
+     */
+    parts: ValuePart[];
+}
+
+interface ElementMeta {
+    name: string;
+    attributes: AttributeMeta[];
+    children: TemplateNode[];
+    loc: Location;
+    nameLoc: Location;
+
+    /**
+     * 结束标签中标签名的位置，自闭合或未闭合时为 null
+     */
+    endNameLoc: Location | null;
+    selfClosing: boolean;
+}
+
+type TemplateNode =
+    | ({type: 'Element'} & ElementMeta)
+    | {type: 'Text'; value: string; loc: Location}
+    | ({type: 'Interpolation'} & InterpolationMeta)
+    | {type: 'Comment'; value: string; loc: Location};
+
+interface TemplateMeta {
+    nodes: TemplateNode[];
+
+    /**
+     * 未闭合的标签、插值等语法错误，解析时会尽量恢复
+     */
+    diagnostics: Diagnostic[];
+}
+
+interface ReferenceMeta {
+
+    /**
+     * 变量名，即访问路径的第一项
+     */
+    name: string;
+
+    /**
+     * 静态的成员访问路径，`item.user['name']` 为 `['item', 'user', 'name']`，遇到 `a[b]` 时结束
+     */
+    path: string[];
+    loc: Location;
+    pathLoc: Location;
+}
+
+interface ExpressionMeta {
+    source: string;
+    loc: Location;
+
+    /**
+     * 按出现顺序排列的变量引用，不包括对象字面量的 key 和成员访问的属性名
+     */
+    references: ReferenceMeta[];
+}
+
+interface BindingMeta {
+    kind: 'Text' | 'Attribute' | 'If' | 'Elif' | 'Show' | 'For' | 'TrackBy';
+
+    /**
+     * 所在的属性名，文本中的插值为 null
+     */
+    attribute: string | null;
+    expression: ExpressionMeta;
+}
+
+interface AttributeCompletion {
+    /**
+     * 模板中的属性名，驼峰形式的属性转为连字符形式，事件为 `bind:` 加事件名
+     */
+    name: string;
+    kind: 'Property' | 'ExternalClass' | 'Event';
+    type: string | null;
+
+    /**
+     * 默认值
+     */
+    value: string | number | boolean | null;
+
+    /**
+     * 自定义组件为 js 中的注释，内置组件为组件目录中的说明
+     */
+    comment: string | null;
+
+    /**
+     * 只能取这些值，只有内置组件有
+     */
+    values: string[] | null;
+
+    /**
+     * 最低基础库版本，只有内置组件有
+     */
+    since: string | null;
+}
+
+interface BuiltinAttribute {
+    name: string;
+    description: string;
+
+    /**
+     * `String`、`Number`、`Boolean`、`Array`、`Object`，可以取多种类型时用 `|` 分隔
+     */
+    type: string;
+    default: string | number | boolean | null;
+    values: string[] | null;
+    since: string | null;
+}
+
+interface BuiltinEvent {
+    name: string;
+    description: string;
+    since: string | null;
+}
+
+interface BuiltinComponent {
+    name: string;
+    description: string;
+
+    /**
+     * 最低基础库版本，`null` 表示最早的基础库已经支持
+     */
+    since: string | null;
+    attributes: BuiltinAttribute[];
+
+    /**
+     * 组件触发的事件，不包括所有组件都有的 `tap`、`touchstart` 等触摸事件
+     */
+    events: BuiltinEvent[];
+}
+
+interface Catalog {
+    /**
+     * 组件目录数据的版本
+     */
+    version: string;
+
+    /**
+     * 所有内置组件都可以使用的属性，例如 `id`、`class`、`animation`
+     */
+    common: BuiltinAttribute[];
+    components: BuiltinComponent[];
+}
+
+interface TemplateImportMeta {
+    /**
+     * `Import` 引入目标文件中的命名模板，`Include` 引入目标文件中除命名模板以外的内容
+     */
+    kind: 'Import' | 'Include';
+
+    /**
+     * 属性中的原始路径
+     */
+    src: string;
+    loc: Location;
+
+    /**
+     * 相对项目根目录的路径，文件不存在时为 `null`
+     */
+    path: string | null;
+}
+
+interface TemplateDefinitionMeta {
+    name: string;
+
+    /**
+     * 所在的模板文件
+     */
+    file: string;
+    loc: Location;
+
+    /**
+     * 模板中读取的变量，即使用模板时需要在 data 中传入的 key
+     */
+    params: string[];
+}
+
+interface TemplateUsageMeta {
+    name: string;
+    loc: Location;
+
+    /**
+     * data 中传入的 key，有展开等无法确定的情况时为 `null`
+     */
+    data: string[] | null;
+    dataLoc: Location | null;
+
+    /**
+     * 使用的命名模板，找不到时为 `null`
+     */
+    definition: TemplateDefinitionMeta | null;
+}
+
+interface FilterFunctionMeta {
+    name: string;
+
+    /**
+     * 参数的源码，例如 `value`、`format = 'YYYY'`、`...args`
+     */
+    params: string[];
+
+    /**
+     * 必须传入的参数个数，即第一个带默认值或剩余参数之前的参数个数
+     */
+    required: number;
+
+    /**
+     * 有剩余参数或使用了 `arguments`，可以接受任意个实参
+     */
+    variadic: boolean;
+    comment: string | null;
+    loc: Location;
+}
+
+interface FilterModuleMeta {
+    functions: FilterFunctionMeta[];
+}
+
+interface TemplateModuleMeta {
+    /**
+     * `module` 属性中的模块名
+     */
+    name: string;
+    loc: Location;
+
+    /**
+     * `src` 属性中的原始路径，内联的模块为 `null`
+     */
+    src: string | null;
+    srcLoc: Location | null;
+
+    /**
+     * 相对项目根目录的路径，文件不存在时为 `null`
+     */
+    path: string | null;
+
+    /**
+     * 导出的函数，模块无法解析时为 `null`
+     */
+    functions: FilterFunctionMeta[] | null;
+}
+
+interface ClassDefinitionMeta {
+    file: string;
+    loc: Location;
+}
+
+interface TemplateClassMeta {
+    name: string;
+    loc: Location;
+
+    /**
+     * 所在的属性，例如 `class`、`hover-class`
+     */
+    attribute: string;
+
+    /**
+     * 可见的样式文件中的定义，没有定义时为空
+     */
+    definitions: ClassDefinitionMeta[];
+}
+
+interface EventBindingMeta {
+    /**
+     * 属性名，例如 `capture-bind:tap`
+     */
+    attribute: string;
+    event: string;
+    catch: boolean;
+    capture: boolean;
+    handler: string;
+
+    /**
+     * 属性值中处理函数名的位置
+     */
+    loc: Location;
+
+    /**
+     * js 中的事件处理函数，找不到时为 null
+     */
+    method: MethodMeta | null;
+}
+
+interface VariableMeta {
+    name: string;
+    kind: 'Data' | 'Property' | 'Computed' | 'Module' | 'Item' | 'Index';
+
+    /**
+     * 声明的位置，data、properties、computed 在 js 中，其他在模板中，
+     * 省略的 item、index 为 s-for 属性名的位置
+     */
+    loc: Location;
+    comment: string | null;
+
+    /**
+     * 已知的字段，item 为列表中对象元素的字段
+     */
+    fields: DataMeta[] | null;
+
+    /**
+     * 值为数组时，数组中对象元素的字段
+     */
+    items: DataMeta[] | null;
+}
+
+interface TemplateBindings {
+    bindings: BindingMeta[];
+
+    /**
+     * 无法解析的表达式
+     */
+    diagnostics: Diagnostic[];
+}
+
+interface UnitFiles {
+    script: string | null;
+    template: string | null;
+    style: string | null;
+    config: string | null;
+}
+
+type ComponentTarget =
+    | {type: 'Local'; path: string; files: UnitFiles}
+    | {type: 'Plugin'; provider: string; name: string}
+    | {type: 'DynamicLib'; provider: string; name: string};
+
+interface ResolvedComponent {
+    name: string;
+
+    /**
+     * 配置中的原始路径
+     */
+    path: string;
+
+    /**
+     * 配置中 key 的位置
+     */
+    loc: Location;
+
+    /**
+     * 无法解析时为 null
+     */
+    target: ComponentTarget | null;
+}
+
+interface ResolvedComponents {
+    components: Record<string, ResolvedComponent>;
+
+    /**
+     * 无法解析的组件，位置为配置中的 key
+     */
+    diagnostics: Diagnostic[];
+}
+
+type GraphNodeKind = 'App' | 'Page' | 'Component' | 'Script' | 'Style' | 'Template';
+
+interface GraphEdge {
+    from: string;
+    to: string;
+    kind:
+        | 'Page'
+        | 'Component'
+        | 'Behavior'
+        | 'CssImport'
+        | 'Require'
+        | 'TemplateImport'
+        | 'TemplateInclude'
+        | 'Module';
+}
+
+interface DependencyGraph {
+
+    /**
+     * 节点 id 为相对项目根目录的路径，页面和组件不带扩展名，其他文件带扩展名，app.json 为 `app`
+     */
+    nodes: Record<string, GraphNodeKind>;
+    edges: GraphEdge[];
+
+    /**
+     * 循环依赖，包含引用自身的节点
+     */
+    cycles: string[][];
+
+    /**
+     * 从 app.json 出发无法到达的页面、组件和文件
+     */
+    unreachable: string[];
+
+    /**
+     * 原生的依赖图，不可枚举，传给 `graphToDot`、`findDependents`、`findDependencies` 时不再重新构建
+     */
+    readonly handle: unknown;
+}
+
+interface ProjectUnit {
+
+    /**
+     * 相对项目根目录、不带扩展名的路径，例如 `pages/index/index`
+     */
+    path: string;
+    kind: 'Page' | 'Component';
+
+    /**
+     * 所属分包的根目录，主包中为 null
+     */
+    package: string | null;
+
+    /**
+     * 同名的 js/ts、swan、css、json 文件，不存在的文件为 null
+     */
+    files: UnitFiles;
+
+    config: FileResult<ConfigMeta> | null;
+
+    /**
+     * 配置中 usingComponents 的解析结果
+     */
+    components: Record<string, ResolvedComponent>;
+
+    /**
+     * 无法解析的组件，位置为配置中的 key
+     */
+    diagnostics: Diagnostic[];
+    script: FileResult<ComponentMeta> | null;
+    template: FileResult<TemplateMeta> | null;
+    style: FileResult<CssMeta> | null;
+
+    /**
+     * 模板中使用的类名及可见样式文件中的定义，页面可以使用 app.css 中的类
+     */
+    classes: TemplateClassMeta[];
+}
+
+interface ProjectMeta {
+    root: string;
+    app: {
+        pages: string[];
+        subPackages: {root: string; pages: string[]}[];
+
+        /**
+         * tabBar.list 中的页面路径
+         */
+        tabBar: string[];
+        usingComponents: Record<string, string>;
+    };
+
+    /**
+     * 按路径索引的页面和组件
+     */
+    units: Record<string, ProjectUnit>;
+}
+
+interface CacheIndexStats {
+
+    /**
+     * 内容未变化，直接载入的文件数
+     */
+    fresh: number;
+
+    /**
+     * 内容已变化，重新解析的文件数
+     */
+    rebuilt: number;
+
+    /**
+     * 已删除或无法解析，被丢弃的文件数
+     */
+    removed: number;
+}
+
+interface AsyncParseOptions {
+
+    /**
+     * 取消解析，取消后 Promise 以 AbortError 拒绝
+     */
+    signal?: AbortSignal;
+
+    /**
+     * 每解析完一个文件时回调
+     * @param file 文件路径
+     * @param done 已完成的文件数
+     * @param total 当前已知的文件总数，css 文件的 `@import` 会陆续计入
+     */
+    onProgress?: (file: string, done: number, total: number) => void;
+}
+
 /**
  * 解析单个 js 文件
  * @param file 文件路径
@@ -95,7 +717,7 @@ export function parseScript(file: string): ComponentMeta;
  * 解析一组 js 文件
  * @param files 文件路径数组
  */
-export function parseScriptFiles(files: string[]): Record<string, ComponentMeta>;
+export function parseScriptFiles(files: string[]): BatchResult<ComponentMeta>;
 
 /**
  * 解析单个 css 文件
@@ -107,4 +729,227 @@ export function parseCss(file: string): CssMeta;
  * 解析一组 css 文件，注意返回的 map 中包含所有被 import 的 css 文件
  * @param files 文件路径数组
  */
-export function parseCssFiles(files: string[]): Record<string, CssMeta>;
+export function parseCssFiles(files: string[]): BatchResult<CssMeta>;
+
+/**
+ * 解析页面或组件的 json 配置
+ * @param file 文件路径
+ */
+export function parseConfig(file: string): ConfigMeta;
+
+/**
+ * 解析 swan 模板，得到带位置的元素、属性、文本、注释和插值
+ * @param file 文件路径
+ */
+export function parseTemplate(file: string): TemplateMeta;
+
+/**
+ * 解析 filter 或 sjs 模块，得到导出的函数及其参数和注释
+ * @param file 文件路径
+ */
+export function parseFilter(file: string): FilterModuleMeta;
+
+/**
+ * 解析 swan 模板中 `{{ }}`、`s-if`、`s-elif`、`s-show`、`s-for` 的表达式，得到其中引用的变量和成员访问路径
+ * @param file 文件路径
+ */
+export function parseTemplateBindings(file: string): TemplateBindings;
+
+/**
+ * 模板分析的类型及参数，`kind` 为分析类型
+ */
+type Analysis =
+    /** 检查没有在 data、properties、computed 中声明的变量和不存在的事件处理函数，s-for 的 item、index 和 filter、import-sjs 模块视为已声明 */
+    | {kind: 'checkTemplate'}
+    /** 某个位置可以使用的变量，内层 s-for 的 item、index 在前，被遮蔽的外层变量不在结果中，行号从 1 开始，列号从 0 开始 */
+    | {kind: 'templateVariables'; position: Pos}
+    /** 事件绑定及 js 中对应的事件处理函数，处理函数由插值指定的事件绑定不在结果中 */
+    | {kind: 'eventBindings'}
+    /**
+     * class、hover-class、s-bind:class 使用的类名及样式文件中的定义，包括插值中条件表达式、数组和对象 key 中的类名。
+     * 样式文件为同名的 css 文件，页面还包括 `app.css`，`@import` 的文件自动加入
+     */
+    | {kind: 'templateClasses'}
+    /** 检查没有在样式文件中定义的类名，样式文件与 `templateClasses` 相同 */
+    | {kind: 'checkClasses'}
+    /** 检查自定义组件和内置组件的属性：不存在的属性、与类型不符或不在可选值中的字面量以及组件不会触发的事件 */
+    | {kind: 'checkComponents'}
+    /** usingComponents 中的组件名或内置组件名 `tag` 可以使用的属性，用于属性补全 */
+    | {kind: 'componentAttributes'; tag: string}
+    /** 检查需要比 `version`（例如 `3.0.0`）更高基础库的内置组件、属性和事件 */
+    | {kind: 'checkVersions'; version: string}
+    /** `<import>`、`<include>` 及其相对项目根目录的路径 */
+    | {kind: 'templateImports'}
+    /** 模板中定义的命名模板及其读取的变量 */
+    | {kind: 'templateDefinitions'}
+    /** `<template is>` 使用的命名模板及其定义，命名模板来自模板自身和 `<import>` 的文件，用于跳转到定义 */
+    | {kind: 'templateUsages'}
+    /** 检查不存在的 `<import>`、`<include>` 文件和命名模板，以及 data 中没有传入命名模板读取的变量 */
+    | {kind: 'checkTemplateImports'}
+    /** `<filter>`、`<import-sjs>` 声明的模块及其导出的函数 */
+    | {kind: 'templateModules'}
+    /** 检查不存在的模块文件、模块没有导出的函数，以及实参多于函数参数的调用 */
+    | {kind: 'checkModuleCalls'}
+    /** 某个位置 `module.` 后可以补全的函数，行号从 1 开始，列号从 0 开始 */
+    | {kind: 'moduleCompletions'; position: Pos};
+
+/**
+ * 各类型分析的结果
+ */
+interface AnalysisResults {
+    checkTemplate: Diagnostic[];
+    templateVariables: VariableMeta[];
+    eventBindings: EventBindingMeta[];
+    templateClasses: TemplateClassMeta[];
+    checkClasses: Diagnostic[];
+    checkComponents: Diagnostic[];
+    componentAttributes: AttributeCompletion[];
+    checkVersions: Diagnostic[];
+    templateImports: TemplateImportMeta[];
+    templateDefinitions: TemplateDefinitionMeta[];
+    templateUsages: TemplateUsageMeta[];
+    checkTemplateImports: Diagnostic[];
+    templateModules: TemplateModuleMeta[];
+    checkModuleCalls: Diagnostic[];
+    moduleCompletions: FilterFunctionMeta[];
+}
+
+/**
+ * 分析页面或组件的模板，只读取该分析需要的同名 js、css 文件和 usingComponents 中的组件
+ * @param root 项目根目录
+ * @param unitPath 页面或组件相对项目根目录、不带扩展名的路径，例如 `pages/index/index`
+ * @param analysis 分析类型及参数，例如 `{kind: 'templateVariables', position: {line: 2, column: 8}}`
+ */
+export function analyze<A extends Analysis>(
+    root: string,
+    unitPath: string,
+    analysis: A
+): AnalysisResults[A['kind']];
+
+/**
+ * 内置组件目录，包含组件的属性、类型、默认值、可选值、事件和最低基础库版本
+ */
+export function builtinComponents(): Catalog;
+
+/**
+ * 解析页面或组件配置中的 usingComponents，支持相对路径、以 `/` 开头的路径、
+ * miniprogram_npm 和 node_modules（package.json 的 miniprogram 目录）中的组件，
+ * 以及 `plugin://`、`dynamicLib://` 外部组件
+ * @param root 项目根目录
+ * @param unitPath 相对项目根目录、不带扩展名的路径，例如 `pages/index/index`
+ */
+export function resolveComponents(root: string, unitPath: string): ResolvedComponents;
+
+/**
+ * 构建项目依赖图，包含 usingComponents、behaviors、css @import 和 js require 关系，
+ * 以及项目目录中未被使用的页面和组件
+ * @param root 项目根目录
+ */
+export function buildGraph(root: string): DependencyGraph;
+
+/**
+ * 在后台线程中构建项目依赖图
+ * @param root 项目根目录
+ * @param options 取消选项，不支持进度回调
+ */
+export function buildGraphAsync(root: string, options?: AsyncParseOptions): Promise<DependencyGraph>;
+
+/**
+ * 输出项目依赖图的 Graphviz DOT 格式
+ * @param graph `buildGraph` 的结果，传入项目根目录时重新构建依赖图
+ */
+export function graphToDot(graph: DependencyGraph | string): string;
+
+/**
+ * 查找依赖指定节点的页面、组件和文件，用于评估修改的影响范围
+ * @param graph `buildGraph` 的结果，传入项目根目录时重新构建依赖图
+ * @param id 节点 id，例如 `components/card/card`
+ * @param transitive 是否包含间接依赖，默认 true
+ */
+export function findDependents(graph: DependencyGraph | string, id: string, transitive?: boolean): string[];
+
+/**
+ * 查找指定节点依赖的页面、组件和文件
+ * @param graph `buildGraph` 的结果，传入项目根目录时重新构建依赖图
+ * @param id 节点 id，例如 `pages/index/index`
+ * @param transitive 是否包含间接依赖，默认 true
+ */
+export function findDependencies(graph: DependencyGraph | string, id: string, transitive?: boolean): string[];
+
+/**
+ * 读取项目根目录的 app.json，查找并解析所有页面和组件
+ * @param root 项目根目录
+ */
+export function parseProject(root: string): ProjectMeta;
+
+/**
+ * 使解析缓存失效，不传参数时清空所有缓存
+ * @param files 文件路径数组
+ */
+export function invalidateCache(files?: string[]): void;
+
+/**
+ * 设置解析缓存上限，js、css、swan 和 json 缓存分别计算
+ * @param maxEntries 最多缓存的文件数，默认 5000
+ * @param maxBytes 最多缓存的源码字节数，默认 64MB
+ */
+export function setCacheLimits(maxEntries: number, maxBytes: number): void;
+
+/**
+ * 将 js、css、swan 和 json 的解析缓存写入缓存目录下的磁盘索引
+ * @param cacheDir 缓存目录，不存在时自动创建
+ */
+export function saveCache(cacheDir: string): void;
+
+/**
+ * 从缓存目录载入磁盘索引，按内容 hash 校验，只重新解析已变化的文件
+ * @param cacheDir 缓存目录
+ */
+export function loadCache(cacheDir: string): CacheIndexStats;
+
+/**
+ * 设置解析线程池的并发数，js 和 css 解析共用同一个线程池
+ * @param concurrency 并发数，传 0 时使用 CPU 核数减 1
+ */
+export function setConcurrency(concurrency: number): void;
+
+/**
+ * 在后台线程中解析单个 js 文件
+ * @param file 文件路径
+ * @param options 取消和进度选项
+ */
+export function parseScriptAsync(file: string, options?: AsyncParseOptions): Promise<ComponentMeta>;
+
+/**
+ * 在后台线程中解析一组 js 文件
+ * @param files 文件路径数组
+ * @param options 取消和进度选项
+ */
+export function parseScriptFilesAsync(
+    files: string[],
+    options?: AsyncParseOptions
+): Promise<BatchResult<ComponentMeta>>;
+
+/**
+ * 在后台线程中解析单个 css 文件
+ * @param file 文件路径
+ * @param options 取消和进度选项
+ */
+export function parseCssAsync(file: string, options?: AsyncParseOptions): Promise<CssMeta>;
+
+/**
+ * 在后台线程中解析一组 css 文件，注意返回的 map 中包含所有被 import 的 css 文件
+ * @param files 文件路径数组
+ * @param options 取消和进度选项
+ */
+export function parseCssFilesAsync(
+    files: string[],
+    options?: AsyncParseOptions
+): Promise<BatchResult<CssMeta>>;
+
+/**
+ * 在后台线程中解析项目中所有页面和组件
+ * @param root 项目根目录
+ * @param options 取消和进度选项，进度包括项目中的 js、css 和模板文件
+ */
+export function parseProjectAsync(root: string, options?: AsyncParseOptions): Promise<ProjectMeta>;
@@ -14,9 +14,53 @@ const parser = (() => {
         return require('./parser-aarch64-darwin.node');
     }
 
+    // 其他平台使用 wasm 版本，没有构建 wasm 时使用 mock
+    try {
+        return require('./parser-wasm.js');
+    }
+    catch (e) {
+        if (e.code !== 'MODULE_NOT_FOUND') {
+            throw e;
+        }
+    }
     return require('./parser-mock.js');
 })();
 
+const abortError = () => {
+    const error = new Error('parse cancelled');
+    error.name = 'AbortError';
+    return error;
+};
+
+/**
+ * 调用异步解析函数，将 AbortSignal 转换为原生的取消标记，`convert` 将结果转换为 js 对象
+ */
+const runAsync = (method, input, options = {}, convert = JSON.parse) => {
+    const {signal, onProgress} = options;
+    if (signal && signal.aborted) {
+        return Promise.reject(abortError());
+    }
+
+    const token = signal ? parser.createCancelToken() : undefined;
+    const onAbort = () => parser.cancel(token);
+    if (signal) {
+        signal.addEventListener('abort', onAbort, {once: true});
+    }
+
+    return parser[method](input, token, onProgress).then(
+        result => convert(result),
+        error => {
+            throw signal && signal.aborted ? abortError() : error;
+        }
+    ).finally(() => {
+        if (signal) {
+            signal.removeEventListener('abort', onAbort);
+        }
+    });
+};
+
+// 结果以 JSON 字符串传回再由 JSON.parse 还原，实测比在 Rust 侧通过 N-API 逐个属性构造 js 对象快，
+// 见 test/benchmark.js 中的 transfer 耗时
 exports.parseScript = filePath => {
     const result = parser.parseScript(filePath);
     return JSON.parse(result);
@@ -42,3 +86,121 @@ exports.parseCssFiles = filePaths => {
     const result = parser.parseCssFiles(filePaths);
     return JSON.parse(result);
 };
+
+exports.parseConfig = filePath => {
+    const result = parser.parseConfig(filePath);
+    return JSON.parse(result);
+};
+
+exports.parseTemplate = filePath => {
+    const result = parser.parseTemplate(filePath);
+    return JSON.parse(result);
+};
+
+exports.parseFilter = filePath => {
+    const result = parser.parseFilter(filePath);
+    return JSON.parse(result);
+};
+
+exports.parseTemplateBindings = filePath => {
+    const result = parser.parseTemplateBindings(filePath);
+    return JSON.parse(result);
+};
+
+/**
+ * 分析页面或组件的模板，`analysis.kind` 为分析类型，其余字段为该分析的参数，见 index.d.ts 中的 `Analysis`
+ */
+exports.analyze = (root, unitPath, analysis) => {
+    const result = parser.analyze(root, unitPath, JSON.stringify(analysis));
+    return JSON.parse(result);
+};
+
+exports.builtinComponents = () => {
+    const result = parser.builtinComponents();
+    return JSON.parse(result);
+};
+
+exports.resolveComponents = (root, unitPath) => {
+    const result = parser.resolveComponents(root, unitPath);
+    return JSON.parse(result);
+};
+
+/**
+ * 依赖图的结果带有不可枚举的 `handle`，即原生的依赖图，传给 `graphToDot` 等查询时不再重新构建
+ */
+const toGraph = handle => {
+    const graph = JSON.parse(parser.graphReport(handle));
+    return Object.defineProperty(graph, 'handle', {value: handle});
+};
+
+// 查询接口可以传入 `buildGraph` 的结果，也可以传入项目根目录
+const graphHandle = graph => {
+    return typeof graph === 'string' ? parser.buildGraph(graph) : graph.handle;
+};
+
+exports.buildGraph = root => toGraph(parser.buildGraph(root));
+
+exports.buildGraphAsync = (root, options) => runAsync('buildGraphAsync', root, options, toGraph);
+
+exports.graphToDot = graph => {
+    return parser.graphToDot(graphHandle(graph));
+};
+
+exports.findDependents = (graph, id, transitive = true) => {
+    const result = parser.findDependents(graphHandle(graph), id, transitive);
+    return JSON.parse(result);
+};
+
+exports.findDependencies = (graph, id, transitive = true) => {
+    const result = parser.findDependencies(graphHandle(graph), id, transitive);
+    return JSON.parse(result);
+};
+
+exports.parseProject = root => {
+    const result = parser.parseProject(root);
+    return JSON.parse(result);
+};
+
+exports.invalidateCache = filePaths => {
+    if (filePaths !== undefined && !Array.isArray(filePaths)) {
+        throw new Error('file paths should be array!');
+    }
+    parser.invalidateCache(filePaths);
+};
+
+exports.setCacheLimits = (maxEntries, maxBytes) => {
+    parser.setCacheLimits(maxEntries, maxBytes);
+};
+
+exports.saveCache = cacheDir => {
+    parser.saveCache(cacheDir);
+};
+
+exports.loadCache = cacheDir => {
+    const result = parser.loadCache(cacheDir);
+    return JSON.parse(result);
+};
+
+exports.setConcurrency = concurrency => {
+    parser.setConcurrency(concurrency);
+};
+
+exports.parseScriptAsync = (filePath, options) => runAsync('parseScriptAsync', filePath, options);
+
+exports.parseScriptFilesAsync = (filePaths, options) => {
+    if (!Array.isArray(filePaths)) {
+        return Promise.reject(new Error('file paths should be array!'));
+    }
+    return runAsync('parseScriptFilesAsync', filePaths, options);
+};
+
+exports.parseCssAsync = (filePath, options) => runAsync('parseCssAsync', filePath, options);
+
+exports.parseCssFilesAsync = (filePaths, options) => {
+    if (!Array.isArray(filePaths)) {
+        return Promise.reject(new Error('file paths should be array!'));
+    }
+    return runAsync('parseCssFilesAsync', filePaths, options);
+};
+
+exports.parseProjectAsync = (root, options) => runAsync('parseProjectAsync', root, options);
@@ -2,18 +2,117 @@
  * @file mock parser
  * @author mengke01(kekee000@gmail.com)
  */
+const emptyProject = '{"root":"","app":{"pages":[],"subPackages":[],"tabBar":[],"usingComponents":{}},'
+    + '"units":{}}';
+const emptyBatchResult = '{"files":{},"summary":{"total":0,"succeeded":0,"failed":0,"elapsed":0}}';
+
 exports.parseScript = filePath => {
-    return {};
+    return '{}';
 };
 
 exports.parseScriptFiles = filePaths => {
-    return {};
+    return emptyBatchResult;
 };
 
 exports.parseCss = filePath => {
-    return {};
+    return '{}';
 };
 
 exports.parseCssFiles = filePaths => {
+    return emptyBatchResult;
+};
+
+exports.parseConfig = filePath => {
+    return '{"component":false,"usingComponents":{},"componentGenerics":{},'
+        + '"navigationBarTitleText":null,"keys":[],"diagnostics":[]}';
+};
+
+exports.parseTemplate = filePath => {
+    return '{"nodes":[],"diagnostics":[]}';
+};
+
+exports.parseFilter = filePath => {
+    return '{"functions":[]}';
+};
+
+exports.parseTemplateBindings = filePath => {
+    return '{"bindings":[],"diagnostics":[]}';
+};
+
+exports.analyze = (root, unitPath, analysis) => {
+    return '[]';
+};
+
+exports.builtinComponents = () => {
+    return '{"version":"1.1.0","common":[],"components":[]}';
+};
+
+exports.resolveComponents = (root, unitPath) => {
+    return '{"components":{},"diagnostics":[]}';
+};
+
+exports.buildGraph = root => {
+    return {};
+};
+
+exports.buildGraphAsync = root => {
+    return Promise.resolve({});
+};
+
+exports.graphReport = graph => {
+    return '{"nodes":{"app":"App"},"edges":[],"cycles":[],"unreachable":[]}';
+};
+
+exports.graphToDot = graph => {
+    return 'digraph dependencies {\n}\n';
+};
+
+exports.findDependents = (graph, id, transitive) => {
+    return '[]';
+};
+
+exports.findDependencies = (graph, id, transitive) => {
+    return '[]';
+};
+
+exports.parseProject = root => {
+    return emptyProject;
+};
+
+exports.invalidateCache = filePaths => {};
+
+exports.setCacheLimits = (maxEntries, maxBytes) => {};
+
+exports.saveCache = cacheDir => {};
+
+exports.loadCache = cacheDir => {
+    return '{"fresh":0,"rebuilt":0,"removed":0}';
+};
+
+exports.setConcurrency = concurrency => {};
+
+exports.parseScriptAsync = filePath => {
+    return Promise.resolve('{}');
+};
+
+exports.parseScriptFilesAsync = filePaths => {
+    return Promise.resolve(emptyBatchResult);
+};
+
+exports.parseCssAsync = filePath => {
+    return Promise.resolve('{}');
+};
+
+exports.parseCssFilesAsync = filePaths => {
+    return Promise.resolve(emptyBatchResult);
+};
+
+exports.parseProjectAsync = root => {
+    return Promise.resolve(emptyProject);
+};
+
+exports.createCancelToken = () => {
     return {};
 };
+
+exports.cancel = token => {};
@@ -0,0 +1,208 @@
+/**
+ * @file wasm parser，没有对应平台的原生模块时使用
+ * @author mengke01(kekee000@gmail.com)
+ *
+ * WebAssembly 版本只解析源码，这里读取文件后调用，需要先执行 `npm run build-wasm-node`。
+ * 批量解析逐个调用 wasm 解析单个文件，需要读取整个项目的接口在 wasm 中没有实现，调用时抛出错误
+ */
+const fs = require('fs');
+const path = require('path');
+const {performance} = require('perf_hooks');
+const wasm = require('./wasm/node/parser.js');
+
+const read = filePath => fs.readFileSync(filePath, 'utf8');
+
+const parseFile = method => filePath => wasm[method](filePath, read(filePath));
+
+// 读取存在的文件，传给 wasm 的 `analyze`
+const sourceFile = filePath => {
+    return fs.existsSync(filePath) ? {path: filePath, source: read(filePath)} : undefined;
+};
+
+const notSupported = name => () => {
+    throw new Error(`${name} is not supported in wasm`);
+};
+
+const cancelledError = () => new Error('parse cancelled');
+
+// 和原生模块批量解析结果中的 `error` 一样带有 `kind`、`message` 和 `loc`
+const toParseError = (filePath, error) => {
+    if (error.code === 'ENOENT') {
+        return {kind: 'NotFound', message: `No such file ${filePath}`, loc: null};
+    }
+    return {kind: error.kind || 'InternalError', message: error.message, loc: error.loc || null};
+};
+
+const parseFileResult = (method, filePath) => {
+    const start = performance.now();
+    try {
+        const meta = JSON.parse(parseFile(method)(filePath));
+        return {status: 'ok', meta, elapsed: performance.now() - start};
+    }
+    catch (e) {
+        return {status: 'error', error: toParseError(filePath, e), elapsed: performance.now() - start};
+    }
+};
+
+// 逐个解析文件并写入 `files`，每解析完一个文件产出 `[filePath, done, total]`，
+// css 和原生模块一样继续解析 `@import` 的文件
+function* parseBatch(method, filePaths, files) {
+    const queue = Array.from(new Set(filePaths));
+    for (let i = 0; i < queue.length; i++) {
+        const filePath = queue[i];
+        const result = parseFileResult(method, filePath);
+        files[filePath] = result;
+        if (method === 'parseCss' && result.status === 'ok') {
+            for (const importPath of result.meta.imports || []) {
+                if (!queue.includes(importPath) && fs.existsSync(importPath)) {
+                    queue.push(importPath);
+                }
+            }
+        }
+        yield [filePath, i + 1, queue.length];
+    }
+}
+
+const batchResult = (files, start) => {
+    const results = Object.values(files);
+    const failed = results.filter(result => result.status === 'error').length;
+    const summary = {
+        total: results.length,
+        succeeded: results.length - failed,
+        failed,
+        elapsed: performance.now() - start
+    };
+    return JSON.stringify({files, summary});
+};
+
+const parseFiles = method => filePaths => {
+    const start = performance.now();
+    const files = {};
+    // 同步解析没有进度通知，依次解析完所有文件
+    Array.from(parseBatch(method, filePaths, files));
+    return batchResult(files, start);
+};
+
+// 每个文件在单独的事件循环中解析，期间可以取消
+const parseFilesAsync = method => (filePaths, token, onProgress) => {
+    return new Promise((resolve, reject) => {
+        const start = performance.now();
+        const files = {};
+        const batch = parseBatch(method, filePaths, files);
+        const step = () => {
+            if (token && token.cancelled) {
+                reject(cancelledError());
+                return;
+            }
+            const {done, value} = batch.next();
+            if (done) {
+                resolve(batchResult(files, start));
+                return;
+            }
+            if (onProgress) {
+                onProgress(...value);
+            }
+            setImmediate(step);
+        };
+        setImmediate(step);
+    });
+};
+
+const parseFileAsync = method => (filePath, token) => {
+    return new Promise((resolve, reject) => {
+        setImmediate(() => {
+            if (token && token.cancelled) {
+                reject(cancelledError());
+                return;
+            }
+            try {
+                resolve(parseFile(method)(filePath));
+            }
+            catch (e) {
+                reject(e);
+            }
+        });
+    });
+};
+
+exports.parseScript = parseFile('parseScript');
+
+exports.parseScriptFiles = parseFiles('parseScript');
+
+exports.parseCss = parseFile('parseCss');
+
+exports.parseCssFiles = parseFiles('parseCss');
+
+exports.parseConfig = parseFile('parseConfig');
+
+exports.parseTemplate = parseFile('parseTemplate');
+
+exports.parseFilter = parseFile('parseFilter');
+
+exports.parseTemplateBindings = parseFile('parseTemplateBindings');
+
+// 读取页面或组件的模板及同名的 js 或 ts、css 文件，`unitPath` 为相对项目根目录、不带扩展名的路径，
+// 不读取 `<import>` 的模板、模块和 usingComponents 中的组件
+exports.analyze = (root, unitPath, analysis) => {
+    const base = path.join(root, unitPath);
+    const request = Object.assign(JSON.parse(analysis), {
+        template: {path: `${base}.swan`, source: read(`${base}.swan`)},
+        // 和原生模块一样，`.js` 和 `.ts` 同时存在时使用 `.js`
+        script: sourceFile(`${base}.js`) || sourceFile(`${base}.ts`),
+        style: sourceFile(`${base}.css`)
+    });
+    return wasm.analyze(JSON.stringify(request));
+};
+
+exports.builtinComponents = () => {
+    return wasm.builtinComponents();
+};
+
+exports.resolveComponents = notSupported('resolveComponents');
+
+exports.buildGraph = notSupported('buildGraph');
+
+exports.buildGraphAsync = root => {
+    return Promise.reject(new Error('buildGraphAsync is not supported in wasm'));
+};
+
+exports.graphReport = notSupported('graphReport');
+
+exports.graphToDot = notSupported('graphToDot');
+
+exports.findDependents = notSupported('findDependents');
+
+exports.findDependencies = notSupported('findDependencies');
+
+exports.parseProject = notSupported('parseProject');
+
+// wasm 中没有解析缓存，也不使用线程池
+exports.invalidateCache = filePaths => {};
+
+exports.setCacheLimits = (maxEntries, maxBytes) => {};
+
+exports.saveCache = notSupported('saveCache');
+
+exports.loadCache = notSupported('loadCache');
+
+exports.setConcurrency = concurrency => {};
+
+exports.parseScriptAsync = parseFileAsync('parseScript');
+
+exports.parseScriptFilesAsync = parseFilesAsync('parseScript');
+
+exports.parseCssAsync = parseFileAsync('parseCss');
+
+exports.parseCssFilesAsync = parseFilesAsync('parseCss');
+
+exports.parseProjectAsync = root => {
+    return Promise.reject(new Error('parseProjectAsync is not supported in wasm'));
+};
+
+exports.createCancelToken = () => {
+    return {cancelled: false};
+};
+
+exports.cancel = token => {
+    token.cancelled = true;
+};
@@ -0,0 +1,2396 @@
+//! 结合模板和 js、css 的解析结果检查模板，例如模板中使用了未声明的变量、不存在的事件处理函数、
+//! 没有定义的类名、自定义组件不支持的属性，以及查询模板中某个位置可以使用的变量
+
+use crate::batch::FileResult;
+use crate::catalog::{self, compare_versions, BuiltinComponent};
+use crate::parser::{
+    attribute_bindings, extract_classes, parse_expression, parse_for, AliasMeta, AttributeMeta,
+    ComponentMeta, ComponentType, CssMeta, DataMeta, Diagnostic, ElementMeta, ExpressionMeta,
+    Location, MethodMeta, ParseError, ParseErrorKind, Position, PropertyValue, TemplateBindings,
+    TemplateMeta, TemplateNode, ValuePart,
+};
+use crate::parser::{
+    extract_bindings, parse_calls, parse_inline_filter, parse_template_data, FilterFunctionMeta,
+    InterpolationMeta,
+};
+use crate::project::visible_styles;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// 模板表达式中不需要声明的全局变量
+const GLOBALS: &[&str] = &["undefined", "NaN", "Infinity"];
+
+/// 声明 sjs 模块的元素，`module` 属性为模块名
+const MODULE_ELEMENTS: &[&str] = &["filter", "import-sjs"];
+
+/// `s-for` 及自定义 item、index 名称的属性
+const FOR_ATTRIBUTES: &[&str] = &["s-for", "s-for-item", "s-for-index"];
+
+/// 页面的生命周期函数，与事件处理函数一样定义在 `Page` 的参数中
+const PAGE_LIFECYCLES: &[&str] = &[
+    "onInit",
+    "onLoad",
+    "onShow",
+    "onReady",
+    "onHide",
+    "onUnload",
+    "onForceReLogin",
+    "onPullDownRefresh",
+    "onReachBottom",
+    "onShareAppMessage",
+    "onPageScroll",
+    "onTabItemTap",
+    "onURLQueryChange",
+];
+
+/// 组件的生命周期函数，定义在 `Component` 的参数中，不在 `methods` 中
+const COMPONENT_LIFECYCLES: &[&str] = &["created", "attached", "ready", "detached"];
+
+/// 所有组件都支持的属性，`s-`、`data-`、`generic:` 开头的属性和事件绑定另外判断
+const COMMON_ATTRIBUTES: &[&str] = &["id", "class", "style", "hidden", "slot"];
+
+/// 从组件内部冒泡上来的触摸事件，自定义组件上可以直接绑定
+const BUBBLING_EVENTS: &[&str] = &[
+    "tap",
+    "longtap",
+    "longpress",
+    "touchstart",
+    "touchmove",
+    "touchend",
+    "touchcancel",
+    "touchforcechange",
+];
+
+/// 内置 behavior `swan://form-field` 添加的属性
+const FORM_FIELD_PROPERTIES: &[&str] = &["name", "value"];
+
+/// 两个名称之间的编辑距离，按字符计算，相邻字符交换算作一次编辑
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] 为 a[..i] 和 b[..j] 之间的距离
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// 在候选名称中查找与 `name` 最接近的一个
+///
+/// 编辑距离超过名称长度的三分之一时不给出建议，单个字符的名称不给出建议
+pub(crate) fn suggest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let len = name.chars().count();
+    let limit = (len / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit && *distance < len)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum VariableKind {
+    Data,
+    Property,
+    Computed,
+    /// `<filter>` 或 `<import-sjs>` 声明的模块
+    Module,
+    /// `s-for` 的 item
+    Item,
+    /// `s-for` 的 index
+    Index,
+}
+
+impl VariableKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            VariableKind::Data => "data",
+            VariableKind::Property => "property",
+            VariableKind::Computed => "computed",
+            VariableKind::Module => "module",
+            VariableKind::Item => "s-for item",
+            VariableKind::Index => "s-for index",
+        }
+    }
+}
+
+/// 模板中可以使用的变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableMeta {
+    pub name: String,
+    pub kind: VariableKind,
+    /// 声明的位置，data、properties、computed 在 js 中，其他在模板中，
+    /// 省略的 item、index 为 `s-for` 属性名的位置
+    pub loc: Location,
+    pub comment: Option<String>,
+    /// 已知的字段，item 为列表中对象元素的字段
+    pub fields: Option<Vec<DataMeta>>,
+    /// 值为数组时，数组中对象元素的字段
+    pub items: Option<Vec<DataMeta>>,
+}
+
+/// 按访问路径查找到的位置
+struct Cursor<'a> {
+    fields: Option<&'a Vec<DataMeta>>,
+    items: Option<&'a Vec<DataMeta>>,
+    data: Option<&'a DataMeta>,
+}
+
+impl VariableMeta {
+    fn new(name: String, kind: VariableKind, loc: Location) -> Self {
+        VariableMeta {
+            name,
+            kind,
+            loc,
+            comment: None,
+            fields: None,
+            items: None,
+        }
+    }
+
+    /// 逐级查找访问路径，数字下标访问数组元素
+    fn walk(&self, path: &[String]) -> Option<Cursor<'_>> {
+        let mut cursor = Cursor {
+            fields: self.fields.as_ref(),
+            items: self.items.as_ref(),
+            data: None,
+        };
+        for segment in path {
+            cursor = if segment.parse::<usize>().is_ok() {
+                Cursor {
+                    fields: cursor.items,
+                    items: None,
+                    data: None,
+                }
+            } else {
+                let data = cursor.fields?.iter().find(|d| &d.name == segment)?;
+                Cursor {
+                    fields: data.children.as_ref(),
+                    items: data.items.as_ref(),
+                    data: Some(data),
+                }
+            };
+        }
+        Some(cursor)
+    }
+
+    /// 按成员访问路径查找字段，`item.user.name` 传入 `["user", "name"]`
+    pub fn resolve(&self, path: &[String]) -> Option<&DataMeta> {
+        self.walk(path)?.data
+    }
+
+    /// 访问路径的值为数组时，数组元素的字段
+    fn element_fields(&self, path: &[String]) -> Option<Vec<DataMeta>> {
+        self.walk(path)?.items.cloned()
+    }
+}
+
+/// 模板中的事件绑定，例如 `bindtap="onTap"`、`catch:touchmove="onMove"`、`capture-bind:tap="onTap"`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventBindingMeta {
+    /// 属性名，例如 `capture-bind:tap`
+    pub attribute: String,
+    /// 事件名，例如 `tap`
+    pub event: String,
+    /// `catch` 绑定阻止事件冒泡
+    pub catch: bool,
+    /// `capture-` 绑定在捕获阶段触发
+    pub capture: bool,
+    /// 事件处理函数名
+    pub handler: String,
+    /// 属性值中处理函数名的位置
+    pub loc: Location,
+    /// js 中的事件处理函数，找不到时为 `None`
+    pub method: Option<MethodMeta>,
+}
+
+/// 解析事件绑定的属性名，返回事件名、是否为 `catch` 和是否为 `capture-`
+fn parse_event_attribute(name: &str) -> Option<(&str, bool, bool)> {
+    let captures = regex!(r"^(capture-)?(bind|catch):?([A-Za-z_][\w-]*)$").captures(name)?;
+    Some((
+        captures.get(3)?.as_str(),
+        &captures[2] == "catch",
+        captures.get(1).is_some(),
+    ))
+}
+
+/// 元素上的事件绑定，处理函数由插值动态指定时无法确定，不在结果中
+fn element_events(element: &ElementMeta, component: &ComponentMeta) -> Vec<EventBindingMeta> {
+    element
+        .attributes
+        .iter()
+        .filter_map(|attribute| event_binding(attribute, component))
+        .collect()
+}
+
+fn event_binding(attribute: &AttributeMeta, component: &ComponentMeta) -> Option<EventBindingMeta> {
+    let (event, catch, capture) = parse_event_attribute(&attribute.name)?;
+    let (value, value_loc) = attribute.value.as_ref().zip(attribute.value_loc.as_ref())?;
+    let handler = value.trim();
+    if handler.is_empty() || handler.contains("{{") || value.contains('\n') {
+        return None;
+    }
+    let leading = value.chars().take_while(|c| c.is_whitespace()).count();
+    let column = value_loc.start.column + leading;
+    let loc = Location::from(
+        [value_loc.start.line, column],
+        [value_loc.start.line, column + handler.chars().count()],
+    );
+    Some(EventBindingMeta {
+        attribute: attribute.name.clone(),
+        event: event.to_string(),
+        catch,
+        capture,
+        handler: handler.to_string(),
+        loc,
+        method: component
+            .methods
+            .iter()
+            .find(|method| method.name == handler)
+            .cloned(),
+    })
+}
+
+/// 事件处理函数不存在，或者是生命周期函数时的警告
+fn check_event(binding: &EventBindingMeta, component: &ComponentMeta) -> Option<Diagnostic> {
+    let handler = binding.handler.as_str();
+    let lifecycles = match component.r#type {
+        ComponentType::Page => PAGE_LIFECYCLES,
+        ComponentType::Component => COMPONENT_LIFECYCLES,
+    };
+    let is_lifecycle = lifecycles.contains(&handler);
+    if binding.method.is_some() {
+        // 组件 methods 中的同名函数不是生命周期函数
+        return (is_lifecycle && matches!(component.r#type, ComponentType::Page)).then(|| {
+            Diagnostic::warning(
+                format!(
+                    "`{}` is a lifecycle hook and should not be used as an event handler",
+                    handler
+                ),
+                binding.loc.clone(),
+            )
+        });
+    }
+    if is_lifecycle {
+        return Some(Diagnostic::warning(
+            format!(
+                "`{}` is a lifecycle hook, event handlers must be defined in methods",
+                handler
+            ),
+            binding.loc.clone(),
+        ));
+    }
+    let candidates = component
+        .methods
+        .iter()
+        .map(|method| method.name.as_str())
+        .filter(|name| !lifecycles.contains(name));
+    let suggestion = suggest(handler, candidates).map(String::from);
+    let message = match &suggestion {
+        Some(suggestion) => format!(
+            "event handler `{}` is not defined in methods, did you mean `{}`?",
+            handler, suggestion
+        ),
+        None => format!("event handler `{}` is not defined in methods", handler),
+    };
+    Some(Diagnostic::warning(message, binding.loc.clone()).with_suggestion(suggestion))
+}
+
+/// 模板中所有的事件绑定及对应的 js 方法，用于从模板跳转到事件处理函数
+///
+/// `<template name>` 中的事件由使用模板的页面处理，不在结果中
+pub fn event_bindings(template: &TemplateMeta, component: &ComponentMeta) -> Vec<EventBindingMeta> {
+    let mut result = vec![];
+    collect_events(&template.nodes, component, &mut result);
+    result
+}
+
+fn collect_events(
+    nodes: &[TemplateNode],
+    component: &ComponentMeta,
+    result: &mut Vec<EventBindingMeta>,
+) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if !is_isolated(element) {
+                result.extend(element_events(element, component));
+                collect_events(&element.children, component, result);
+            }
+        }
+    }
+}
+
+/// 样式文件中的类定义
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClassDefinitionMeta {
+    pub file: String,
+    pub loc: Location,
+}
+
+/// 模板中使用的类名及其在样式文件中的定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateClassMeta {
+    pub name: String,
+    pub loc: Location,
+    /// 所在的属性，例如 `class`、`hover-class`
+    pub attribute: String,
+    /// 可见的样式文件中的定义，没有定义时为空
+    pub definitions: Vec<ClassDefinitionMeta>,
+}
+
+/// 模板中使用的类名，在 `styles` 中查找定义，`styles` 为模板可见的样式文件路径及解析结果，
+/// 包括同名的 css 文件及其 `@import` 的文件
+pub fn template_classes(
+    template: &TemplateMeta,
+    styles: &[(&str, &CssMeta)],
+) -> Vec<TemplateClassMeta> {
+    extract_classes(template)
+        .into_iter()
+        .map(|usage| {
+            let definitions = styles
+                .iter()
+                .flat_map(|(file, css)| {
+                    css.classes
+                        .iter()
+                        .filter(|class| class.name == usage.name)
+                        .map(move |class| ClassDefinitionMeta {
+                            file: file.to_string(),
+                            loc: class.loc.clone(),
+                        })
+                })
+                .collect();
+            TemplateClassMeta {
+                name: usage.name,
+                loc: usage.loc,
+                attribute: usage.attribute,
+                definitions,
+            }
+        })
+        .collect()
+}
+
+/// 检查没有在样式文件中定义的类名，没有可见的样式文件时不检查
+pub fn check_classes(
+    classes: &[TemplateClassMeta],
+    styles: &[(&str, &CssMeta)],
+) -> Vec<Diagnostic> {
+    if styles.is_empty() {
+        return vec![];
+    }
+    let candidates: Vec<&str> = styles
+        .iter()
+        .flat_map(|(_, css)| css.classes.iter().map(|class| class.name.as_str()))
+        .collect();
+    classes
+        .iter()
+        .filter(|class| class.definitions.is_empty())
+        .map(|class| {
+            let suggestion = suggest(&class.name, candidates.iter().copied()).map(String::from);
+            let message = match &suggestion {
+                Some(suggestion) => format!(
+                    "class `{}` is not defined in any stylesheet, did you mean `{}`?",
+                    class.name, suggestion
+                ),
+                None => format!("class `{}` is not defined in any stylesheet", class.name),
+            };
+            Diagnostic::warning(message, class.loc.clone()).with_suggestion(suggestion)
+        })
+        .collect()
+}
+
+/// 属性名的驼峰形式转为模板中的连字符形式，`maxCount` 转为 `max-count`
+fn kebab_case(name: &str) -> String {
+    let mut result = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            result.push('-');
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn is_common_attribute(name: &str) -> bool {
+    COMMON_ATTRIBUTES.contains(&name)
+        || ["s-", "data-", "generic:"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AttributeKind {
+    Property,
+    /// `externalClasses` 中声明的外部样式类
+    ExternalClass,
+    /// 组件触发的事件，名称为 `bind:` 加事件名
+    Event,
+}
+
+/// 组件上可以使用的属性，用于属性补全
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeCompletion {
+    /// 模板中的属性名，驼峰形式的属性转为连字符形式
+    pub name: String,
+    pub kind: AttributeKind,
+    /// properties 中声明的类型，例如 `String`
+    pub r#type: Option<String>,
+    /// 默认值
+    pub value: Option<PropertyValue>,
+    /// 自定义组件为 js 中的注释，内置组件为组件目录中的说明
+    pub comment: Option<String>,
+    /// 只能取这些值，只有内置组件有
+    pub values: Option<Vec<String>>,
+    /// 最低基础库版本，只有内置组件有
+    pub since: Option<String>,
+}
+
+impl AttributeCompletion {
+    fn new(name: String, kind: AttributeKind) -> Self {
+        AttributeCompletion {
+            name,
+            kind,
+            r#type: None,
+            value: None,
+            comment: None,
+            values: None,
+            since: None,
+        }
+    }
+}
+
+/// 组件可以使用的属性和触发的事件，自定义组件来自 js，内置组件来自组件目录
+struct ComponentSchema {
+    attributes: Vec<AttributeCompletion>,
+    /// 没有 `None` 时不检查事件
+    events: Option<Vec<String>>,
+    /// 属性和事件可能来自无法解析的 behavior，不检查不存在的属性和事件
+    open: bool,
+}
+
+impl ComponentSchema {
+    fn custom(component: &ComponentMeta) -> Self {
+        ComponentSchema {
+            attributes: component_attributes(component),
+            events: component
+                .events
+                .as_ref()
+                .map(|events| events.iter().map(|e| e.name.clone()).collect()),
+            open: component
+                .behaviors
+                .iter()
+                .any(|behavior| !behavior.starts_with("swan://")),
+        }
+    }
+
+    fn builtin(component: &BuiltinComponent) -> Self {
+        ComponentSchema {
+            attributes: builtin_attributes(component),
+            events: Some(component.events.iter().map(|e| e.name.clone()).collect()),
+            open: false,
+        }
+    }
+}
+
+/// 内置组件的属性和事件，包括目录中所有组件都可以使用的属性
+pub fn builtin_attributes(component: &BuiltinComponent) -> Vec<AttributeCompletion> {
+    let mut result: Vec<AttributeCompletion> = component
+        .attributes
+        .iter()
+        .chain(&catalog::catalog().common)
+        .map(|attribute| AttributeCompletion {
+            name: attribute.name.clone(),
+            kind: AttributeKind::Property,
+            r#type: Some(attribute.r#type.clone()),
+            value: attribute.default.clone(),
+            comment: Some(attribute.description.clone()),
+            values: attribute.values.clone(),
+            since: attribute.since.clone(),
+        })
+        .collect();
+    for event in &component.events {
+        let mut completion =
+            AttributeCompletion::new(format!("bind:{}", event.name), AttributeKind::Event);
+        completion.comment = Some(event.description.clone());
+        completion.since = event.since.clone();
+        result.push(completion);
+    }
+    result
+}
+
+/// 自定义组件的属性、外部样式类和触发的事件
+pub fn component_attributes(component: &ComponentMeta) -> Vec<AttributeCompletion> {
+    let mut result: Vec<AttributeCompletion> = component
+        .properties
+        .iter()
+        .map(|property| {
+            let mut completion =
+                AttributeCompletion::new(kebab_case(&property.name), AttributeKind::Property);
+            completion.r#type = Some(property.r#type.clone()).filter(|t| !t.is_empty());
+            completion.value = property.value.clone();
+            completion.comment = property.comment.clone();
+            completion
+        })
+        .collect();
+    if component.behaviors.iter().any(|b| b == "swan://form-field") {
+        for name in FORM_FIELD_PROPERTIES {
+            if !result.iter().any(|attribute| attribute.name == *name) {
+                result.push(AttributeCompletion::new(
+                    name.to_string(),
+                    AttributeKind::Property,
+                ));
+            }
+        }
+    }
+    for class in &component.external_classes {
+        result.push(AttributeCompletion::new(
+            class.clone(),
+            AttributeKind::ExternalClass,
+        ));
+    }
+    for event in component.events.iter().flatten() {
+        let mut completion =
+            AttributeCompletion::new(format!("bind:{}", event.name), AttributeKind::Event);
+        completion.comment = event.comment.clone();
+        result.push(completion);
+    }
+    result
+}
+
+/// 字面量不是属性声明的类型时，返回声明的类型，`Boolean` 接受 `true`、`false` 和空值
+fn literal_mismatch<'a>(r#type: &'a str, value: &str) -> Option<&'a str> {
+    let mismatch = match r#type {
+        "Number" => value.trim().parse::<f64>().is_err(),
+        "Boolean" => !matches!(value.trim(), "true" | "false" | ""),
+        "Object" | "Array" => true,
+        _ => false,
+    };
+    mismatch.then_some(r#type)
+}
+
+/// 属性的字面量与类型不符或不在可选值中时的警告
+fn check_literal(
+    tag: &str,
+    completion: &AttributeCompletion,
+    value: &str,
+    loc: &Location,
+) -> Option<Diagnostic> {
+    let name = &completion.name;
+    if let Some(r#type) = completion
+        .r#type
+        .as_deref()
+        .and_then(|r#type| literal_mismatch(r#type, value))
+    {
+        return Some(Diagnostic::warning(
+            format!(
+                "property `{}` of component `{}` expects {}, got string `{}`",
+                name, tag, r#type, value
+            ),
+            loc.clone(),
+        ));
+    }
+    let values = completion.values.as_ref()?;
+    if values.iter().any(|v| v == value.trim()) {
+        return None;
+    }
+    let suggestion = suggest(value.trim(), values.iter().map(String::as_str)).map(String::from);
+    let message = match &suggestion {
+        Some(suggestion) => format!(
+            "`{}` is not a valid value of property `{}` of component `{}`, did you mean `{}`?",
+            value, name, tag, suggestion
+        ),
+        None => format!(
+            "`{}` is not a valid value of property `{}` of component `{}`",
+            value, name, tag
+        ),
+    };
+    Some(Diagnostic::warning(message, loc.clone()).with_suggestion(suggestion))
+}
+
+/// 检查模板中使用的内置组件、属性和事件是否需要比 `version` 更高的基础库
+pub fn check_versions(template: &TemplateMeta, version: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    check_version_nodes(&template.nodes, version, &mut diagnostics);
+    diagnostics
+}
+
+fn check_version_nodes(nodes: &[TemplateNode], version: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let newer = |since: &'static Option<String>| {
+        since
+            .as_deref()
+            .filter(|since| compare_versions(since, version) == Ordering::Greater)
+    };
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if let Some(component) = catalog::builtin(&element.name) {
+                if let Some(since) = newer(&component.since) {
+                    diagnostics.push(Diagnostic::warning(
+                        format!(
+                            "component `{}` requires base library {} or later",
+                            element.name, since
+                        ),
+                        element.name_loc.clone(),
+                    ));
+                }
+                for attribute in &element.attributes {
+                    let since = match parse_event_attribute(&attribute.name) {
+                        Some((event, _, _)) => component
+                            .events
+                            .iter()
+                            .find(|e| e.name == event)
+                            .and_then(|e| newer(&e.since)),
+                        None => component
+                            .attributes
+                            .iter()
+                            .find(|a| a.name == attribute.name)
+                            .and_then(|a| newer(&a.since)),
+                    };
+                    if let Some(since) = since {
+                        diagnostics.push(Diagnostic::warning(
+                            format!(
+                                "`{}` of component `{}` requires base library {} or later",
+                                attribute.name, element.name, since
+                            ),
+                            attribute.name_loc.clone(),
+                        ));
+                    }
+                }
+            }
+            check_version_nodes(&element.children, version, diagnostics);
+        }
+    }
+}
+
+/// 检查模板中自定义组件和内置组件的属性，`components` 为 `usingComponents` 中的标签名及组件的 js 解析结果，
+/// 与内置组件同名时以 `usingComponents` 为准
+///
+/// 不存在的属性、与属性类型不符或不在可选值中的字面量，以及组件不会触发的事件给出警告。
+/// 组件引用了项目中的 behavior 时，属性可能来自 behavior，不检查不存在的属性
+pub fn check_components(
+    template: &TemplateMeta,
+    components: &BTreeMap<&str, &ComponentMeta>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    check_component_nodes(&template.nodes, components, &mut diagnostics);
+    diagnostics
+}
+
+fn check_component_nodes(
+    nodes: &[TemplateNode],
+    components: &BTreeMap<&str, &ComponentMeta>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if MODULE_ELEMENTS.contains(&element.name.as_str()) {
+                continue;
+            }
+            let schema = match components.get(element.name.as_str()) {
+                Some(component) => Some(ComponentSchema::custom(component)),
+                None => catalog::builtin(&element.name).map(ComponentSchema::builtin),
+            };
+            if let Some(schema) = schema {
+                check_component_element(element, &schema, diagnostics);
+            }
+            check_component_nodes(&element.children, components, diagnostics);
+        }
+    }
+}
+
+fn check_component_element(
+    element: &ElementMeta,
+    schema: &ComponentSchema,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let tag = element.name.as_str();
+    let attributes = &schema.attributes;
+    for attribute in &element.attributes {
+        let name = attribute.name.as_str();
+        if let Some((event, _, _)) = parse_event_attribute(name) {
+            let events = match &schema.events {
+                Some(events) if !schema.open => events,
+                _ => continue,
+            };
+            if BUBBLING_EVENTS.contains(&event) || events.iter().any(|e| e == event) {
+                continue;
+            }
+            let suggestion = suggest(event, events.iter().map(String::as_str)).map(String::from);
+            let message = match &suggestion {
+                Some(suggestion) => format!(
+                    "component `{}` never triggers event `{}`, did you mean `{}`?",
+                    tag, event, suggestion
+                ),
+                None => format!("component `{}` never triggers event `{}`", tag, event),
+            };
+            diagnostics.push(
+                Diagnostic::warning(message, attribute.name_loc.clone())
+                    .with_suggestion(suggestion),
+            );
+            continue;
+        }
+        if is_common_attribute(name) {
+            continue;
+        }
+
+        match attributes.iter().find(|a| a.name == name) {
+            Some(completion) => {
+                let is_literal = !attribute
+                    .parts
+                    .iter()
+                    .any(|part| matches!(part, ValuePart::Interpolation(_)));
+                let literal = attribute.value.as_ref().zip(attribute.value_loc.as_ref());
+                if let (true, Some((value, value_loc))) = (is_literal, literal) {
+                    if let Some(diagnostic) = check_literal(tag, completion, value, value_loc) {
+                        diagnostics.push(diagnostic);
+                    }
+                }
+            }
+            None if schema.open => (),
+            None => {
+                let candidates = attributes
+                    .iter()
+                    .filter(|a| a.kind != AttributeKind::Event)
+                    .map(|a| a.name.as_str());
+                let suggestion = suggest(name, candidates).map(String::from);
+                let message = match &suggestion {
+                    Some(suggestion) => format!(
+                        "component `{}` has no property `{}`, did you mean `{}`?",
+                        tag, name, suggestion
+                    ),
+                    None => format!("component `{}` has no property `{}`", tag, name),
+                };
+                diagnostics.push(
+                    Diagnostic::warning(message, attribute.name_loc.clone())
+                        .with_suggestion(suggestion),
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TemplateImportKind {
+    /// `<import>` 引入目标文件中的命名模板，不包括目标文件 import 的模板
+    Import,
+    /// `<include>` 引入目标文件中除命名模板以外的内容
+    Include,
+}
+
+/// 模板中的 `<import src>` 或 `<include src>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateImportMeta {
+    pub kind: TemplateImportKind,
+    /// 属性中的原始路径
+    pub src: String,
+    /// `src` 属性值的位置
+    pub loc: Location,
+    /// 相对项目根目录的路径，文件不存在时为 `None`
+    pub path: Option<String>,
+}
+
+/// `<template name>` 定义的命名模板
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateDefinitionMeta {
+    pub name: String,
+    /// 所在的模板文件
+    pub file: String,
+    /// `name` 属性值的位置
+    pub loc: Location,
+    /// 模板中读取的变量，即使用模板时需要在 data 中传入的 key，按首次出现的顺序排列
+    pub params: Vec<String>,
+}
+
+/// `<template is="x" data="{{...}}">` 对命名模板的使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateUsageMeta {
+    pub name: String,
+    /// `is` 属性值的位置
+    pub loc: Location,
+    /// data 中传入的 key，没有 data 时为空，有展开等无法确定的情况时为 `None`
+    pub data: Option<Vec<String>>,
+    /// `data` 属性值的位置
+    #[serde(rename = "dataLoc")]
+    pub data_loc: Option<Location>,
+    /// 使用的命名模板，用于跳转到定义，找不到时为 `None`
+    pub definition: Option<TemplateDefinitionMeta>,
+}
+
+/// 属性的值和位置，值中有插值时返回 `None`
+fn static_value(element: &ElementMeta, name: &str) -> Option<(String, Location)> {
+    let attribute = element.attribute(name)?;
+    let value = attribute.value.as_ref()?.trim();
+    if value.is_empty() || value.contains("{{") {
+        return None;
+    }
+    Some((value.to_string(), attribute.value_loc.clone()?))
+}
+
+/// 模板中的 `<import>` 和 `<include>`，`path` 由调用方解析
+pub fn template_imports(template: &TemplateMeta) -> Vec<TemplateImportMeta> {
+    let mut result = vec![];
+    collect_imports(&template.nodes, &mut result);
+    result
+}
+
+fn collect_imports(nodes: &[TemplateNode], result: &mut Vec<TemplateImportMeta>) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            let kind = match element.name.as_str() {
+                "import" => Some(TemplateImportKind::Import),
+                "include" => Some(TemplateImportKind::Include),
+                _ => None,
+            };
+            if let Some((kind, (src, loc))) = kind.zip(static_value(element, "src")) {
+                result.push(TemplateImportMeta {
+                    kind,
+                    src,
+                    loc,
+                    path: None,
+                });
+            }
+            collect_imports(&element.children, result);
+        }
+    }
+}
+
+/// 检查 `<import>`、`<include>` 的文件是否存在，`imports` 的 `path` 需要已经解析
+pub fn check_template_imports(imports: &[TemplateImportMeta]) -> Vec<Diagnostic> {
+    imports
+        .iter()
+        .filter(|import| import.path.is_none())
+        .map(|import| {
+            Diagnostic::error(
+                format!("cannot find template file `{}`", import.src),
+                import.loc.clone(),
+            )
+        })
+        .collect()
+}
+
+/// 模板文件 `file` 中定义的命名模板
+pub fn template_definitions(file: &str, template: &TemplateMeta) -> Vec<TemplateDefinitionMeta> {
+    let mut result = vec![];
+    collect_definitions(file, template, &template.nodes, &mut result);
+    result
+}
+
+fn collect_definitions(
+    file: &str,
+    template: &TemplateMeta,
+    nodes: &[TemplateNode],
+    result: &mut Vec<TemplateDefinitionMeta>,
+) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if element.name == "template" {
+                if let Some((name, loc)) = static_value(element, "name") {
+                    result.push(TemplateDefinitionMeta {
+                        name,
+                        file: file.to_string(),
+                        loc,
+                        params: template_params(template, element),
+                    });
+                }
+            }
+            collect_definitions(file, template, &element.children, result);
+        }
+    }
+}
+
+/// 命名模板中没有在模板内的 `s-for` 或模块中声明的变量
+fn template_params(template: &TemplateMeta, element: &ElementMeta) -> Vec<String> {
+    let component = ComponentMeta::new(ComponentType::Component);
+    let mut checker = TemplateChecker {
+        component: &component,
+        scope: Scope::new(template, &component),
+        diagnostics: vec![],
+        undeclared: vec![],
+    };
+    checker.nodes(&element.children);
+    let mut params: Vec<String> = vec![];
+    for name in checker.undeclared {
+        if !params.contains(&name) {
+            params.push(name);
+        }
+    }
+    params
+}
+
+/// 模板中使用的命名模板，`definitions` 为模板自身和 `<import>` 的文件中定义的模板，
+/// `is` 中有插值的动态模板不在结果中
+pub fn template_usages(
+    template: &TemplateMeta,
+    definitions: &[TemplateDefinitionMeta],
+) -> Vec<TemplateUsageMeta> {
+    let mut result = vec![];
+    collect_usages(&template.nodes, definitions, &mut result);
+    result
+}
+
+fn collect_usages(
+    nodes: &[TemplateNode],
+    definitions: &[TemplateDefinitionMeta],
+    result: &mut Vec<TemplateUsageMeta>,
+) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if element.name == "template" {
+                if let Some((name, loc)) = static_value(element, "is") {
+                    let (data, data_loc) = template_data(element);
+                    result.push(TemplateUsageMeta {
+                        definition: definitions.iter().find(|d| d.name == name).cloned(),
+                        name,
+                        loc,
+                        data,
+                        data_loc,
+                    });
+                }
+            }
+            collect_usages(&element.children, definitions, result);
+        }
+    }
+}
+
+/// `data` 属性中传入的 key，只有一个插值时才能确定
+fn template_data(element: &ElementMeta) -> (Option<Vec<String>>, Option<Location>) {
+    let attribute = match element.attribute("data") {
+        Some(attribute) => attribute,
+        None => return (Some(vec![]), None),
+    };
+    let data = match attribute.parts.as_slice() {
+        [ValuePart::Interpolation(interpolation)] => parse_template_data(
+            &interpolation.expression,
+            &interpolation.expression_loc.start,
+        )
+        .ok()
+        .flatten(),
+        _ => None,
+    };
+    (data, attribute.value_loc.clone())
+}
+
+/// 检查使用了不存在的命名模板，以及 data 中没有传入模板读取的变量
+pub fn check_template_usages(
+    usages: &[TemplateUsageMeta],
+    definitions: &[TemplateDefinitionMeta],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for usage in usages {
+        let definition = match &usage.definition {
+            Some(definition) => definition,
+            None => {
+                let suggestion = suggest(&usage.name, definitions.iter().map(|d| d.name.as_str()))
+                    .map(String::from);
+                let message = match &suggestion {
+                    Some(suggestion) => format!(
+                        "template `{}` is not defined or imported, did you mean `{}`?",
+                        usage.name, suggestion
+                    ),
+                    None => format!("template `{}` is not defined or imported", usage.name),
+                };
+                diagnostics.push(
+                    Diagnostic::warning(message, usage.loc.clone()).with_suggestion(suggestion),
+                );
+                continue;
+            }
+        };
+        let data = match &usage.data {
+            Some(data) => data,
+            None => continue,
+        };
+        for param in &definition.params {
+            if !data.contains(param) {
+                diagnostics.push(Diagnostic::warning(
+                    format!(
+                        "template `{}` reads `{}`, but it is not passed in data",
+                        usage.name, param
+                    ),
+                    usage.data_loc.as_ref().unwrap_or(&usage.loc).clone(),
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// 模板中 `<filter>` 或 `<import-sjs>` 声明的模块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateModuleMeta {
+    /// `module` 属性中的模块名
+    pub name: String,
+    /// `module` 属性值的位置
+    pub loc: Location,
+    /// `src` 属性中的原始路径，内联的模块为 `None`
+    pub src: Option<String>,
+    #[serde(rename = "srcLoc")]
+    pub src_loc: Option<Location>,
+    /// `src` 相对项目根目录的路径，由调用方解析，文件不存在时为 `None`
+    pub path: Option<String>,
+    /// 导出的函数，模块无法解析时为 `None`
+    pub functions: Option<Vec<FilterFunctionMeta>>,
+}
+
+impl TemplateModuleMeta {
+    fn function(&self, name: &str) -> Option<&FilterFunctionMeta> {
+        self.functions.as_ref()?.iter().find(|f| f.name == name)
+    }
+}
+
+/// 模板中声明的模块，内联的模块直接解析，`src` 引用的模块由调用方解析路径并填入 `functions`
+pub fn template_modules(file_path: &str, template: &TemplateMeta) -> Vec<TemplateModuleMeta> {
+    let mut result = vec![];
+    collect_template_modules(file_path, &template.nodes, &mut result);
+    result
+}
+
+fn collect_template_modules(
+    file_path: &str,
+    nodes: &[TemplateNode],
+    result: &mut Vec<TemplateModuleMeta>,
+) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if !MODULE_ELEMENTS.contains(&element.name.as_str()) {
+                collect_template_modules(file_path, &element.children, result);
+                continue;
+            }
+            let (name, loc) = match static_value(element, "module") {
+                Some(module) => module,
+                None => continue,
+            };
+            let (src, src_loc) = static_value(element, "src").unzip();
+            let functions = match (&src, element.children.as_slice()) {
+                (Some(_), _) => None,
+                (None, [TemplateNode::Text { value, loc }]) => {
+                    parse_inline_filter(file_path, value, &loc.start)
+                        .ok()
+                        .map(|meta| meta.functions)
+                }
+                (None, _) => Some(vec![]),
+            };
+            result.push(TemplateModuleMeta {
+                name,
+                loc,
+                src,
+                src_loc,
+                path: None,
+                functions,
+            });
+        }
+    }
+}
+
+/// 检查 `src` 引用的模块文件是否存在，`modules` 的 `path` 需要已经解析
+pub fn check_template_modules(modules: &[TemplateModuleMeta]) -> Vec<Diagnostic> {
+    modules
+        .iter()
+        .filter(|module| module.path.is_none())
+        .filter_map(|module| {
+            let (src, loc) = module.src.as_ref().zip(module.src_loc.as_ref())?;
+            Some(Diagnostic::error(
+                format!("cannot find module file `{}`", src),
+                loc.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// 检查模板中对模块函数的调用：模块没有导出的函数，以及实参多于函数的参数，
+/// 有剩余参数或函数体中使用了 `arguments` 的函数不检查参数个数
+///
+/// 无法解析的模块不检查，有展开的实参不检查个数
+pub fn check_module_calls(
+    template: &TemplateMeta,
+    modules: &[TemplateModuleMeta],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for binding in extract_bindings(template).bindings {
+        let expression = &binding.expression;
+        let calls = match parse_calls(&expression.source, &expression.loc.start) {
+            Ok(calls) => calls,
+            Err(_) => continue,
+        };
+        for call in calls {
+            let module = match modules.iter().find(|m| m.name == call.module) {
+                Some(module) => module,
+                None => continue,
+            };
+            let functions = match &module.functions {
+                Some(functions) => functions,
+                None => continue,
+            };
+            let function = match module.function(&call.function) {
+                Some(function) => function,
+                None => {
+                    let suggestion =
+                        suggest(&call.function, functions.iter().map(|f| f.name.as_str()))
+                            .map(String::from);
+                    let message = match &suggestion {
+                        Some(suggestion) => format!(
+                            "module `{}` has no function `{}`, did you mean `{}`?",
+                            call.module, call.function, suggestion
+                        ),
+                        None => format!(
+                            "module `{}` has no function `{}`",
+                            call.module, call.function
+                        ),
+                    };
+                    diagnostics.push(
+                        Diagnostic::warning(message, call.loc.clone()).with_suggestion(suggestion),
+                    );
+                    continue;
+                }
+            };
+            let count = match call.arguments {
+                Some(count) => count,
+                None => continue,
+            };
+            // 省略末尾的参数是合法的，只检查多传的参数
+            let max = function.params.len();
+            if count <= max || function.variadic {
+                continue;
+            }
+            diagnostics.push(Diagnostic::warning(
+                format!(
+                    "function `{}.{}` expects at most {} argument{}, got {}",
+                    call.module,
+                    call.function,
+                    max,
+                    if max == 1 { "" } else { "s" },
+                    count
+                ),
+                call.loc.clone(),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// `source` 中 `position` 处的字节偏移，`start` 为 `source` 开头的位置
+fn offset_at(source: &str, start: &Position, position: &Position) -> Option<usize> {
+    let mut current = start.clone();
+    for (offset, c) in source.char_indices() {
+        if current == *position {
+            return Some(offset);
+        }
+        if c == '\n' {
+            current.line += 1;
+            current.column = 0;
+        } else {
+            current.column += 1;
+        }
+    }
+    (current == *position).then_some(source.len())
+}
+
+/// 模板中 `position` 处的 `module.` 后可以补全的函数，例如 `{{utils.fo}}` 中返回以 `fo` 开头的函数
+pub fn module_completions(
+    template: &TemplateMeta,
+    modules: &[TemplateModuleMeta],
+    position: &Position,
+) -> Vec<FilterFunctionMeta> {
+    let mut sources = vec![];
+    collect_expression_sources(&template.nodes, &mut sources);
+    let before = sources.into_iter().find_map(|(source, start)| {
+        let offset = offset_at(source, start, position)?;
+        Some(&source[..offset])
+    });
+    let captures = match before
+        .and_then(|before| regex!(r"(?:^|[^\w$.])([A-Za-z_$][\w$]*)\.([\w$]*)$").captures(before))
+    {
+        Some(captures) => captures,
+        None => return vec![],
+    };
+    let (name, prefix) = (&captures[1], &captures[2]);
+    modules
+        .iter()
+        .find(|module| module.name == name)
+        .and_then(|module| module.functions.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|function| function.name.starts_with(prefix))
+        .cloned()
+        .collect()
+}
+
+/// 模板中的表达式源码及其开始位置：插值和省略了 `{{ }}` 的指令
+fn collect_expression_sources<'a>(
+    nodes: &'a [TemplateNode],
+    sources: &mut Vec<(&'a str, &'a Position)>,
+) {
+    let interpolation = |i: &'a InterpolationMeta| (i.expression.as_str(), &i.expression_loc.start);
+    for node in nodes {
+        match node {
+            TemplateNode::Element(element) => {
+                if is_isolated(element) && element.name != "template" {
+                    continue;
+                }
+                for attribute in &element.attributes {
+                    let mut interpolated = false;
+                    for part in &attribute.parts {
+                        if let ValuePart::Interpolation(i) = part {
+                            sources.push(interpolation(i));
+                            interpolated = true;
+                        }
+                    }
+                    if !interpolated && attribute.name.starts_with("s-") {
+                        if let (Some(value), Some(value_loc)) =
+                            (&attribute.value, &attribute.value_loc)
+                        {
+                            sources.push((value.as_str(), &value_loc.start));
+                        }
+                    }
+                }
+                collect_expression_sources(&element.children, sources);
+            }
+            TemplateNode::Interpolation(i) => sources.push(interpolation(i)),
+            _ => (),
+        }
+    }
+}
+
+/// 元素上 `s-for` 声明的作用域
+struct ForScope {
+    list: ExpressionMeta,
+    track_by: Option<ExpressionMeta>,
+    item: VariableMeta,
+    index: VariableMeta,
+}
+
+/// 模板中某个位置的作用域
+struct Scope {
+    /// data、properties、computed 和 sjs 模块
+    globals: Vec<VariableMeta>,
+    /// 外层 `s-for` 声明的 item 和 index，内层的在后
+    locals: Vec<VariableMeta>,
+}
+
+impl Scope {
+    fn new(template: &TemplateMeta, component: &ComponentMeta) -> Self {
+        let mut globals = vec![];
+        for data in &component.data {
+            let mut variable =
+                VariableMeta::new(data.name.clone(), VariableKind::Data, data.loc.clone());
+            variable.comment = data.comment.clone();
+            variable.fields = data.children.clone();
+            variable.items = data.items.clone();
+            globals.push(variable);
+        }
+        for property in &component.properties {
+            let mut variable = VariableMeta::new(
+                property.name.clone(),
+                VariableKind::Property,
+                property.loc.clone(),
+            );
+            variable.comment = property.comment.clone();
+            globals.push(variable);
+        }
+        for computed in &component.computed {
+            let mut variable = VariableMeta::new(
+                computed.name.clone(),
+                VariableKind::Computed,
+                computed.loc.clone(),
+            );
+            variable.comment = computed.comment.clone();
+            globals.push(variable);
+        }
+        collect_modules(&template.nodes, &mut globals);
+        Scope {
+            globals,
+            locals: vec![],
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&VariableMeta> {
+        self.locals
+            .iter()
+            .rev()
+            .chain(self.globals.iter())
+            .find(|variable| variable.name == name)
+    }
+
+    /// 当前可以使用的变量，内层的在前，不包括被遮蔽的变量
+    fn visible(&self) -> Vec<VariableMeta> {
+        let mut result: Vec<VariableMeta> = vec![];
+        for variable in self.locals.iter().rev().chain(self.globals.iter()) {
+            if !result.iter().any(|v| v.name == variable.name) {
+                result.push(variable.clone());
+            }
+        }
+        result
+    }
+
+    /// 解析元素的 `s-for`，item 和 index 的名称依次取 `s-for` 中的声明、
+    /// `s-for-item`、`s-for-index` 属性和默认的 `item`、`index`
+    fn parse_for(&self, element: &ElementMeta) -> Option<Result<ForScope, Diagnostic>> {
+        let attribute = element.attribute("s-for")?;
+        let (value, value_loc) = attribute.value.as_ref().zip(attribute.value_loc.as_ref())?;
+        let for_meta = match parse_for(value, &value_loc.start) {
+            Ok(for_meta) => for_meta,
+            Err(diagnostic) => return Some(Err(diagnostic)),
+        };
+        let alias = |alias: Option<AliasMeta>, name: &str, default: &str, kind: VariableKind| {
+            let custom = element.attribute(name).and_then(|a| {
+                let value = a.value.as_ref()?.trim();
+                let loc = a.value_loc.clone()?;
+                (!value.is_empty()).then(|| AliasMeta {
+                    name: value.to_string(),
+                    loc,
+                })
+            });
+            match alias.or(custom) {
+                Some(alias) => VariableMeta::new(alias.name, kind, alias.loc),
+                None => VariableMeta::new(default.to_string(), kind, attribute.name_loc.clone()),
+            }
+        };
+        let mut item = alias(for_meta.item, "s-for-item", "item", VariableKind::Item);
+        let index = alias(for_meta.index, "s-for-index", "index", VariableKind::Index);
+        item.fields = self.element_fields(&for_meta.list);
+        Some(Ok(ForScope {
+            list: for_meta.list,
+            track_by: for_meta.track_by,
+            item,
+            index,
+        }))
+    }
+
+    /// 列表是 data 或外层 item 的访问路径时，列表元素的字段
+    fn element_fields(&self, list: &ExpressionMeta) -> Option<Vec<DataMeta>> {
+        let reference = match list.references.as_slice() {
+            [reference] if reference.path_loc == list.loc => reference,
+            _ => return None,
+        };
+        self.lookup(&reference.name)?
+            .element_fields(&reference.path[1..])
+    }
+
+    /// 进入 `s-for` 的作用域，返回遮蔽了外层变量的警告
+    fn push(&mut self, for_scope: &ForScope) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for variable in [&for_scope.item, &for_scope.index] {
+            if let Some(outer) = self.lookup(&variable.name) {
+                diagnostics.push(Diagnostic::warning(
+                    format!(
+                        "{} `{}` shadows {} `{}`",
+                        variable.kind.describe(),
+                        variable.name,
+                        outer.kind.describe(),
+                        outer.name
+                    ),
+                    variable.loc.clone(),
+                ));
+            }
+            self.locals.push(variable.clone());
+        }
+        diagnostics
+    }
+}
+
+/// 命名模板中的变量由使用模板的地方传入，filter 等元素的内容是脚本
+fn is_isolated(element: &ElementMeta) -> bool {
+    let name = element.name.as_str();
+    MODULE_ELEMENTS.contains(&name) || (name == "template" && element.attribute("name").is_some())
+}
+
+fn contains(loc: &Location, position: &Position) -> bool {
+    let key = |p: &Position| (p.line, p.column);
+    key(&loc.start) <= key(position) && key(position) <= key(&loc.end)
+}
+
+/// 检查模板中没有在 data、properties、computed 中声明的变量，遮蔽了外层变量的 `s-for` item、index，
+/// 以及不存在或者是生命周期函数的事件处理函数
+///
+/// `s-for` 的 item、index 和 `<filter>`、`<import-sjs>` 声明的模块视为已声明，
+/// `<template name>` 中的变量由使用模板的地方传入，不检查
+pub fn check_template(template: &TemplateMeta, component: &ComponentMeta) -> Vec<Diagnostic> {
+    let mut checker = TemplateChecker {
+        component,
+        scope: Scope::new(template, component),
+        diagnostics: vec![],
+        undeclared: vec![],
+    };
+    checker.nodes(&template.nodes);
+    checker.diagnostics
+}
+
+/// 模板中 `position` 处可以使用的变量，内层 `s-for` 的 item、index 在前，被遮蔽的外层变量不在结果中
+pub fn variables_at(
+    template: &TemplateMeta,
+    component: &ComponentMeta,
+    position: &Position,
+) -> Vec<VariableMeta> {
+    let mut scope = Scope::new(template, component);
+    let mut nodes = &template.nodes;
+    'outer: loop {
+        for node in nodes {
+            if let TemplateNode::Element(element) = node {
+                if !contains(&element.loc, position) {
+                    continue;
+                }
+                if is_isolated(element) {
+                    return vec![];
+                }
+                // 列表在外层作用域中求值
+                let in_for = element
+                    .attribute("s-for")
+                    .is_some_and(|a| contains(&a.loc, position));
+                if !in_for {
+                    if let Some(Ok(for_scope)) = scope.parse_for(element) {
+                        scope.push(&for_scope);
+                    }
+                }
+                nodes = &element.children;
+                continue 'outer;
+            }
+        }
+        break;
+    }
+    scope.visible()
+}
+
+/// 模板中 `<filter module="x">` 和 `<import-sjs module="x">` 声明的模块
+fn collect_modules(nodes: &[TemplateNode], modules: &mut Vec<VariableMeta>) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            if MODULE_ELEMENTS.contains(&element.name.as_str()) {
+                if let Some(attribute) = element.attribute("module") {
+                    if let Some((value, value_loc)) =
+                        attribute.value.as_ref().zip(attribute.value_loc.as_ref())
+                    {
+                        modules.push(VariableMeta::new(
+                            value.trim().to_string(),
+                            VariableKind::Module,
+                            value_loc.clone(),
+                        ));
+                    }
+                }
+            }
+            collect_modules(&element.children, modules);
+        }
+    }
+}
+
+struct TemplateChecker<'a> {
+    component: &'a ComponentMeta,
+    scope: Scope,
+    diagnostics: Vec<Diagnostic>,
+    /// 未声明的变量名，按出现的顺序排列
+    undeclared: Vec<String>,
+}
+
+impl TemplateChecker<'_> {
+    fn nodes(&mut self, nodes: &[TemplateNode]) {
+        for node in nodes {
+            match node {
+                TemplateNode::Element(element) => self.element(element),
+                TemplateNode::Interpolation(interpolation) => {
+                    let expression = parse_expression(
+                        &interpolation.expression,
+                        &interpolation.expression_loc.start,
+                    );
+                    match expression {
+                        Ok(expression) => self.check(&expression),
+                        Err(diagnostic) => self.diagnostics.push(diagnostic),
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn element(&mut self, element: &ElementMeta) {
+        if is_isolated(element) {
+            return;
+        }
+
+        let locals_len = self.scope.locals.len();
+        // 列表在外层作用域中求值，元素自身的其他属性和子节点可以使用 item 和 index
+        match self.scope.parse_for(element) {
+            Some(Ok(for_scope)) => {
+                self.check(&for_scope.list);
+                let diagnostics = self.scope.push(&for_scope);
+                self.diagnostics.extend(diagnostics);
+                if let Some(track_by) = &for_scope.track_by {
+                    self.check(track_by);
+                }
+            }
+            Some(Err(diagnostic)) => self.diagnostics.push(diagnostic),
+            None => (),
+        }
+
+        let mut bindings = TemplateBindings::default();
+        for attribute in &element.attributes {
+            if !FOR_ATTRIBUTES.contains(&attribute.name.as_str()) {
+                attribute_bindings(element, attribute, &mut bindings);
+            }
+        }
+        for binding in &bindings.bindings {
+            self.check(&binding.expression);
+        }
+        self.diagnostics.extend(bindings.diagnostics);
+
+        for binding in element_events(element, self.component) {
+            if let Some(diagnostic) = check_event(&binding, self.component) {
+                self.diagnostics.push(diagnostic);
+            }
+        }
+
+        self.nodes(&element.children);
+        self.scope.locals.truncate(locals_len);
+    }
+
+    fn check(&mut self, expression: &ExpressionMeta) {
+        for reference in &expression.references {
+            let name = reference.name.as_str();
+            if GLOBALS.contains(&name) || self.scope.lookup(name).is_some() {
+                continue;
+            }
+            self.undeclared.push(name.to_string());
+            let visible = self.scope.visible();
+            let suggestion =
+                suggest(name, visible.iter().map(|v| v.name.as_str())).map(String::from);
+            let message = match &suggestion {
+                Some(suggestion) => format!(
+                    "`{}` is not declared in data, properties or computed, did you mean `{}`?",
+                    name, suggestion
+                ),
+                None => format!("`{}` is not declared in data, properties or computed", name),
+            };
+            self.diagnostics.push(
+                Diagnostic::warning(message, reference.loc.clone()).with_suggestion(suggestion),
+            );
+        }
+    }
+}
+
+/// 页面或组件模板的一种分析，序列化时 `kind` 为分析类型，其余字段为该分析的参数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Analysis {
+    /// 检查没有在 js 中声明的变量和不存在的事件处理函数
+    CheckTemplate,
+    /// `position` 处可以使用的变量
+    TemplateVariables { position: Position },
+    /// 事件绑定及 js 中对应的事件处理函数
+    EventBindings,
+    /// 使用的类名及其在样式文件中的定义
+    TemplateClasses,
+    /// 检查没有在样式文件中定义的类名
+    CheckClasses,
+    /// 检查自定义组件和内置组件的属性
+    CheckComponents,
+    /// 标签 `tag` 对应的组件可以使用的属性，没有在 `usingComponents` 中声明时查找内置组件
+    ComponentAttributes { tag: String },
+    /// 检查需要比 `version` 更高基础库的内置组件、属性和事件
+    CheckVersions { version: String },
+    /// `<import>`、`<include>` 及其路径
+    TemplateImports,
+    /// 模板中定义的命名模板
+    TemplateDefinitions,
+    /// `<template is>` 使用的命名模板及其定义
+    TemplateUsages,
+    /// 检查不存在的 `<import>`、`<include>` 文件和命名模板，以及 data 中没有传入命名模板读取的变量
+    CheckTemplateImports,
+    /// `<filter>`、`<import-sjs>` 声明的模块及其导出的函数
+    TemplateModules,
+    /// 检查不存在的模块文件和对模块函数的调用
+    CheckModuleCalls,
+    /// `position` 处 `module.` 后可以补全的函数
+    ModuleCompletions { position: Position },
+}
+
+/// 分析结果，序列化时为对应的数组
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AnalysisResult {
+    Diagnostics(Vec<Diagnostic>),
+    Variables(Vec<VariableMeta>),
+    EventBindings(Vec<EventBindingMeta>),
+    Classes(Vec<TemplateClassMeta>),
+    Attributes(Vec<AttributeCompletion>),
+    Imports(Vec<TemplateImportMeta>),
+    Definitions(Vec<TemplateDefinitionMeta>),
+    Usages(Vec<TemplateUsageMeta>),
+    Modules(Vec<TemplateModuleMeta>),
+    Functions(Vec<FilterFunctionMeta>),
+}
+
+impl AnalysisResult {
+    /// 检查类分析给出的诊断，其他分析返回空数组
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        match self {
+            AnalysisResult::Diagnostics(diagnostics) => diagnostics,
+            _ => vec![],
+        }
+    }
+}
+
+/// 模板可以使用的样式文件，以及包括 `@import` 的文件在内的解析结果
+pub(crate) type UnitStyles = (Vec<String>, HashMap<String, FileResult<CssMeta>>);
+
+/// 分析页面或组件时读取的文件，`Parser` 从项目中读取，wasm 绑定使用传入的源码
+pub(crate) trait AnalysisSource {
+    /// 模板相对项目根目录的路径，用于解析 `<import>` 和模块的路径
+    fn template_path(&self) -> String;
+
+    fn template(&self) -> Result<TemplateMeta, ParseError>;
+
+    fn component(&self) -> Result<ComponentMeta, ParseError>;
+
+    fn styles(&self) -> Result<UnitStyles, ParseError>;
+
+    /// `usingComponents` 中的组件名及组件 js 的解析结果
+    fn components(&self) -> Result<BTreeMap<String, ComponentMeta>, ParseError>;
+
+    /// 解析路径后的 `<import>`、`<include>` 以及模板中可以使用的命名模板
+    fn link(
+        &self,
+        template: &TemplateMeta,
+    ) -> (Vec<TemplateImportMeta>, Vec<TemplateDefinitionMeta>);
+
+    /// 解析路径并填入导出函数后的模块
+    fn modules(&self, template: &TemplateMeta) -> Vec<TemplateModuleMeta>;
+}
+
+/// 执行一种分析，只读取该分析需要的文件
+pub(crate) fn analyze(
+    source: &impl AnalysisSource,
+    analysis: &Analysis,
+) -> Result<AnalysisResult, ParseError> {
+    let template = source.template()?;
+    Ok(match analysis {
+        Analysis::CheckTemplate => {
+            AnalysisResult::Diagnostics(check_template(&template, &source.component()?))
+        }
+        Analysis::TemplateVariables { position } => {
+            AnalysisResult::Variables(variables_at(&template, &source.component()?, position))
+        }
+        Analysis::EventBindings => {
+            AnalysisResult::EventBindings(event_bindings(&template, &source.component()?))
+        }
+        Analysis::TemplateClasses | Analysis::CheckClasses => {
+            let (style_paths, styles) = source.styles()?;
+            let visible = visible_styles(&styles, &style_paths);
+            let classes = template_classes(&template, &visible);
+            match analysis {
+                Analysis::CheckClasses => {
+                    AnalysisResult::Diagnostics(check_classes(&classes, &visible))
+                }
+                _ => AnalysisResult::Classes(classes),
+            }
+        }
+        Analysis::CheckComponents => {
+            let components = source.components()?;
+            let components = components
+                .iter()
+                .map(|(name, meta)| (name.as_str(), meta))
+                .collect();
+            AnalysisResult::Diagnostics(check_components(&template, &components))
+        }
+        Analysis::ComponentAttributes { tag } => {
+            let attributes = match source.components()?.get(tag) {
+                Some(component) => component_attributes(component),
+                None => builtin_attributes(catalog::builtin(tag).ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::ComponentNotFound,
+                        format!("component `{}` is not declared in usingComponents", tag),
+                    )
+                })?),
+            };
+            AnalysisResult::Attributes(attributes)
+        }
+        Analysis::CheckVersions { version } => {
+            AnalysisResult::Diagnostics(check_versions(&template, version))
+        }
+        Analysis::TemplateImports => AnalysisResult::Imports(source.link(&template).0),
+        Analysis::TemplateDefinitions => {
+            AnalysisResult::Definitions(template_definitions(&source.template_path(), &template))
+        }
+        Analysis::TemplateUsages => {
+            let (_, definitions) = source.link(&template);
+            AnalysisResult::Usages(template_usages(&template, &definitions))
+        }
+        Analysis::CheckTemplateImports => {
+            let (imports, definitions) = source.link(&template);
+            let usages = template_usages(&template, &definitions);
+            let mut diagnostics = check_template_imports(&imports);
+            diagnostics.extend(check_template_usages(&usages, &definitions));
+            AnalysisResult::Diagnostics(diagnostics)
+        }
+        Analysis::TemplateModules => AnalysisResult::Modules(source.modules(&template)),
+        Analysis::CheckModuleCalls => {
+            let modules = source.modules(&template);
+            let mut diagnostics = check_template_modules(&modules);
+            diagnostics.extend(check_module_calls(&template, &modules));
+            AnalysisResult::Diagnostics(diagnostics)
+        }
+        Analysis::ModuleCompletions { position } => {
+            let modules = source.modules(&template);
+            AnalysisResult::Functions(module_completions(&template, &modules, position))
+        }
+    })
+}
+
+#[test]
+fn test_suggest() {
+    assert_eq!(edit_distance("dat1", "data1"), 1);
+    assert_eq!(edit_distance("标题", "标签"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("titel", "title"), 1);
+    assert_eq!(suggest("dat1", ["list", "data1", "data2"]), Some("data1"));
+    assert_eq!(suggest("item", ["list", "items"]), Some("items"));
+    assert_eq!(suggest("ab", ["b", "ac"]), Some("b"));
+    assert_eq!(suggest("a", ["b", "c"]), None);
+    assert_eq!(suggest("title", ["list"]), None);
+}
+
+#[test]
+fn test_check_template() {
+    use crate::parser::{parse_component_source, parse_template_source, Location, Severity};
+
+    let component = parse_component_source(
+        "component.js",
+        r#"
+Component({
+    properties: {
+        count: {type: Number, value: 0}
+    },
+    data: {
+        ifOpen: false,
+        data1: 1,
+        list: []
+    },
+    computed: {
+        fullName() {}
+    }
+});"#
+            .to_string(),
+    )
+    .unwrap();
+    let template = parse_template_source(
+        "component.swan",
+        r#"<filter module="utils">module.exports = {format: value => value};</filter>
+<import-sjs src="./tools.sjs" module="tools" />
+<view s-if="ifOpen">{{dat1}}</view>
+<view s-for="row, i in list trackBy row.id" class="{{i % 2 ? 'odd' : ''}}">
+    {{row.name}} {{utils.format(count)}} {{tools.x}}
+    <text s-for="list">{{index}}: {{item}} {{fullNam}}</text>
+</view>
+<view>{{row}}</view>
+<template name="cell">{{anything}}</template>
+<view s-if="{{a +}}">{{undefined}}</view>"#,
+    )
+    .unwrap();
+    let diagnostics = check_template(&template, &component);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "`dat1` is not declared in data, properties or computed, did you mean `data1`?",
+            "`fullNam` is not declared in data, properties or computed, did you mean `fullName`?",
+            "`row` is not declared in data, properties or computed",
+            diagnostics[3].message.as_str(),
+        ]
+    );
+    assert!(diagnostics[3].message.starts_with("invalid expression"));
+    assert_eq!(diagnostics[3].severity, Severity::Error);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].loc, Location::from([3, 22], [3, 26]));
+    assert_eq!(diagnostics[0].suggestion.as_deref(), Some("data1"));
+    assert_eq!(diagnostics[2].suggestion, None);
+}
+
+#[test]
+fn test_template_scopes() {
+    use crate::parser::{parse_component_source, parse_template_source};
+
+    let component = parse_component_source(
+        "page.js",
+        r#"
+Page({
+    data: {
+        groups: [{name: 'g', users: [{nick: 'u'}]}],
+        item: 1
+    },
+    onLoad() {}
+});"#
+            .to_string(),
+    )
+    .unwrap();
+    let template = parse_template_source(
+        "page.swan",
+        r#"<view s-for="groups" s-for-item="group" s-for-index="gi">
+    <view s-for="user, gi in group.users trackBy user.nick">{{user.nick}}</view>
+    <view s-for="group.users">{{item.nick}}</view>
+</view>
+<template name="cell">{{value}}</template>"#,
+    )
+    .unwrap();
+
+    let diagnostics = check_template(&template, &component);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "s-for index `gi` shadows s-for index `gi`",
+            "s-for item `item` shadows data `item`",
+        ]
+    );
+    assert_eq!(diagnostics[0].loc, Location::from([2, 23], [2, 25]));
+    // 省略的 item 位于 s-for 属性名
+    assert_eq!(diagnostics[1].loc, Location::from([3, 10], [3, 15]));
+
+    let names = |variables: &[VariableMeta]| -> Vec<String> {
+        variables.iter().map(|v| v.name.clone()).collect()
+    };
+    let variables = variables_at(
+        &template,
+        &component,
+        &Position {
+            line: 2,
+            column: 62,
+        },
+    );
+    assert_eq!(
+        names(&variables),
+        vec!["gi", "user", "group", "groups", "item"]
+    );
+    assert_eq!(variables[1].kind, VariableKind::Item);
+    assert_eq!(variables[1].fields.as_ref().unwrap()[0].name, "nick");
+    let group = &variables[2];
+    let path = vec![
+        String::from("users"),
+        String::from("0"),
+        String::from("nick"),
+    ];
+    assert_eq!(group.resolve(&path).unwrap().name, "nick");
+    assert!(group.resolve(&path[..1]).unwrap().items.is_some());
+    assert!(group.resolve(&[String::from("missing")]).is_none());
+
+    // 列表在外层作用域中求值
+    let variables = variables_at(
+        &template,
+        &component,
+        &Position {
+            line: 2,
+            column: 26,
+        },
+    );
+    assert_eq!(names(&variables), vec!["gi", "group", "groups", "item"]);
+
+    let variables = variables_at(
+        &template,
+        &component,
+        &Position {
+            line: 3,
+            column: 32,
+        },
+    );
+    assert_eq!(
+        names(&variables),
+        vec!["index", "item", "gi", "group", "groups"]
+    );
+    assert_eq!(variables[1].fields.as_ref().unwrap()[0].name, "nick");
+    assert_eq!(variables[4].kind, VariableKind::Data);
+
+    assert!(variables_at(
+        &template,
+        &component,
+        &Position {
+            line: 5,
+            column: 25
+        }
+    )
+    .is_empty());
+    assert_eq!(
+        names(&variables_at(
+            &template,
+            &component,
+            &Position { line: 6, column: 0 }
+        )),
+        vec!["groups", "item"]
+    );
+}
+
+#[test]
+fn test_event_bindings() {
+    use crate::parser::{parse_component_source, parse_template_source};
+
+    assert_eq!(
+        parse_event_attribute("bindtap"),
+        Some(("tap", false, false))
+    );
+    assert_eq!(
+        parse_event_attribute("catchtouchmove"),
+        Some(("touchmove", true, false))
+    );
+    assert_eq!(
+        parse_event_attribute("bind:change"),
+        Some(("change", false, false))
+    );
+    assert_eq!(
+        parse_event_attribute("capture-catch:tap"),
+        Some(("tap", true, true))
+    );
+    assert_eq!(parse_event_attribute("data-bind"), None);
+    assert_eq!(parse_event_attribute("bind"), None);
+
+    let page = parse_component_source(
+        "page.js",
+        "Page({data: {}, onLoad() {}, onTap() {}, onChange: function () {}});".to_string(),
+    )
+    .unwrap();
+    let template = parse_template_source(
+        "page.swan",
+        r#"<view bindtap=" onTap " catchtouchmove="onMove">
+    <switch bind:change="onChang" capture-bind:tap="onLoad" />
+    <view bindtap="{{handler}}" />
+</view>
+<template name="cell"><view bindtap="anything" /></template>"#,
+    )
+    .unwrap();
+    let bindings = event_bindings(&template, &page);
+    let handlers: Vec<&str> = bindings.iter().map(|b| b.handler.as_str()).collect();
+    assert_eq!(handlers, vec!["onTap", "onMove", "onChang", "onLoad"]);
+    assert_eq!(bindings[0].loc, Location::from([1, 16], [1, 21]));
+    assert_eq!(
+        bindings[0].method.as_ref().unwrap().loc,
+        Location::from([1, 29], [1, 34])
+    );
+    assert!(bindings[1].catch);
+    assert!(bindings[1].method.is_none());
+    assert_eq!(bindings[3].attribute, "capture-bind:tap");
+    assert!(bindings[3].capture);
+
+    let diagnostics = check_template(&template, &page);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "event handler `onMove` is not defined in methods",
+            "event handler `onChang` is not defined in methods, did you mean `onChange`?",
+            "`onLoad` is a lifecycle hook and should not be used as an event handler",
+            "`handler` is not declared in data, properties or computed",
+        ]
+    );
+    assert_eq!(diagnostics[1].suggestion.as_deref(), Some("onChange"));
+
+    let component = parse_component_source(
+        "component.js",
+        "Component({attached() {}, methods: {ready() {}}});".to_string(),
+    )
+    .unwrap();
+    let template = parse_template_source(
+        "component.swan",
+        r#"<view bindtap="attached" catchtap="ready" />"#,
+    )
+    .unwrap();
+    let messages: Vec<String> = check_template(&template, &component)
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["`attached` is a lifecycle hook, event handlers must be defined in methods"]
+    );
+}
+
+#[test]
+fn test_template_classes() {
+    use crate::parser::{parse_css_source_with, parse_template_source};
+
+    let template = parse_template_source(
+        "component.swan",
+        r#"<view class="card {{open ? 'card-open' : 'card-opne'}}">
+    <text class="titel item-{{index}}" />
+</view>"#,
+    )
+    .unwrap();
+    let own = parse_css_source_with("card.css", "\n.card {}\n.card-open {}", |_| None).unwrap();
+    let common = parse_css_source_with("common.css", ".title {}\n.card {}", |_| None).unwrap();
+    let styles = [("card.css", &own), ("common.css", &common)];
+
+    let classes = template_classes(&template, &styles);
+    let names: Vec<&str> = classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["card", "card-open", "card-opne", "titel"]);
+    assert_eq!(
+        classes[0].definitions,
+        vec![
+            ClassDefinitionMeta {
+                file: String::from("card.css"),
+                loc: Location::from([2, 1], [2, 5]),
+            },
+            ClassDefinitionMeta {
+                file: String::from("common.css"),
+                loc: Location::from([2, 1], [2, 5]),
+            },
+        ]
+    );
+
+    let diagnostics = check_classes(&classes, &styles);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "class `card-opne` is not defined in any stylesheet, did you mean `card-open`?",
+            "class `titel` is not defined in any stylesheet, did you mean `title`?",
+        ]
+    );
+    assert_eq!(diagnostics[1].loc, Location::from([2, 17], [2, 22]));
+    assert!(check_classes(&classes, &[]).is_empty());
+
+    // 单个字符和大写字母开头的类名
+    let template = parse_template_source("a.swan", r#"<view class="a Header -b" />"#).unwrap();
+    let css = parse_css_source_with("a.css", ".a {}\n.Header, .-b {}", |_| None).unwrap();
+    let styles = [("a.css", &css)];
+    let classes = template_classes(&template, &styles);
+    assert!(classes.iter().all(|c| c.definitions.len() == 1));
+    assert!(check_classes(&classes, &styles).is_empty());
+}
+
+#[test]
+fn test_check_components() {
+    use crate::parser::{parse_component_source, parse_template_source};
+
+    assert_eq!(kebab_case("maxCount"), "max-count");
+    assert_eq!(literal_mismatch("Number", " 1.5 "), None);
+    assert_eq!(literal_mismatch("Number", "abc"), Some("Number"));
+    assert_eq!(literal_mismatch("String", "abc"), None);
+    assert_eq!(literal_mismatch("Boolean", "true"), None);
+    assert_eq!(literal_mismatch("Boolean", ""), None);
+    assert_eq!(literal_mismatch("Boolean", "yes"), Some("Boolean"));
+
+    let card = parse_component_source(
+        "card.js",
+        r#"
+Component({
+    behaviors: ['swan://form-field'],
+    externalClasses: ['title-class'],
+    properties: {
+        title: String,
+        // 最大数量
+        maxCount: {type: Number, value: 10},
+        open: Boolean
+    },
+    methods: {
+        toggle() {
+            this.triggerEvent('change', {});
+        }
+    }
+});"#
+            .to_string(),
+    )
+    .unwrap();
+    let attributes = component_attributes(&card);
+    let names: Vec<&str> = attributes.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "title",
+            "max-count",
+            "open",
+            "name",
+            "value",
+            "title-class",
+            "bind:change"
+        ]
+    );
+    assert_eq!(attributes[1].r#type.as_deref(), Some("Number"));
+    assert_eq!(attributes[1].value, Some(PropertyValue::Number(10.0)));
+    assert_eq!(attributes[1].comment.as_deref(), Some("// 最大数量"));
+    assert_eq!(attributes[6].kind, AttributeKind::Event);
+
+    let template = parse_template_source(
+        "page.swan",
+        r#"<my-card id="c" titel="x" max-count="ten" open="yes" name="n" title-class="t"
+    s-if="show" data-id="1" bindtap="onTap" bind:change="onChange" bind:close="onClose">
+    <my-card max-count="{{n}}" open />
+</my-card>"#,
+    )
+    .unwrap();
+    let components = BTreeMap::from([("my-card", &card)]);
+    let diagnostics = check_components(&template, &components);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "component `my-card` has no property `titel`, did you mean `title`?",
+            "property `max-count` of component `my-card` expects Number, got string `ten`",
+            "property `open` of component `my-card` expects Boolean, got string `yes`",
+            "component `my-card` never triggers event `close`",
+        ]
+    );
+    assert_eq!(diagnostics[0].loc, Location::from([1, 16], [1, 21]));
+    assert_eq!(diagnostics[0].suggestion.as_deref(), Some("title"));
+    assert_eq!(diagnostics[1].loc, Location::from([1, 37], [1, 40]));
+
+    // 项目中的 behavior 可能添加属性和触发事件
+    let mut with_behavior = card.clone();
+    with_behavior.behaviors.push(String::from("expandable"));
+    let components = BTreeMap::from([("my-card", &with_behavior)]);
+    let diagnostics = check_components(&template, &components);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "property `max-count` of component `my-card` expects Number, got string `ten`",
+            "property `open` of component `my-card` expects Boolean, got string `yes`",
+        ]
+    );
+    let template =
+        parse_template_source("page.swan", r#"<my-card bind:expand="onExpand" />"#).unwrap();
+    assert!(check_components(&template, &components).is_empty());
+}
+
+#[test]
+fn test_check_builtin_components() {
+    use crate::parser::parse_template_source;
+
+    let view = catalog::builtin("view").unwrap();
+    let attributes = builtin_attributes(view);
+    assert_eq!(attributes[0].name, "hover-class");
+    assert_eq!(
+        attributes[0].value,
+        Some(PropertyValue::String(String::from("none")))
+    );
+    let swiper = builtin_attributes(catalog::builtin("swiper").unwrap());
+    assert!(swiper
+        .iter()
+        .any(|a| a.name == "bind:change" && matches!(a.kind, AttributeKind::Event)));
+
+    let template = parse_template_source(
+        "page.swan",
+        r#"<view hover-class="none" hover-stay-time="long" bindtap="onTap" bind:change="onChange">
+    <image src="a.png" mode="aspectFil" lazy-load="{{true}}" binderror="onError" />
+    <swiper interval="3000" autoplay="{{true}}" circle="{{true}}" />
+    <animation-view path="a.json" bindended="onEnded" />
+    <my-view anything="1" />
+</view>"#,
+    )
+    .unwrap();
+    let diagnostics = check_components(&template, &BTreeMap::new());
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "property `hover-stay-time` of component `view` expects Number, got string `long`",
+            "component `view` never triggers event `change`",
+            "`aspectFil` is not a valid value of property `mode` of component `image`, did you mean `aspectFit`?",
+            "component `swiper` has no property `circle`",
+        ]
+    );
+    assert_eq!(diagnostics[2].suggestion.as_deref(), Some("aspectFit"));
+
+    let diagnostics = check_versions(&template, "1.10.0");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "component `animation-view` requires base library 1.11.0 or later"
+    );
+    assert_eq!(diagnostics[0].loc, Location::from([4, 5], [4, 19]));
+    assert!(check_versions(&template, "3.0").is_empty());
+
+    // 常见的内置组件写法没有诊断信息
+    let template = parse_template_source(
+        "page.swan",
+        r#"<scroll-view scroll-y="true" scroll-x="false" scroll-top="{{top}}">
+    <view animation="{{animation}}" hidden="{{hidden}}" hover-class="none">
+        <button type="primary" disabled="true" loading bindtap="onTap">ok</button>
+        <input value="{{value}}" maxlength="10" password disabled="" />
+        <image src="a.png" mode="aspectFit" lazy-load="true" />
+    </view>
+</scroll-view>"#,
+    )
+    .unwrap();
+    assert!(check_components(&template, &BTreeMap::new()).is_empty());
+    assert!(builtin_attributes(view)
+        .iter()
+        .any(|a| a.name == "animation"));
+}
+
+#[test]
+fn test_named_templates() {
+    use crate::parser::{parse_template_source, Location, Severity};
+
+    let library = parse_template_source(
+        "templates/item.swan",
+        r#"<filter module="utils">module.exports = {};</filter>
+<template name="item">
+    <view s-for="tag in tags">{{tag}} {{utils.upper(title)}}</view>
+    <template is="badge" data="{{count}}" />
+</template>
+<template name="badge"><text>{{count}}</text></template>"#,
+    )
+    .unwrap();
+    let definitions = template_definitions("templates/item.swan", &library);
+    assert_eq!(definitions.len(), 2);
+    assert_eq!(definitions[0].name, "item");
+    assert_eq!(definitions[0].loc, Location::from([2, 16], [2, 20]));
+    assert_eq!(definitions[0].params, vec!["tags", "title", "count"]);
+    assert_eq!(definitions[1].params, vec!["count"]);
+
+    let template = parse_template_source(
+        "page.swan",
+        r#"<import src="/templates/item.swan" />
+<include src="./header.swan" />
+<template is="item" data="{{tags: list, title}}" />
+<template is="item" data="{{...detail}}" />
+<template is="itme" />
+<template is="{{dynamic}}" />
+<template is="badge" />"#,
+    )
+    .unwrap();
+    let imports = template_imports(&template);
+    assert_eq!(imports.len(), 2);
+    assert_eq!(imports[0].kind, TemplateImportKind::Import);
+    assert_eq!(imports[0].src, "/templates/item.swan");
+    assert_eq!(imports[1].kind, TemplateImportKind::Include);
+    let diagnostics = check_template_imports(&imports);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[1].severity, Severity::Error);
+    assert_eq!(
+        diagnostics[1].message,
+        "cannot find template file `./header.swan`"
+    );
+
+    let usages = template_usages(&template, &definitions);
+    assert_eq!(usages.len(), 4);
+    assert_eq!(
+        usages[0].data,
+        Some(vec![String::from("tags"), String::from("title")])
+    );
+    assert_eq!(usages[0].definition.as_ref(), Some(&definitions[0]));
+    assert_eq!(usages[1].data, None);
+    assert!(usages[2].definition.is_none());
+
+    let diagnostics = check_template_usages(&usages, &definitions);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "template `item` reads `count`, but it is not passed in data",
+            "template `itme` is not defined or imported, did you mean `item`?",
+            "template `badge` reads `count`, but it is not passed in data",
+        ]
+    );
+    assert_eq!(diagnostics[0].loc, Location::from([3, 26], [3, 47]));
+    assert_eq!(diagnostics[1].suggestion.as_deref(), Some("item"));
+    assert_eq!(diagnostics[2].loc, Location::from([7, 14], [7, 19]));
+
+    // 文档中 `data="{{{...person}}}"` 的写法，插值中为对象字面量
+    let template = parse_template_source(
+        "page.swan",
+        r#"<template is="item" data="{{{...detail}}}" />
+<template is="item" data="{{{tags: list, title}}}" />"#,
+    )
+    .unwrap();
+    let component = crate::parser::parse_component_source(
+        "page.js",
+        String::from("Page({data: {list: [], title: '', detail: {}}})"),
+    )
+    .unwrap();
+    assert!(check_template(&template, &component).is_empty());
+    let usages = template_usages(&template, &definitions);
+    assert_eq!(usages[0].data, None);
+    assert_eq!(
+        usages[1].data,
+        Some(vec![String::from("tags"), String::from("title")])
+    );
+    let diagnostics = check_template_usages(&usages, &definitions);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "template `item` reads `count`, but it is not passed in data"
+    );
+}
+
+#[test]
+fn test_template_modules() {
+    use crate::parser::{parse_template_source, Location, Severity};
+
+    let template = parse_template_source(
+        "page.swan",
+        r#"<filter module="utils">
+export default {
+    // 格式化
+    format: function (date, pattern) {},
+    upper: value => value,
+    pad: (value, length = 2) => value,
+    join: (...items) => items.join(''), concat: function () { return [].join.call(arguments); }
+};
+</filter>
+<filter src="./missing.filter.js" module="missing"></filter>
+<view s-if="utils.upper(a, b)">{{utils.fromat(date)}} {{utils.format(date)}}</view>
+<view title="{{utils.pad()}} {{utils.join(a, b, c)}} {{missing.any()}} {{utils.pad(...list)}} {{utils.concat(a, b)}}" />
+<view>{{utils.}} {{ a + utils.p }}</view>"#,
+    )
+    .unwrap();
+    let mut modules = template_modules("page.swan", &template);
+    assert_eq!(modules.len(), 2);
+    let functions = modules[0].functions.as_ref().unwrap();
+    assert_eq!(functions.len(), 5);
+    assert_eq!(functions[0].loc, Location::from([4, 4], [4, 10]));
+    assert_eq!(functions[0].comment.as_deref(), Some("// 格式化"));
+    assert_eq!(modules[1].src.as_deref(), Some("./missing.filter.js"));
+    assert!(modules[1].functions.is_none());
+
+    let diagnostics = check_template_modules(&modules);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(
+        diagnostics[0].message,
+        "cannot find module file `./missing.filter.js`"
+    );
+    modules[1].path = Some(String::from("missing.filter.js"));
+    assert!(check_template_modules(&modules).is_empty());
+
+    let diagnostics = check_module_calls(&template, &modules);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "function `utils.upper` expects at most 1 argument, got 2",
+            "module `utils` has no function `fromat`, did you mean `format`?",
+        ]
+    );
+    assert_eq!(diagnostics[1].loc, Location::from([11, 39], [11, 45]));
+    assert_eq!(diagnostics[1].suggestion.as_deref(), Some("format"));
+
+    let position = Position {
+        line: 13,
+        column: 14,
+    };
+    let completions = module_completions(&template, &modules, &position);
+    assert_eq!(completions.len(), 5);
+    let position = Position {
+        line: 13,
+        column: 31,
+    };
+    let completions = module_completions(&template, &modules, &position);
+    let names: Vec<&str> = completions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["pad"]);
+    let position = Position {
+        line: 13,
+        column: 8,
+    };
+    assert!(module_completions(&template, &modules, &position).is_empty());
+}
@@ -0,0 +1,241 @@
+use crate::parser::{CssMeta, ParseError};
+use crate::pool;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 进度回调，参数依次为刚完成的文件路径、已完成数、当前已知的文件总数
+pub type ProgressFn = Box<dyn Fn(&str, usize, usize) + Send + Sync>;
+
+/// 批量解析的取消标记和进度通知
+#[derive(Default)]
+pub struct BatchContext {
+    cancelled: Option<Arc<AtomicBool>>,
+    on_progress: Option<ProgressFn>,
+    done: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl BatchContext {
+    pub fn new(cancelled: Option<Arc<AtomicBool>>, on_progress: Option<ProgressFn>) -> Self {
+        BatchContext {
+            cancelled,
+            on_progress,
+            done: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        match &self.cancelled {
+            Some(cancelled) => cancelled.load(Ordering::Relaxed),
+            None => false,
+        }
+    }
+
+    /// 增加待解析文件数，css 解析过程中发现的 `@import` 文件会陆续加入
+    pub fn add_total(&self, count: usize) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// 通知一个文件已解析完毕
+    pub fn report(&self, file_path: &str) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(file_path, done, self.total.load(Ordering::Relaxed));
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// 单个文件的解析结果，`elapsed` 为解析耗时，单位毫秒
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum FileResult<T> {
+    Ok { meta: T, elapsed: f64 },
+    Error { error: ParseError, elapsed: f64 },
+}
+
+impl<T> FileResult<T> {
+    /// 执行解析并记录耗时
+    pub fn timed(parse: impl FnOnce() -> Result<T, ParseError>) -> Self {
+        let start = Instant::now();
+        let result = parse();
+        let elapsed = millis(start.elapsed());
+        match result {
+            Ok(meta) => FileResult::Ok { meta, elapsed },
+            Err(error) => FileResult::Error { error, elapsed },
+        }
+    }
+
+    pub fn meta(&self) -> Option<&T> {
+        match self {
+            FileResult::Ok { meta, .. } => Some(meta),
+            FileResult::Error { .. } => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&ParseError> {
+        match self {
+            FileResult::Ok { .. } => None,
+            FileResult::Error { error, .. } => Some(error),
+        }
+    }
+}
+
+/// 批量解析的统计，`elapsed` 为总耗时，单位毫秒
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub elapsed: f64,
+}
+
+/// 批量解析结果，每个文件对应解析成功的数据或错误信息
+#[derive(Debug, Serialize)]
+pub struct BatchResult<T> {
+    pub files: HashMap<String, FileResult<T>>,
+    pub summary: BatchSummary,
+}
+
+impl<T> BatchResult<T> {
+    pub fn new(files: HashMap<String, FileResult<T>>, start: Instant) -> Self {
+        let failed = files.values().filter(|r| r.error().is_some()).count();
+        let summary = BatchSummary {
+            total: files.len(),
+            succeeded: files.len() - failed,
+            failed,
+            elapsed: millis(start.elapsed()),
+        };
+        BatchResult { files, summary }
+    }
+}
+
+/// 在共享线程池中并行解析一组文件，取消后未开始解析的文件不会出现在结果中
+pub fn parse_files<T: Send>(
+    file_paths: &[String],
+    batch: &BatchContext,
+    parse: impl Fn(&str) -> Result<T, ParseError> + Sync,
+) -> BatchResult<T> {
+    let start = Instant::now();
+    let files = parse_wave(file_paths, batch, &parse).into_iter().collect();
+    BatchResult::new(files, start)
+}
+
+fn parse_wave<T: Send>(
+    file_paths: &[String],
+    batch: &BatchContext,
+    parse: &(impl Fn(&str) -> Result<T, ParseError> + Sync),
+) -> Vec<(String, FileResult<T>)> {
+    batch.add_total(file_paths.len());
+    pool::install(|| {
+        file_paths
+            .par_iter()
+            .filter_map(|file_path| {
+                if batch.is_cancelled() {
+                    return None;
+                }
+                let result = FileResult::timed(|| parse(file_path));
+                batch.report(file_path);
+                Some((file_path.clone(), result))
+            })
+            .collect()
+    })
+}
+
+fn parse_css_files_recrusive(
+    file_paths: &[String],
+    result_map: &mut HashMap<String, FileResult<CssMeta>>,
+    batch: &BatchContext,
+    parse: &(impl Fn(&str) -> Result<CssMeta, ParseError> + Sync),
+) {
+    let results = parse_wave(file_paths, batch, parse);
+
+    let mut import_paths: Vec<String> = vec![];
+    for (file_path, result) in results {
+        if let Some(imports) = result.meta().and_then(|meta| meta.imports.as_ref()) {
+            for import_file_path in imports {
+                if !result_map.contains_key(import_file_path)
+                    && !import_paths.contains(import_file_path)
+                {
+                    import_paths.push(import_file_path.to_string());
+                }
+            }
+        }
+        result_map.insert(file_path, result);
+    }
+    // 同一轮中已解析的文件不再重复解析
+    import_paths.retain(|import_file_path| !result_map.contains_key(import_file_path));
+
+    if !import_paths.is_empty() && !batch.is_cancelled() {
+        parse_css_files_recrusive(&import_paths, result_map, batch, parse);
+    }
+}
+
+/// 并行解析一组 css 文件，结果中包含所有被 `@import` 的文件
+pub fn parse_css_files(
+    file_paths: &[String],
+    batch: &BatchContext,
+    parse: impl Fn(&str) -> Result<CssMeta, ParseError> + Sync,
+) -> BatchResult<CssMeta> {
+    let start = Instant::now();
+    let mut result_map: HashMap<String, FileResult<CssMeta>> = HashMap::new();
+    parse_css_files_recrusive(file_paths, &mut result_map, batch, &parse);
+    BatchResult::new(result_map, start)
+}
+
+#[test]
+fn test_batch_result() {
+    let mut files = HashMap::new();
+    files.insert(String::from("a.js"), FileResult::timed(|| Ok(1)));
+    files.insert(
+        String::from("b.js"),
+        FileResult::timed(|| Err(ParseError::not_found("b.js"))),
+    );
+    let result = BatchResult::new(files, Instant::now());
+    assert_eq!(result.summary.total, 2);
+    assert_eq!(result.summary.succeeded, 1);
+    assert_eq!(result.summary.failed, 1);
+    assert_eq!(result.files["a.js"].meta(), Some(&1));
+
+    let value = serde_json::to_value(&result.files["b.js"]).unwrap();
+    assert_eq!(value["status"], "error");
+    assert_eq!(value["error"]["kind"], "NotFound");
+    assert_eq!(value["error"]["message"], "No such file b.js");
+}
+
+#[test]
+fn test_batch_context() {
+    use std::sync::Mutex;
+
+    let reports = Arc::new(Mutex::new(vec![]));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let on_progress: ProgressFn = {
+        let reports = reports.clone();
+        Box::new(move |file_path, done, total| {
+            reports
+                .lock()
+                .unwrap()
+                .push((file_path.to_string(), done, total));
+        })
+    };
+    let batch = BatchContext::new(Some(cancelled.clone()), Some(on_progress));
+    batch.add_total(2);
+    batch.report("a.js");
+    batch.report("b.js");
+    assert_eq!(
+        *reports.lock().unwrap(),
+        vec![(String::from("a.js"), 1, 2), (String::from("b.js"), 2, 2)]
+    );
+
+    assert!(!batch.is_cancelled());
+    cancelled.store(true, Ordering::Relaxed);
+    assert!(batch.is_cancelled());
+    assert!(!BatchContext::default().is_cancelled());
+}
@@ -0,0 +1,541 @@
+use crate::parser::{
+    parse_component_source, parse_config_source, parse_css_source, parse_template_source,
+    ComponentMeta, ConfigMeta, CssMeta, ParseError, TemplateMeta,
+};
+use crate::pool;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// 默认最多缓存的文件数
+pub const DEFAULT_MAX_ENTRIES: usize = 5000;
+
+/// 默认最多缓存的源码字节数，按源码大小估算占用的内存
+pub const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// 磁盘索引格式版本，格式变化时需要递增
+const INDEX_VERSION: u32 = 2;
+
+/// 磁盘索引文件名
+const INDEX_FILE_NAME: &str = "component-parser-index.json";
+
+struct CacheEntry<T> {
+    hash: u64,
+    size: usize,
+    last_used: u64,
+    meta: T,
+}
+
+/// 按文件路径和内容 hash 缓存解析结果，超出限制时淘汰最久未使用的文件
+pub struct ParseCache<T> {
+    entries: HashMap<String, CacheEntry<T>>,
+    total_bytes: usize,
+    tick: u64,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+impl<T: Clone> ParseCache<T> {
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        ParseCache {
+            entries: HashMap::new(),
+            total_bytes: 0,
+            tick: 0,
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    /// 获取内容 hash 一致的缓存结果
+    pub fn get(&mut self, file_path: &str, hash: u64) -> Option<T> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.entries.get_mut(file_path) {
+            Some(entry) if entry.hash == hash => {
+                entry.last_used = tick;
+                Some(entry.meta.clone())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn insert(&mut self, file_path: &str, hash: u64, size: usize, meta: T) {
+        self.remove(file_path);
+        self.tick += 1;
+        self.total_bytes += size;
+        self.entries.insert(
+            file_path.to_string(),
+            CacheEntry {
+                hash,
+                size,
+                last_used: self.tick,
+                meta,
+            },
+        );
+        self.evict();
+    }
+
+    pub fn remove(&mut self, file_path: &str) {
+        if let Some(entry) = self.entries.remove(file_path) {
+            self.total_bytes -= entry.size;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.total_bytes = 0;
+    }
+
+    pub fn set_limits(&mut self, max_entries: usize, max_bytes: usize) {
+        self.max_entries = max_entries;
+        self.max_bytes = max_bytes;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.max_entries || self.total_bytes > self.max_bytes {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(file_path, _)| file_path.clone());
+            match oldest {
+                Some(file_path) => self.remove(&file_path),
+                None => break,
+            }
+        }
+    }
+}
+
+/// FNV-1a 64 位 hash，跨进程稳定，便于校验缓存内容
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn lock<T>(cache: &Mutex<T>) -> MutexGuard<'_, T> {
+    // 缓存中的数据总是完整写入的，锁被污染时仍可继续使用
+    cache.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// js、css、swan 和 json 各自的解析缓存
+struct Caches {
+    scripts: Mutex<ParseCache<ComponentMeta>>,
+    styles: Mutex<ParseCache<CssMeta>>,
+    templates: Mutex<ParseCache<TemplateMeta>>,
+    configs: Mutex<ParseCache<ConfigMeta>>,
+}
+
+impl Caches {
+    fn new() -> Self {
+        Caches {
+            scripts: Mutex::new(ParseCache::new(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_BYTES)),
+            styles: Mutex::new(ParseCache::new(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_BYTES)),
+            templates: Mutex::new(ParseCache::new(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_BYTES)),
+            configs: Mutex::new(ParseCache::new(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_BYTES)),
+        }
+    }
+}
+
+/// 所有 `Parser` 共享的进程内缓存
+fn caches() -> &'static Caches {
+    static CACHES: OnceLock<Caches> = OnceLock::new();
+    CACHES.get_or_init(Caches::new)
+}
+
+fn script_cache() -> &'static Mutex<ParseCache<ComponentMeta>> {
+    &caches().scripts
+}
+
+fn css_cache() -> &'static Mutex<ParseCache<CssMeta>> {
+    &caches().styles
+}
+
+fn template_cache() -> &'static Mutex<ParseCache<TemplateMeta>> {
+    &caches().templates
+}
+
+fn config_cache() -> &'static Mutex<ParseCache<ConfigMeta>> {
+    &caches().configs
+}
+
+fn parse_with_cache<T: Clone>(
+    cache: &Mutex<ParseCache<T>>,
+    file_path: &str,
+    parse: impl FnOnce(String) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let read_result = fs::read_to_string(file_path);
+    if read_result.is_err() {
+        // 文件已不存在，缓存一并失效
+        lock(cache).remove(file_path);
+        return Err(ParseError::not_found(file_path));
+    }
+
+    let source = read_result.unwrap();
+    let hash = content_hash(source.as_bytes());
+    if let Some(meta) = lock(cache).get(file_path, hash) {
+        return Ok(meta);
+    }
+
+    let size = source.len();
+    let meta = parse(source)?;
+    lock(cache).insert(file_path, hash, size, meta.clone());
+    Ok(meta)
+}
+
+/// 解析 js 文件，内容未变化时直接返回缓存结果
+pub fn parse_component_cached(file_path: &str) -> Result<ComponentMeta, ParseError> {
+    parse_with_cache(script_cache(), file_path, |source| {
+        parse_component_source(file_path, source)
+    })
+}
+
+/// 解析 css 文件，内容未变化时直接返回缓存结果
+pub fn parse_css_class_cached(file_path: &str) -> Result<CssMeta, ParseError> {
+    parse_with_cache(css_cache(), file_path, |source| {
+        parse_css_source(file_path, &source)
+    })
+}
+
+/// 解析 swan 模板，内容未变化时直接返回缓存结果
+pub fn parse_template_cached(file_path: &str) -> Result<TemplateMeta, ParseError> {
+    parse_with_cache(template_cache(), file_path, |source| {
+        parse_template_source(file_path, &source)
+    })
+}
+
+/// 解析 json 配置，内容未变化时直接返回缓存结果
+pub fn parse_config_cached(file_path: &str) -> Result<ConfigMeta, ParseError> {
+    parse_with_cache(config_cache(), file_path, |source| {
+        parse_config_source(file_path, &source)
+    })
+}
+
+/// 使指定文件的缓存失效
+pub fn invalidate(file_paths: &[String]) {
+    let mut scripts = lock(script_cache());
+    let mut styles = lock(css_cache());
+    let mut templates = lock(template_cache());
+    let mut configs = lock(config_cache());
+    for file_path in file_paths {
+        scripts.remove(file_path);
+        styles.remove(file_path);
+        templates.remove(file_path);
+        configs.remove(file_path);
+    }
+}
+
+/// 清空所有缓存
+pub fn clear() {
+    lock(script_cache()).clear();
+    lock(css_cache()).clear();
+    lock(template_cache()).clear();
+    lock(config_cache()).clear();
+}
+
+/// 设置缓存上限，js、css、swan 和 json 缓存各自独立计算
+pub fn set_limits(max_entries: usize, max_bytes: usize) {
+    lock(script_cache()).set_limits(max_entries, max_bytes);
+    lock(css_cache()).set_limits(max_entries, max_bytes);
+    lock(template_cache()).set_limits(max_entries, max_bytes);
+    lock(config_cache()).set_limits(max_entries, max_bytes);
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexEntry<T> {
+    hash: u64,
+    size: usize,
+    meta: T,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Index {
+    version: u32,
+    parser: String,
+    scripts: HashMap<String, IndexEntry<ComponentMeta>>,
+    styles: HashMap<String, IndexEntry<CssMeta>>,
+    templates: HashMap<String, IndexEntry<TemplateMeta>>,
+    configs: HashMap<String, IndexEntry<ConfigMeta>>,
+}
+
+/// 加载磁盘索引的统计结果
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct IndexStats {
+    /// 内容未变化，直接载入的文件数
+    pub fresh: usize,
+    /// 内容已变化，重新解析的文件数
+    pub rebuilt: usize,
+    /// 已删除或无法解析，被丢弃的文件数
+    pub removed: usize,
+}
+
+fn snapshot<T: Clone>(cache: &Mutex<ParseCache<T>>) -> HashMap<String, IndexEntry<T>> {
+    lock(cache)
+        .entries
+        .iter()
+        .map(|(file_path, entry)| {
+            let entry = IndexEntry {
+                hash: entry.hash,
+                size: entry.size,
+                meta: entry.meta.clone(),
+            };
+            (file_path.clone(), entry)
+        })
+        .collect()
+}
+
+/// 单个索引项的校验结果
+enum Restored<T> {
+    Fresh(IndexEntry<T>),
+    Rebuilt(IndexEntry<T>),
+    Removed,
+}
+
+fn restore<T: Clone + Send>(
+    cache: &Mutex<ParseCache<T>>,
+    entries: HashMap<String, IndexEntry<T>>,
+    parse: impl Fn(&str, String) -> Result<T, ParseError> + Sync,
+    stats: &mut IndexStats,
+) {
+    // 校验和重新解析在共享线程池中并行执行，结果统一写入缓存
+    let restored: Vec<(String, Restored<T>)> = pool::install(|| {
+        entries
+            .into_par_iter()
+            .map(|(file_path, entry)| {
+                let source = match fs::read_to_string(&file_path) {
+                    Ok(source) => source,
+                    Err(_) => return (file_path, Restored::Removed),
+                };
+                let hash = content_hash(source.as_bytes());
+                if hash == entry.hash {
+                    return (file_path, Restored::Fresh(entry));
+                }
+
+                let size = source.len();
+                let restored = match parse(&file_path, source) {
+                    Ok(meta) => Restored::Rebuilt(IndexEntry { hash, size, meta }),
+                    Err(_) => Restored::Removed,
+                };
+                (file_path, restored)
+            })
+            .collect()
+    });
+
+    let mut cache = lock(cache);
+    for (file_path, restored) in restored {
+        let entry = match restored {
+            Restored::Fresh(entry) => {
+                stats.fresh += 1;
+                entry
+            }
+            Restored::Rebuilt(entry) => {
+                stats.rebuilt += 1;
+                entry
+            }
+            Restored::Removed => {
+                stats.removed += 1;
+                continue;
+            }
+        };
+        cache.insert(&file_path, entry.hash, entry.size, entry.meta);
+    }
+}
+
+impl Caches {
+    fn save_index(&self, cache_dir: &str) -> Result<(), String> {
+        let index = Index {
+            version: INDEX_VERSION,
+            parser: env!("CARGO_PKG_VERSION").to_string(),
+            scripts: snapshot(&self.scripts),
+            styles: snapshot(&self.styles),
+            templates: snapshot(&self.templates),
+            configs: snapshot(&self.configs),
+        };
+        let text = serde_json::to_string(&index).map_err(|e| e.to_string())?;
+
+        let dir = Path::new(cache_dir);
+        if fs::create_dir_all(dir).is_err() {
+            return Err(format!("Can not create cache dir {}", cache_dir));
+        }
+        // 先写临时文件再重命名，避免进程退出时留下不完整的索引
+        let tmp_path = dir.join(format!("{}.tmp", INDEX_FILE_NAME));
+        if fs::write(&tmp_path, text).is_err()
+            || fs::rename(&tmp_path, dir.join(INDEX_FILE_NAME)).is_err()
+        {
+            return Err(format!("Can not write cache index in {}", cache_dir));
+        }
+        Ok(())
+    }
+
+    fn load_index(&self, cache_dir: &str) -> Result<IndexStats, String> {
+        let mut stats = IndexStats::default();
+        let text = match fs::read_to_string(Path::new(cache_dir).join(INDEX_FILE_NAME)) {
+            Ok(text) => text,
+            Err(_) => return Ok(stats),
+        };
+        let index: Index = match serde_json::from_str(&text) {
+            Ok(index) => index,
+            Err(_) => return Ok(stats),
+        };
+        if index.version != INDEX_VERSION || index.parser != env!("CARGO_PKG_VERSION") {
+            return Ok(stats);
+        }
+
+        restore(
+            &self.scripts,
+            index.scripts,
+            parse_component_source,
+            &mut stats,
+        );
+        restore(
+            &self.styles,
+            index.styles,
+            |file_path, source| parse_css_source(file_path, &source),
+            &mut stats,
+        );
+        restore(
+            &self.templates,
+            index.templates,
+            |file_path, source| parse_template_source(file_path, &source),
+            &mut stats,
+        );
+        restore(
+            &self.configs,
+            index.configs,
+            |file_path, source| parse_config_source(file_path, &source),
+            &mut stats,
+        );
+        Ok(stats)
+    }
+}
+
+/// 将缓存写入 `cache_dir` 下的磁盘索引，包括 js、css、swan 和 json 文件的解析结果
+pub fn save_index(cache_dir: &str) -> Result<(), String> {
+    caches().save_index(cache_dir)
+}
+
+/// 从 `cache_dir` 载入磁盘索引，按内容 hash 校验每个文件，只重新解析已变化的文件
+///
+/// 索引不存在或版本不一致时不载入任何内容
+pub fn load_index(cache_dir: &str) -> Result<IndexStats, String> {
+    caches().load_index(cache_dir)
+}
+
+#[test]
+fn test_parse_cache() {
+    let mut cache: ParseCache<String> = ParseCache::new(2, 100);
+    cache.insert("a", 1, 10, String::from("a"));
+    cache.insert("b", 2, 10, String::from("b"));
+    assert_eq!(cache.get("a", 1), Some(String::from("a")));
+    // hash 变化视为未命中
+    assert_eq!(cache.get("b", 3), None);
+
+    // 超出文件数上限，淘汰最久未使用的 b
+    cache.insert("c", 3, 10, String::from("c"));
+    assert_eq!(cache.entries.len(), 2);
+    assert_eq!(cache.get("b", 2), None);
+    assert_eq!(cache.get("a", 1), Some(String::from("a")));
+
+    // 超出字节数上限
+    cache.insert("d", 4, 95, String::from("d"));
+    assert_eq!(cache.entries.len(), 1);
+    assert_eq!(cache.total_bytes, 95);
+
+    cache.set_limits(2, 50);
+    assert!(cache.entries.is_empty());
+}
+
+#[test]
+fn test_parse_component_cached() {
+    let file_path = std::env::temp_dir().join("component-parser-cache-test.js");
+    let file_path = file_path.to_str().unwrap().to_string();
+    fs::write(&file_path, "Page({data: {a: 1}, onLoad() {}});").unwrap();
+
+    let result = parse_component_cached(&file_path).unwrap();
+    assert_eq!(result.data.len(), 1);
+    let hash = content_hash(fs::read(&file_path).unwrap().as_slice());
+    assert!(lock(script_cache()).get(&file_path, hash).is_some());
+
+    // 内容变化后重新解析
+    fs::write(&file_path, "Page({data: {a: 1, b: 2}, onLoad() {}});").unwrap();
+    let result = parse_component_cached(&file_path).unwrap();
+    assert_eq!(result.data.len(), 2);
+    let hash = content_hash(fs::read(&file_path).unwrap().as_slice());
+    assert!(lock(script_cache()).get(&file_path, hash).is_some());
+
+    invalidate(std::slice::from_ref(&file_path));
+    assert!(lock(script_cache()).get(&file_path, hash).is_none());
+
+    // 文件删除后返回错误
+    fs::remove_file(&file_path).unwrap();
+    assert_eq!(
+        parse_component_cached(&file_path).err(),
+        Some(ParseError::not_found(&file_path))
+    );
+}
+
+#[test]
+fn test_save_and_load_index() {
+    // 使用独立的缓存和目录，不受其他测试写入的共享缓存影响
+    let dir = std::env::temp_dir().join(format!(
+        "component-parser-index-test-{}",
+        std::process::id()
+    ));
+    let cache_dir = dir.to_str().unwrap().to_string();
+    let fresh_path = dir.join("fresh.css").to_str().unwrap().to_string();
+    let stale_path = dir.join("stale.swan").to_str().unwrap().to_string();
+    let removed_path = dir.join("removed.css").to_str().unwrap().to_string();
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&fresh_path, ".fresh {}").unwrap();
+    fs::write(&stale_path, "<view>{{title}}</view>").unwrap();
+    fs::write(&removed_path, ".removed {}").unwrap();
+
+    let caches = Caches::new();
+    for file_path in [&fresh_path, &removed_path] {
+        parse_with_cache(&caches.styles, file_path, |source| {
+            parse_css_source(file_path, &source)
+        })
+        .unwrap();
+    }
+    parse_with_cache(&caches.templates, &stale_path, |source| {
+        parse_template_source(&stale_path, &source)
+    })
+    .unwrap();
+    caches.save_index(&cache_dir).unwrap();
+
+    fs::write(&stale_path, "<view>{{title}}{{name}}</view>").unwrap();
+    fs::remove_file(&removed_path).unwrap();
+    let caches = Caches::new();
+    let stats = caches.load_index(&cache_dir).unwrap();
+    assert_eq!(
+        stats,
+        IndexStats {
+            fresh: 1,
+            rebuilt: 1,
+            removed: 1
+        }
+    );
+
+    let hash = content_hash(fs::read(&fresh_path).unwrap().as_slice());
+    assert!(lock(&caches.styles).get(&fresh_path, hash).is_some());
+    let hash = content_hash(fs::read(&stale_path).unwrap().as_slice());
+    let meta = lock(&caches.templates).get(&stale_path, hash).unwrap();
+    let source = fs::read_to_string(&stale_path).unwrap();
+    assert_eq!(meta, parse_template_source(&stale_path, &source).unwrap());
+    assert_eq!(lock(&caches.styles).entries.len(), 1);
+
+    // 版本不一致时忽略索引
+    fs::write(dir.join(INDEX_FILE_NAME), r#"{"version":0}"#).unwrap();
+    assert_eq!(
+        Caches::new().load_index(&cache_dir).unwrap(),
+        IndexStats::default()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
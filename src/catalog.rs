@@ -0,0 +1,127 @@
+//! 内置 swan 组件目录：组件的属性、类型、默认值、事件和最低基础库版本
+//!
+//! 数据在 `catalog/components.json` 中维护，编译时嵌入，修改数据后需要更新其中的 `version`
+
+use crate::parser::PropertyValue;
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+
+/// 内置组件目录，`version` 为数据的版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    pub version: String,
+    /// 所有内置组件都可以使用的属性
+    #[serde(default)]
+    pub common: Vec<BuiltinAttribute>,
+    pub components: Vec<BuiltinComponent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuiltinComponent {
+    pub name: String,
+    pub description: String,
+    /// 最低基础库版本，`None` 表示最早的基础库已经支持
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<BuiltinAttribute>,
+    /// 组件触发的事件，不包括所有组件都有的 `tap`、`touchstart` 等触摸事件
+    #[serde(default)]
+    pub events: Vec<BuiltinEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuiltinAttribute {
+    pub name: String,
+    pub description: String,
+    /// `String`、`Number`、`Boolean`、`Array`、`Object`，可以取多种类型时用 `|` 分隔
+    pub r#type: String,
+    #[serde(default)]
+    pub default: Option<PropertyValue>,
+    /// 只能取这些值
+    #[serde(default)]
+    pub values: Option<Vec<String>>,
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuiltinEvent {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+/// 编译时嵌入的内置组件目录
+pub fn catalog() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        serde_json::from_str(include_str!("catalog/components.json"))
+            .expect("invalid built-in component catalog")
+    })
+}
+
+/// 按标签名查找内置组件
+pub fn builtin(name: &str) -> Option<&'static BuiltinComponent> {
+    catalog().components.iter().find(|c| c.name == name)
+}
+
+/// 按数字逐段比较基础库版本，`3.10.0` 大于 `3.9.1`，缺少的段按 0 处理
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.trim().parse().unwrap_or(0))
+            .collect()
+    };
+    let (a, b) = (parse(a), parse(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+#[test]
+fn test_catalog() {
+    let catalog = catalog();
+    assert!(!catalog.version.is_empty());
+    // 组件名不重复，每个组件的属性名不重复
+    for (i, component) in catalog.components.iter().enumerate() {
+        assert!(catalog.components[..i]
+            .iter()
+            .all(|c| c.name != component.name));
+        for (j, attribute) in component.attributes.iter().enumerate() {
+            assert!(
+                component.attributes[..j]
+                    .iter()
+                    .all(|a| a.name != attribute.name),
+                "{} {}",
+                component.name,
+                attribute.name
+            );
+        }
+    }
+
+    let swiper = builtin("swiper").unwrap();
+    let interval = swiper
+        .attributes
+        .iter()
+        .find(|a| a.name == "interval")
+        .unwrap();
+    assert_eq!(interval.r#type, "Number");
+    assert_eq!(interval.default, Some(PropertyValue::Number(5000.0)));
+    assert!(swiper.events.iter().any(|e| e.name == "change"));
+    assert_eq!(
+        builtin("animation-view").unwrap().since.as_deref(),
+        Some("1.11.0")
+    );
+    assert!(builtin("my-card").is_none());
+
+    assert_eq!(compare_versions("3.10.0", "3.9.1"), Ordering::Greater);
+    assert_eq!(compare_versions("1.11", "1.11.0"), Ordering::Equal);
+    assert_eq!(compare_versions("1.0.0", "1.0.1"), Ordering::Less);
+}
@@ -0,0 +1,615 @@
+//! 项目依赖图：页面、组件之间的 `usingComponents`，以及 `behaviors`、css `@import`、js `require`
+//! 和模板 `<import>`、`<include>` 关系
+
+use crate::analyzer::{template_imports, template_modules, TemplateImportKind};
+use crate::npm;
+use crate::parser::{normalize_path, parse_config, parse_css_class, parse_template, ParseError};
+use crate::project::{parse_app, read_json, UnitFiles};
+use crate::resolver::{find_files, ComponentTarget, Resolver};
+use std::collections::BTreeMap;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 依赖图中的节点类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NodeKind {
+    /// `app.json`，依赖所有页面和全局组件，id 为 `app`
+    App,
+    Page,
+    Component,
+    /// 不属于页面或组件的 js 文件，例如工具函数和 behavior
+    Script,
+    /// 不属于页面或组件的 css 文件
+    Style,
+    /// 不属于页面或组件的模板文件，例如只定义命名模板的文件
+    Template,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EdgeKind {
+    /// `app.json` 中的页面
+    Page,
+    /// `usingComponents` 中的组件，插件等外部组件不在图中
+    Component,
+    /// `behaviors` 中引用的模块
+    Behavior,
+    /// css `@import`
+    CssImport,
+    /// js `require` 和 `import`
+    Require,
+    /// 模板 `<import>`
+    TemplateImport,
+    /// 模板 `<include>`
+    TemplateInclude,
+    /// 模板 `<filter>`、`<import-sjs>` 引用的模块
+    Module,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// 项目依赖图，节点 id 为相对项目根目录的路径，页面和组件不带扩展名，其他文件带扩展名
+///
+/// 除了从 `app.json` 出发能找到的页面和组件，还包含项目目录中所有带 json 配置的页面和组件，
+/// 用于查找未被使用的页面和组件
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DependencyGraph {
+    pub nodes: BTreeMap<String, NodeKind>,
+    /// 按起点、终点排序，相同的边只保留一条
+    pub edges: Vec<GraphEdge>,
+}
+
+/// 依赖图及其中的循环依赖和未使用的节点，用于 json 输出
+#[derive(Debug, Serialize)]
+pub struct GraphReport<'a> {
+    #[serde(flatten)]
+    pub graph: &'a DependencyGraph,
+    pub cycles: Vec<Vec<String>>,
+    pub unreachable: Vec<String>,
+}
+
+/// 递归查找带 json 配置的页面和组件，跳过 npm 目录和隐藏目录
+fn discover_units(root: &Path, dir: &Path, units: &mut Vec<String>) {
+    let entries = match fs::read_dir(root.join(dir)) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok().map(|e| e.file_name().to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    for name in names {
+        let path = dir.join(&name);
+        if root.join(&path).is_dir() {
+            if !name.starts_with('.') && name != "node_modules" && name != "miniprogram_npm" {
+                discover_units(root, &path, units);
+            }
+            continue;
+        }
+        let base = match name.strip_suffix(".json") {
+            Some(base) => dir.join(base),
+            None => continue,
+        };
+        let files = find_files(&root.join(&base));
+        // `app.json`、`project.config.json` 等不是页面或组件
+        if base != Path::new("app") && (files.script.is_some() || files.template.is_some()) {
+            units.push(base.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+struct Builder<'a> {
+    root: &'a Path,
+    canonical_root: Option<PathBuf>,
+    graph: DependencyGraph,
+    edges: BTreeSet<GraphEdge>,
+    /// 页面和组件的同名文件，用于将 js、css 文件归属到页面或组件
+    units: HashMap<String, UnitFiles>,
+    /// 待扫描依赖的独立 js、css、模板文件
+    files: VecDeque<String>,
+    resolver: Resolver,
+}
+
+impl<'a> Builder<'a> {
+    fn add_edge(&mut self, from: &str, to: &str, kind: EdgeKind) {
+        self.edges.insert(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        });
+    }
+
+    /// 相对项目根目录的路径，项目外的文件返回 `None`
+    fn relative(&self, file_path: &Path) -> Option<String> {
+        let path = file_path
+            .strip_prefix(self.root)
+            .ok()
+            .or_else(|| file_path.strip_prefix(self.canonical_root.as_ref()?).ok())?;
+        Some(path.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// 文件所属的节点，页面和组件的 js、css、模板文件归属到页面或组件，其他文件作为独立节点
+    fn node_for(&mut self, file: &str) -> String {
+        let base = file.rsplit_once('.').map_or(file, |(base, _)| base);
+        if self.units.contains_key(base) {
+            return base.to_string();
+        }
+        if !self.graph.nodes.contains_key(file) {
+            let kind = if file.ends_with(".css") {
+                NodeKind::Style
+            } else if file.ends_with(".swan") {
+                NodeKind::Template
+            } else {
+                NodeKind::Script
+            };
+            self.graph.nodes.insert(file.to_string(), kind);
+            self.files.push_back(file.to_string());
+        }
+        file.to_string()
+    }
+
+    /// 解析 js 中引用的模块，只处理项目中存在的文件
+    fn resolve_script(&self, dir: &Path, request: &str) -> Option<String> {
+        let bases = if let Some(path) = request.strip_prefix('/') {
+            vec![PathBuf::from(path)]
+        } else if request.starts_with('.') {
+            vec![dir.join(request)]
+        } else {
+            npm::candidates(self.root, dir, request)
+        };
+        bases.iter().find_map(|base| {
+            let base = normalize_path(base);
+            if base.starts_with("..") || base.has_root() {
+                return None;
+            }
+            let base = base.to_string_lossy().replace('\\', "/");
+            ["", ".js", ".ts", "/index.js", "/index.ts"]
+                .iter()
+                .map(|ext| format!("{}{}", base, ext))
+                .find(|file| self.root.join(file).is_file())
+        })
+    }
+
+    fn scan_script(&mut self, from: &str, file: &str) {
+        let text = match fs::read_to_string(self.root.join(file)) {
+            Ok(text) => text,
+            Err(_) => return,
+        };
+        let regex_require =
+            regex!(r#"(?:\brequire\s*\(|\bimport\s*\(|\bfrom|\bimport)\s*["']([^"']+)["']"#);
+        let regex_binding = regex!(
+            r#"(?:\bimport\s+([\w$]+)\s+from|\b(?:const|let|var)\s+([\w$]+)\s*=\s*require\s*\()\s*["']([^"']+)["']"#
+        );
+        let regex_behaviors = regex!(r"\bbehaviors\s*:\s*\[([^\]]*)\]");
+        // 字符串为 `swan://form-field` 等内置 behavior，单独匹配以跳过
+        let regex_item =
+            regex!(r#"\brequire\s*\(\s*["']([^"']+)["']\s*\)|["'][^"']*["']|([\w$]+)"#);
+
+        let dir = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+        let bindings: HashMap<&str, &str> = regex_binding
+            .captures_iter(&text)
+            .filter_map(|caps| {
+                let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+                Some((name, caps.get(3)?.as_str()))
+            })
+            .collect();
+
+        let mut behaviors = HashSet::new();
+        for caps in regex_behaviors.captures_iter(&text) {
+            for item in regex_item.captures_iter(&caps[1]) {
+                let request = match (item.get(1), item.get(2)) {
+                    (Some(request), _) => request.as_str(),
+                    (_, Some(name)) => match bindings.get(name.as_str()) {
+                        Some(request) => request,
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                if let Some(target) = self.resolve_script(dir, request) {
+                    let to = self.node_for(&target);
+                    self.add_edge(from, &to, EdgeKind::Behavior);
+                    behaviors.insert(target);
+                }
+            }
+        }
+
+        for caps in regex_require.captures_iter(&text) {
+            if let Some(target) = self.resolve_script(dir, &caps[1]) {
+                if !behaviors.contains(&target) {
+                    let to = self.node_for(&target);
+                    self.add_edge(from, &to, EdgeKind::Require);
+                }
+            }
+        }
+    }
+
+    fn scan_style(&mut self, from: &str, file: &str) {
+        let meta = match parse_css_class(&self.root.join(file).to_string_lossy()) {
+            Ok(meta) => meta,
+            Err(_) => return,
+        };
+        for import in meta.imports.into_iter().flatten() {
+            if let Some(target) = self.relative(Path::new(&import)) {
+                let to = self.node_for(&target);
+                self.add_edge(from, &to, EdgeKind::CssImport);
+            }
+        }
+    }
+
+    fn scan_template(&mut self, from: &str, file: &str) {
+        let template = match parse_template(&self.root.join(file).to_string_lossy()) {
+            Ok(template) => template,
+            Err(_) => return,
+        };
+        for import in template_imports(&template) {
+            if let Some(target) = self.resolver.resolve_template(file, &import.src) {
+                let to = self.node_for(&target);
+                let kind = match import.kind {
+                    TemplateImportKind::Import => EdgeKind::TemplateImport,
+                    TemplateImportKind::Include => EdgeKind::TemplateInclude,
+                };
+                self.add_edge(from, &to, kind);
+            }
+        }
+        for module in template_modules(file, &template) {
+            let target = module
+                .src
+                .and_then(|src| self.resolver.resolve_module(file, &src));
+            if let Some(target) = target {
+                let to = self.node_for(&target);
+                self.add_edge(from, &to, EdgeKind::Module);
+            }
+        }
+    }
+
+    fn scan_file(&mut self, from: &str, file: &str) {
+        if file.ends_with(".css") {
+            self.scan_style(from, file);
+        } else if file.ends_with(".swan") {
+            self.scan_template(from, file);
+        } else {
+            self.scan_script(from, file);
+        }
+    }
+}
+
+impl DependencyGraph {
+    /// 读取 `app.json` 和项目中的配置、js、css、模板文件，构建依赖图
+    pub fn build(root: &str) -> Result<Self, ParseError> {
+        let root_path = Path::new(root);
+        let app = parse_app(&read_json(&root_path.join("app.json"))?);
+        let resolver = Resolver::new(root);
+        let mut builder = Builder {
+            root: root_path,
+            canonical_root: root_path.canonicalize().ok(),
+            graph: DependencyGraph::default(),
+            edges: BTreeSet::new(),
+            units: HashMap::new(),
+            files: VecDeque::new(),
+            resolver: resolver.clone(),
+        };
+        builder
+            .graph
+            .nodes
+            .insert(String::from("app"), NodeKind::App);
+
+        // 未指定类型的页面和组件按配置中的 `component` 判断
+        let mut queue: VecDeque<(String, Option<NodeKind>)> = VecDeque::new();
+        let pages = app
+            .pages
+            .iter()
+            .chain(app.sub_packages.iter().flat_map(|p| p.pages.iter()));
+        for page in pages {
+            builder.add_edge("app", page, EdgeKind::Page);
+            queue.push_back((page.clone(), Some(NodeKind::Page)));
+        }
+        for component_path in app.using_components.values() {
+            if let Ok(ComponentTarget::Local { path, .. }) = resolver.resolve("app", component_path)
+            {
+                builder.add_edge("app", &path, EdgeKind::Component);
+                queue.push_back((path, Some(NodeKind::Component)));
+            }
+        }
+        let mut discovered = vec![];
+        discover_units(root_path, Path::new(""), &mut discovered);
+        queue.extend(discovered.into_iter().map(|path| (path, None)));
+
+        while let Some((path, kind)) = queue.pop_front() {
+            if builder.units.contains_key(&path) {
+                continue;
+            }
+            let files = find_files(&root_path.join(&path));
+            let config = files.config.as_ref().and_then(|c| parse_config(c).ok());
+            let kind = match (kind, &config) {
+                (Some(kind), _) => kind,
+                (None, Some(config)) if config.component => NodeKind::Component,
+                (None, _) => NodeKind::Page,
+            };
+            if let Some(config) = &config {
+                for component in resolver
+                    .resolve_components(&path, config)
+                    .components
+                    .values()
+                {
+                    if let Some(ComponentTarget::Local { path: target, .. }) = &component.target {
+                        builder.add_edge(&path, target, EdgeKind::Component);
+                        queue.push_back((target.clone(), Some(NodeKind::Component)));
+                    }
+                }
+            }
+            builder.graph.nodes.insert(path.clone(), kind);
+            builder.units.insert(path, files);
+        }
+
+        let mut unit_files: Vec<(String, String)> = vec![];
+        for (path, files) in &builder.units {
+            let own = files
+                .script
+                .iter()
+                .chain(&files.style)
+                .chain(&files.template);
+            for file in own {
+                if let Some(file) = builder.relative(Path::new(file)) {
+                    unit_files.push((path.clone(), file));
+                }
+            }
+        }
+        unit_files.sort();
+        for (path, file) in unit_files {
+            builder.scan_file(&path, &file);
+        }
+        while let Some(file) = builder.files.pop_front() {
+            builder.scan_file(&file, &file);
+        }
+
+        let mut graph = builder.graph;
+        graph.edges = builder.edges.into_iter().collect();
+        Ok(graph)
+    }
+
+    fn adjacency(&self, forward: bool) -> HashMap<&str, Vec<&str>> {
+        let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            let (from, to) = if forward {
+                (&edge.from, &edge.to)
+            } else {
+                (&edge.to, &edge.from)
+            };
+            adjacency.entry(from).or_default().push(to);
+        }
+        adjacency
+    }
+
+    fn walk(&self, id: &str, transitive: bool, forward: bool) -> Vec<String> {
+        let adjacency = self.adjacency(forward);
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(current) = queue.pop_front() {
+            for next in adjacency.get(current).into_iter().flatten() {
+                if visited.insert(*next) && transitive {
+                    queue.push_back(next);
+                }
+            }
+        }
+        visited.remove(id);
+        visited.into_iter().map(String::from).collect()
+    }
+
+    /// `id` 依赖的节点，`transitive` 为 `true` 时包含间接依赖
+    pub fn dependencies(&self, id: &str, transitive: bool) -> Vec<String> {
+        self.walk(id, transitive, true)
+    }
+
+    /// 依赖 `id` 的节点，`transitive` 为 `true` 时包含间接依赖，用于评估修改的影响范围
+    pub fn dependents(&self, id: &str, transitive: bool) -> Vec<String> {
+        self.walk(id, transitive, false)
+    }
+
+    /// 循环依赖，每个循环为一个强连通分量，包含引用自身的节点
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let forward = self.adjacency(true);
+        let backward = self.adjacency(false);
+
+        // 第一遍记录节点的完成顺序，使用显式的栈避免递归过深
+        let mut visited = HashSet::new();
+        let mut order = vec![];
+        for start in self.nodes.keys() {
+            if !visited.insert(start.as_str()) {
+                continue;
+            }
+            let mut stack = vec![(start.as_str(), 0)];
+            while let Some((node, index)) = stack.pop() {
+                match forward.get(node).and_then(|list| list.get(index)) {
+                    Some(next) => {
+                        stack.push((node, index + 1));
+                        if visited.insert(next) {
+                            stack.push((next, 0));
+                        }
+                    }
+                    None => order.push(node),
+                }
+            }
+        }
+
+        // 第二遍按完成顺序倒序在反向图上遍历，每次遍历到的节点为一个强连通分量
+        let mut assigned = HashSet::new();
+        let mut cycles = vec![];
+        for start in order.into_iter().rev() {
+            if !assigned.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for next in backward.get(node).into_iter().flatten() {
+                    if assigned.insert(next) {
+                        component.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            let self_loop = forward.get(start).is_some_and(|list| list.contains(&start));
+            if component.len() > 1 || self_loop {
+                component.sort_unstable();
+                cycles.push(component.into_iter().map(String::from).collect());
+            }
+        }
+        cycles.sort();
+        cycles
+    }
+
+    /// 从 `app.json` 出发无法到达的节点，即未被使用的页面、组件和文件
+    pub fn unreachable(&self) -> Vec<String> {
+        let reachable: HashSet<String> = self.dependencies("app", true).into_iter().collect();
+        self.nodes
+            .keys()
+            .filter(|id| id.as_str() != "app" && !reachable.contains(*id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn report(&self) -> GraphReport<'_> {
+        GraphReport {
+            graph: self,
+            cycles: self.cycles(),
+            unreachable: self.unreachable(),
+        }
+    }
+
+    /// 输出 Graphviz DOT 格式
+    pub fn to_dot(&self) -> String {
+        let quote = |id: &str| format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut output = String::from("digraph dependencies {\n    rankdir=LR;\n");
+        for (id, kind) in &self.nodes {
+            let shape = match kind {
+                NodeKind::App => "doubleoctagon",
+                NodeKind::Page => "box",
+                NodeKind::Component => "ellipse",
+                NodeKind::Script => "note",
+                NodeKind::Style => "tab",
+                NodeKind::Template => "component",
+            };
+            output.push_str(&format!("    {} [shape={}];\n", quote(id), shape));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::Page | EdgeKind::Component => "solid",
+                EdgeKind::Behavior => "bold",
+                EdgeKind::CssImport
+                | EdgeKind::Require
+                | EdgeKind::TemplateImport
+                | EdgeKind::TemplateInclude
+                | EdgeKind::Module => "dashed",
+            };
+            output.push_str(&format!(
+                "    {} -> {} [label=\"{:?}\", style={}];\n",
+                quote(&edge.from),
+                quote(&edge.to),
+                edge.kind,
+                style
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+}
+
+#[test]
+fn test_dependency_graph() {
+    let graph = DependencyGraph::build("test/fixtures/project").unwrap();
+    assert_eq!(graph.nodes["components/tree/tree"], NodeKind::Component);
+    assert_eq!(graph.nodes["utils/format.js"], NodeKind::Script);
+    assert_eq!(graph.nodes["common.css"], NodeKind::Style);
+    assert_eq!(graph.nodes["templates/log.swan"], NodeKind::Template);
+    assert!(!graph.nodes.contains_key("components/missing/missing"));
+
+    let edges: Vec<(&str, &str, EdgeKind)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.from == "components/card/card" || e.from == "pages/index/index")
+        .map(|e| (e.from.as_str(), e.to.as_str(), e.kind))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            (
+                "components/card/card",
+                "behaviors/expandable.js",
+                EdgeKind::Behavior
+            ),
+            ("components/card/card", "common.css", EdgeKind::CssImport),
+            (
+                "components/card/card",
+                "components/avatar/avatar",
+                EdgeKind::Component
+            ),
+            ("pages/index/index", "common.css", EdgeKind::CssImport),
+            (
+                "pages/index/index",
+                "components/card/card",
+                EdgeKind::Component
+            ),
+            (
+                "pages/index/index",
+                "miniprogram_npm/ui-kit/cell/cell",
+                EdgeKind::Component
+            ),
+            (
+                "pages/index/index",
+                "node_modules/@demo/ui/miniprogram_dist/common/base.css",
+                EdgeKind::CssImport
+            ),
+            ("pages/index/index", "utils/format.js", EdgeKind::Require),
+        ]
+    );
+
+    assert_eq!(
+        graph.dependencies("utils/format.js", false),
+        vec!["utils/date.js"]
+    );
+    assert_eq!(
+        graph.dependents("utils/format.js", false),
+        vec!["behaviors/expandable.js", "pages/index/index"]
+    );
+    assert_eq!(
+        graph.dependents("components/avatar/avatar", true),
+        vec![
+            "app",
+            "components/card/card",
+            "pages/index/index",
+            "sub/pages/detail/detail"
+        ]
+    );
+    assert_eq!(
+        graph.dependents("templates/log.swan", false),
+        vec!["pages/logs/logs"]
+    );
+    assert!(graph.edges.contains(&GraphEdge {
+        from: String::from("pages/logs/logs"),
+        to: String::from("templates/log.swan"),
+        kind: EdgeKind::TemplateImport,
+    }));
+    assert_eq!(
+        graph.dependencies("pages/logs/logs", false),
+        vec![
+            "node_modules/@demo/ui/miniprogram_dist/tag/tag",
+            "templates/log.swan",
+            "utils/log.filter.js"
+        ]
+    );
+    assert_eq!(graph.cycles(), vec![vec!["components/tree/tree"]]);
+    assert_eq!(graph.unreachable(), vec!["components/tree/tree"]);
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph dependencies {"));
+    assert!(dot.contains(
+        "\"components/tree/tree\" -> \"components/tree/tree\" [label=\"Component\", style=solid];"
+    ));
+    let json = serde_json::to_string(&graph.report()).unwrap();
+    assert!(json.contains(r#""unreachable":["components/tree/tree"]"#));
+}
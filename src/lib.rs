@@ -1,177 +1,487 @@
+//! 解析小程序 Page、Component 组件中的变量信息和 css 类名，供代码提示使用
+//!
+//! ```
+//! use component_parser::{ParseOptions, Parser};
+//!
+//! let parser = Parser::new();
+//! let meta = parser.parse_script("test/fixtures/page.js").unwrap();
+//! assert_eq!(meta.data.len(), 4);
+//!
+//! // 不使用进程内缓存，解析已读取的源码
+//! let parser = ParseOptions::new().cache(false).build();
+//! let meta = parser
+//!     .parse_script_source("page.js", "Page({data: {a: 1}, onLoad() {}})")
+//!     .unwrap();
+//! assert_eq!(meta.data[0].name, "a");
+//!
+//! let result = parser.parse_css_files(&[String::from("test/fixtures/page.css")]);
+//! assert_eq!(result.summary.failed, 0);
+//! ```
+//!
+//! 默认开启的 `node` feature 提供 node 绑定，作为 Rust 库使用时可以关闭。
+//! `wasm` feature 提供 WebAssembly 绑定，在浏览器中解析内存中的源码。
+
 #[macro_use]
 extern crate serde_derive;
 extern crate swc_common;
 extern crate swc_ecma_parser;
 
+// `regex!` 宏在 parser 中定义，需要在使用它的模块之前声明
+#[macro_use]
 mod parser;
-use neon::prelude::*;
-use parser::{parse_component, parse_css_class, ComponentMeta, CssMeta};
-use std::collections::HashMap;
-use std::sync::mpsc::channel;
-use std::thread;
-
-/// 解析单个文件
-fn parse_script(mut cx: FunctionContext) -> JsResult<JsString> {
-    let file_path: Handle<JsString> = cx.argument(0)?;
-    if let Ok(result) = parse_component(&file_path.value(&mut cx)) {
-        let result = serde_json::to_string(&result).unwrap();
-        return Ok(cx.string(result));
-    }
-    cx.throw_error("parse script error!")
-}
-
-fn parse_script_files_with_thread(file_paths: &Vec<String>) -> HashMap<String, ComponentMeta> {
-    let mut result_map: HashMap<String, ComponentMeta> = HashMap::new();
-    let size = file_paths.len();
-    let mut threads = vec![];
-    let (sender, receiver) = channel();
-    for i in 0..size {
-        let sender = sender.clone();
-        let file_path = file_paths.get(i).unwrap().clone();
-        threads.push(thread::spawn(move || {
-            if let Ok(meta) = parse_component(&file_path) {
-                sender.send((file_path, meta)).unwrap();
-            }
-        }));
+
+mod analyzer;
+mod batch;
+mod cache;
+mod catalog;
+mod graph;
+#[cfg(feature = "node")]
+mod node;
+mod npm;
+mod pool;
+mod project;
+mod resolver;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+// 公开的 API 只有 `Parser` 及以下类型和函数，各模块中的其他函数是内部实现
+pub use analyzer::{
+    Analysis, AnalysisResult, AttributeCompletion, AttributeKind, ClassDefinitionMeta,
+    EventBindingMeta, TemplateClassMeta, TemplateDefinitionMeta, TemplateImportKind,
+    TemplateImportMeta, TemplateModuleMeta, TemplateUsageMeta, VariableKind, VariableMeta,
+};
+use analyzer::{AnalysisSource, UnitStyles};
+pub use batch::{BatchContext, BatchResult, BatchSummary, FileResult, ProgressFn};
+pub use cache::{
+    clear as clear_cache, invalidate as invalidate_cache, load_index as load_cache,
+    save_index as save_cache, set_limits as set_cache_limits, IndexStats,
+};
+pub use catalog::{catalog, BuiltinAttribute, BuiltinComponent, BuiltinEvent, Catalog};
+pub use graph::{DependencyGraph, EdgeKind, GraphEdge, GraphReport, NodeKind};
+pub use parser::{
+    AliasMeta, AttributeMeta, BindingKind, BindingMeta, ClassUsageMeta, ComponentGenericMeta,
+    ComponentMeta, ComponentType, ConfigKeyMeta, ConfigMeta, CssClassMeta, CssMeta, DataMeta,
+    Diagnostic, ElementMeta, EventMeta, ExpressionMeta, FilterFunctionMeta, FilterModuleMeta,
+    ForMeta, InterpolationMeta, Location, MethodMeta, ParseError, ParseErrorKind, Position,
+    PropertyMeta, PropertyValue, ReferenceMeta, Severity, TemplateBindings, TemplateMeta,
+    TemplateNode, UsingComponentMeta, ValuePart, MAX_NESTING_DEPTH,
+};
+pub use pool::{default_concurrency, set_concurrency};
+pub use project::{AppMeta, ProjectMeta, ProjectUnit, SubPackage, UnitFiles, UnitKind};
+pub use resolver::{ComponentTarget, ResolvedComponent, ResolvedComponents, Resolver};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// 解析选项，通过 `build` 创建 `Parser`
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    cache: bool,
+    follow_css_imports: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            cache: true,
+            follow_css_imports: true,
+        }
     }
-    drop(sender);
-    // 接受数据
-    for (file_path, meta) in receiver {
-        result_map.insert(file_path, meta);
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
     }
-    // 等待进程完毕
-    for t in threads {
-        t.join().unwrap();
+
+    /// 是否使用进程内缓存，默认开启，缓存在所有 `Parser` 之间共享
+    pub fn cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
     }
-    result_map
-}
 
-/// 解析一组 js 文件
-fn parse_script_files(mut cx: FunctionContext) -> JsResult<JsString> {
-    let arg0: Handle<JsArray> = cx.argument(0)?;
-    let vec: Vec<Handle<JsValue>> = arg0.to_vec(&mut cx).unwrap();
-    let file_paths: Vec<String> = vec
-        .iter()
-        .map(|&v| v.to_string(&mut cx).unwrap().value(&mut cx))
-        .collect();
+    /// 批量解析 css 时是否同时解析 `@import` 的文件，默认开启
+    pub fn follow_css_imports(mut self, follow_css_imports: bool) -> Self {
+        self.follow_css_imports = follow_css_imports;
+        self
+    }
 
-    let result_map = parse_script_files_with_thread(&file_paths);
-    let result = serde_json::to_string(&result_map).unwrap();
-    return Ok(cx.string(result));
+    pub fn build(self) -> Parser {
+        Parser { options: self }
+    }
 }
 
-/// 解析单个 css 文件
-fn parse_css(mut cx: FunctionContext) -> JsResult<JsString> {
-    let file_path: Handle<JsString> = cx.argument(0)?;
-    if let Ok(result) = parse_css_class(&file_path.value(&mut cx)) {
-        let result = serde_json::to_string(&result).unwrap();
-        return Ok(cx.string(result));
-    }
-    cx.throw_error("parse css error!")
+/// js 和 css 解析器，解析都在共享线程池中执行，单个文件的错误不会影响其他文件
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Parser {
+    options: ParseOptions,
 }
 
-fn parse_css_files_recrusive(file_paths: &Vec<String>, result_map: &mut HashMap<String, CssMeta>) {
-    let size = file_paths.len();
-    let mut threads = vec![];
-    let (sx, rx) = channel();
+impl Parser {
+    /// 使用默认选项创建解析器
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    for i in 0..size {
-        let sender = sx.clone();
-        let file_path = file_paths.get(i).unwrap().clone();
-        threads.push(thread::spawn(move || {
-            if let Ok(meta) = parse_css_class(&file_path) {
-                sender.send((file_path, meta)).unwrap();
-            }
-        }));
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
     }
-    drop(sx);
 
-    let mut import_paths: Vec<String> = vec![];
-    // 接受数据
-    for (file_path, meta) in rx {
-        if let Some(imports) = &meta.imports {
-            for import_file_path in imports {
-                if !result_map.contains_key(import_file_path) {
-                    import_paths.push(import_file_path.to_string());
-                }
-            }
+    fn parse_script_file(&self, file_path: &str) -> Result<ComponentMeta, ParseError> {
+        if self.options.cache {
+            cache::parse_component_cached(file_path)
+        } else {
+            parser::parse_component(file_path)
         }
-        result_map.insert(file_path, meta);
     }
 
-    // 等待线程完毕
-    for t in threads {
-        t.join().unwrap();
+    fn parse_css_file(&self, file_path: &str) -> Result<CssMeta, ParseError> {
+        if self.options.cache {
+            cache::parse_css_class_cached(file_path)
+        } else {
+            parser::parse_css_class(file_path)
+        }
     }
 
-    if import_paths.len() > 0 {
-        parse_css_files_recrusive(&import_paths, result_map);
+    fn parse_template_file(&self, file_path: &str) -> Result<TemplateMeta, ParseError> {
+        if self.options.cache {
+            cache::parse_template_cached(file_path)
+        } else {
+            parser::parse_template(file_path)
+        }
     }
-}
 
-fn parse_css_files_with_thread(file_paths: &Vec<String>) -> HashMap<String, CssMeta> {
-    let mut result_map: HashMap<String, CssMeta> = HashMap::new();
-    parse_css_files_recrusive(file_paths, &mut result_map);
-    result_map
+    fn parse_config_file(&self, file_path: &str) -> Result<ConfigMeta, ParseError> {
+        if self.options.cache {
+            cache::parse_config_cached(file_path)
+        } else {
+            parser::parse_config(file_path)
+        }
+    }
+
+    /// 解析单个 js 文件
+    pub fn parse_script(&self, file_path: &str) -> Result<ComponentMeta, ParseError> {
+        // 在解析线程中执行，调用方线程的栈不一定足以解析深层嵌套的代码
+        pool::install(|| self.parse_script_file(file_path))
+    }
+
+    /// 解析已读取的 js 源码，不使用缓存，`file_path` 仅用于错误信息
+    pub fn parse_script_source(
+        &self,
+        file_path: &str,
+        source: &str,
+    ) -> Result<ComponentMeta, ParseError> {
+        pool::install(|| parser::parse_component_source(file_path, source.to_string()))
+    }
+
+    /// 解析单个 css 文件
+    pub fn parse_css(&self, file_path: &str) -> Result<CssMeta, ParseError> {
+        pool::install(|| self.parse_css_file(file_path))
+    }
+
+    /// 解析已读取的 css 源码，不使用缓存，`file_path` 用于定位 `@import` 的相对路径
+    pub fn parse_css_source(&self, file_path: &str, text: &str) -> Result<CssMeta, ParseError> {
+        parser::parse_css_source(file_path, text)
+    }
+
+    /// 解析页面或组件的 json 配置
+    pub fn parse_config(&self, file_path: &str) -> Result<ConfigMeta, ParseError> {
+        self.parse_config_file(file_path)
+    }
+
+    /// 解析已读取的 json 配置，`file_path` 仅用于错误信息
+    pub fn parse_config_source(
+        &self,
+        file_path: &str,
+        text: &str,
+    ) -> Result<ConfigMeta, ParseError> {
+        parser::parse_config_source(file_path, text)
+    }
+
+    /// 解析 swan 模板，模板语法错误记录在结果的 `diagnostics` 中
+    pub fn parse_template(&self, file_path: &str) -> Result<TemplateMeta, ParseError> {
+        self.parse_template_file(file_path)
+    }
+
+    /// 解析已读取的 swan 模板，`file_path` 仅用于错误信息
+    pub fn parse_template_source(
+        &self,
+        file_path: &str,
+        text: &str,
+    ) -> Result<TemplateMeta, ParseError> {
+        parser::parse_template_source(file_path, text)
+    }
+
+    /// 解析 filter 或 sjs 模块，得到导出的函数
+    pub fn parse_filter(&self, file_path: &str) -> Result<FilterModuleMeta, ParseError> {
+        parser::parse_filter(file_path)
+    }
+
+    /// 解析已读取的 filter 或 sjs 模块，`file_path` 仅用于错误信息
+    pub fn parse_filter_source(
+        &self,
+        file_path: &str,
+        text: &str,
+    ) -> Result<FilterModuleMeta, ParseError> {
+        parser::parse_filter_source(file_path, text.to_string())
+    }
+
+    /// 解析 swan 模板中的绑定表达式，得到其中引用的变量，无法解析的表达式记录在 `diagnostics` 中
+    pub fn parse_template_bindings(&self, file_path: &str) -> Result<TemplateBindings, ParseError> {
+        parser::parse_template(file_path).map(|template| parser::extract_bindings(&template))
+    }
+
+    /// 分析页面或组件的模板，`unit_path` 为相对项目根目录、不带扩展名的路径，分析的类型和参数见 `Analysis`，
+    /// 只读取该分析需要的同名 js、css 文件和 `usingComponents` 中的组件
+    pub fn analyze(
+        &self,
+        root: &str,
+        unit_path: &str,
+        analysis: &Analysis,
+    ) -> Result<AnalysisResult, ParseError> {
+        analyzer::analyze(&UnitSource::new(self, root, unit_path), analysis)
+    }
+
+    /// 读取 `app.json`，解析项目中所有页面和组件
+    pub fn parse_project(&self, root: &str) -> Result<ProjectMeta, ParseError> {
+        self.parse_project_with_context(root, &BatchContext::default())
+    }
+
+    /// 解析项目中所有页面和组件，通过 `batch` 取消解析或接收进度通知，
+    /// 取消后只返回已发现的页面和组件及其配置
+    pub fn parse_project_with_context(
+        &self,
+        root: &str,
+        batch: &BatchContext,
+    ) -> Result<ProjectMeta, ParseError> {
+        project::parse_project_with_context(self, root, batch)
+    }
+
+    /// 并行解析一组 js 文件
+    pub fn parse_script_files(&self, file_paths: &[String]) -> BatchResult<ComponentMeta> {
+        self.parse_script_files_with_context(file_paths, &BatchContext::default())
+    }
+
+    /// 并行解析一组 js 文件，通过 `batch` 取消解析或接收进度通知
+    pub fn parse_script_files_with_context(
+        &self,
+        file_paths: &[String],
+        batch: &BatchContext,
+    ) -> BatchResult<ComponentMeta> {
+        batch::parse_files(file_paths, batch, |file_path| {
+            self.parse_script_file(file_path)
+        })
+    }
+
+    /// 并行解析一组 swan 模板，模板语法错误记录在各个结果的 `diagnostics` 中
+    pub fn parse_template_files(&self, file_paths: &[String]) -> BatchResult<TemplateMeta> {
+        self.parse_template_files_with_context(file_paths, &BatchContext::default())
+    }
+
+    /// 并行解析一组 swan 模板，通过 `batch` 取消解析或接收进度通知
+    pub fn parse_template_files_with_context(
+        &self,
+        file_paths: &[String],
+        batch: &BatchContext,
+    ) -> BatchResult<TemplateMeta> {
+        batch::parse_files(file_paths, batch, |file_path| {
+            self.parse_template_file(file_path)
+        })
+    }
+
+    /// 并行解析一组 css 文件，开启 `follow_css_imports` 时结果中包含所有被 `@import` 的文件
+    pub fn parse_css_files(&self, file_paths: &[String]) -> BatchResult<CssMeta> {
+        self.parse_css_files_with_context(file_paths, &BatchContext::default())
+    }
+
+    /// 并行解析一组 css 文件，通过 `batch` 取消解析或接收进度通知
+    pub fn parse_css_files_with_context(
+        &self,
+        file_paths: &[String],
+        batch: &BatchContext,
+    ) -> BatchResult<CssMeta> {
+        let parse = |file_path: &str| self.parse_css_file(file_path);
+        if self.options.follow_css_imports {
+            batch::parse_css_files(file_paths, batch, parse)
+        } else {
+            batch::parse_files(file_paths, batch, parse)
+        }
+    }
 }
 
-/// 解析一组 css 文件
-fn parse_css_files(mut cx: FunctionContext) -> JsResult<JsString> {
-    let arg0: Handle<JsArray> = cx.argument(0)?;
-    let vec: Vec<Handle<JsValue>> = arg0.to_vec(&mut cx).unwrap();
-    let file_paths: Vec<String> = vec
-        .iter()
-        .map(|&v| v.to_string(&mut cx).unwrap().value(&mut cx))
-        .collect();
+/// 从项目中读取的页面或组件的同名文件
+struct UnitSource<'a> {
+    parser: &'a Parser,
+    root: &'a str,
+    unit_path: &'a str,
+    base: String,
+    files: UnitFiles,
+}
 
-    let result_map = parse_css_files_with_thread(&file_paths);
-    let result = serde_json::to_string(&result_map).unwrap();
-    return Ok(cx.string(result));
+impl<'a> UnitSource<'a> {
+    fn new(parser: &'a Parser, root: &'a str, unit_path: &'a str) -> Self {
+        let base = Path::new(root).join(unit_path);
+        let files = resolver::find_files(&base);
+        UnitSource {
+            parser,
+            root,
+            unit_path,
+            base: base.to_string_lossy().into_owned(),
+            files,
+        }
+    }
 }
 
-#[neon::main]
-fn main(mut cx: ModuleContext) -> NeonResult<()> {
-    cx.export_function("parseScript", parse_script)?;
-    cx.export_function("parseScriptFiles", parse_script_files)?;
-    cx.export_function("parseCss", parse_css)?;
-    cx.export_function("parseCssFiles", parse_css_files)?;
-    Ok(())
+impl AnalysisSource for UnitSource<'_> {
+    fn template_path(&self) -> String {
+        format!("{}.swan", self.unit_path)
+    }
+
+    fn template(&self) -> Result<TemplateMeta, ParseError> {
+        let template_path = self
+            .files
+            .template
+            .as_ref()
+            .ok_or_else(|| ParseError::not_found(&format!("{}.swan", self.base)))?;
+        self.parser.parse_template(template_path)
+    }
+
+    fn component(&self) -> Result<ComponentMeta, ParseError> {
+        let script_path = self
+            .files
+            .script
+            .as_ref()
+            .ok_or_else(|| ParseError::not_found(&format!("{}.js", self.base)))?;
+        self.parser.parse_script(script_path)
+    }
+
+    /// 组件的样式是隔离的，只有页面可以使用 `app.css`
+    fn styles(&self) -> Result<UnitStyles, ParseError> {
+        let is_component = self
+            .files
+            .config
+            .as_ref()
+            .and_then(|config| self.parser.parse_config(config).ok())
+            .is_some_and(|config| config.component);
+        let mut style_paths: Vec<String> = self.files.style.iter().cloned().collect();
+        let app_style = Path::new(self.root).join("app.css");
+        if !is_component && app_style.is_file() {
+            style_paths.push(app_style.to_string_lossy().into_owned());
+        }
+        let styles = self.parser.parse_css_files(&style_paths).files;
+        Ok((style_paths, styles))
+    }
+
+    /// `usingComponents` 中项目内的组件，js 无法解析的组件忽略
+    fn components(&self) -> Result<BTreeMap<String, ComponentMeta>, ParseError> {
+        let resolver = Resolver::new(self.root);
+        let resolved = resolver.resolve_unit(self.unit_path)?;
+        let mut result = BTreeMap::new();
+        // 页面或组件自身的配置覆盖同名的全局组件
+        let global = resolver.resolve_global();
+        for (name, component) in global.components.into_iter().chain(resolved.components) {
+            if let Some(ComponentTarget::Local { files, .. }) = &component.target {
+                if let Some(Ok(meta)) = files.script.as_ref().map(|s| self.parser.parse_script(s)) {
+                    result.insert(name, meta);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn link(
+        &self,
+        template: &TemplateMeta,
+    ) -> (Vec<TemplateImportMeta>, Vec<TemplateDefinitionMeta>) {
+        project::link_template(
+            &Resolver::new(self.root),
+            Path::new(self.root),
+            &self.template_path(),
+            template,
+            &mut project::LinkCache::default(),
+        )
+    }
+
+    fn modules(&self, template: &TemplateMeta) -> Vec<TemplateModuleMeta> {
+        project::load_modules(
+            &Resolver::new(self.root),
+            Path::new(self.root),
+            &self.template_path(),
+            template,
+            &mut project::LinkCache::default(),
+        )
+    }
 }
 
 #[test]
-fn test_parse_script_files_with_thread() {
+fn test_parse_script_files() {
     let file_paths = vec![
         String::from("test/fixtures/page.js"),
         String::from("test/fixtures/component.js"),
     ];
-    let results = parse_script_files_with_thread(&file_paths);
-    let result = results.get("test/fixtures/component.js").unwrap();
+    let results = Parser::new().parse_script_files(&file_paths);
+    assert_eq!(results.summary.total, 2);
+    assert_eq!(results.summary.succeeded, 2);
+    let result = results.files["test/fixtures/component.js"].meta().unwrap();
     assert_eq!(result.data.len(), 4);
     assert_eq!(result.methods.len(), 6);
     assert_eq!(result.properties.len(), 5);
 
-    let result = results.get("test/fixtures/page.js").unwrap();
+    let result = results.files["test/fixtures/page.js"].meta().unwrap();
     assert_eq!(result.data.len(), 4);
     assert_eq!(result.methods.len(), 6);
     assert_eq!(result.properties.len(), 0);
 
-    // with not found
-    let file_paths = vec![String::from("test/fixtures/component-notfound.js")];
-    let results = parse_css_files_with_thread(&file_paths);
-    assert_eq!(results.len(), 0);
+    // with not found and syntax error
+    let file_paths = vec![
+        String::from("test/fixtures/component-notfound.js"),
+        String::from("test/fixtures/component.error-js"),
+        String::from("test/fixtures/error.js"),
+    ];
+    let results = Parser::new().parse_script_files(&file_paths);
+    assert_eq!(results.summary.total, 3);
+    assert_eq!(results.summary.failed, 3);
+    let error = results.files["test/fixtures/component-notfound.js"]
+        .error()
+        .unwrap();
+    assert_eq!(error.kind, ParseErrorKind::NotFound);
+    let error = results.files["test/fixtures/component.error-js"]
+        .error()
+        .unwrap();
+    assert_eq!(error.kind, ParseErrorKind::SyntaxError);
+    let error = results.files["test/fixtures/error.js"].error().unwrap();
+    assert_eq!(error.kind, ParseErrorKind::ComponentNotFound);
 }
 
 #[test]
-fn test_parse_css_files_with_thread() {
+fn test_parse_template_files() {
+    let file_paths = vec![
+        String::from("test/fixtures/component.swan"),
+        String::from("test/fixtures/error.swan"),
+        String::from("test/fixtures/notfound.swan"),
+    ];
+    let results = Parser::new().parse_template_files(&file_paths);
+    assert_eq!(results.summary.total, 3);
+    assert_eq!(results.summary.failed, 1);
+    let result = results.files["test/fixtures/error.swan"].meta().unwrap();
+    assert_eq!(result.diagnostics[0].message, "unclosed tag `<text>`");
+    let error = results.files["test/fixtures/notfound.swan"]
+        .error()
+        .unwrap();
+    assert_eq!(error.kind, ParseErrorKind::NotFound);
+}
+
+#[test]
+fn test_parse_css_files() {
     let file_paths = vec![String::from("test/fixtures/component.css")];
-    let results = parse_css_files_with_thread(&file_paths);
-    let result = results.get("test/fixtures/component.css").unwrap();
+    let results = Parser::new().parse_css_files(&file_paths);
+    let result = results.files["test/fixtures/component.css"].meta().unwrap();
     assert_eq!(result.classes.len(), 10);
 
     // with not found
     let file_paths = vec![String::from("test/fixtures/component-notfound.css")];
-    let results = parse_css_files_with_thread(&file_paths);
-    assert_eq!(results.len(), 0);
+    let results = Parser::new().parse_css_files(&file_paths);
+    assert_eq!(results.summary.total, 1);
+    assert_eq!(results.summary.failed, 1);
 
     // with imported css
     let file_paths = vec![std::path::PathBuf::from("test/fixtures/import.css")
@@ -180,8 +490,9 @@ fn test_parse_css_files_with_thread() {
         .to_str()
         .unwrap()
         .to_string()];
-    let results = parse_css_files_with_thread(&file_paths);
-    assert_eq!(results.len(), 3);
+    let results = Parser::new().parse_css_files(&file_paths);
+    assert_eq!(results.summary.total, 3);
+    assert_eq!(results.summary.succeeded, 3);
 
     let css_path = std::path::PathBuf::from("test/fixtures/import.css")
         .canonicalize()
@@ -189,7 +500,7 @@ fn test_parse_css_files_with_thread() {
         .to_str()
         .unwrap()
         .to_string();
-    let result = results.get(&css_path).unwrap();
+    let result = results.files[&css_path].meta().unwrap();
     assert_eq!(result.classes.len(), 1);
 
     let css_path = std::path::PathBuf::from("test/fixtures/component.css")
@@ -198,7 +509,7 @@ fn test_parse_css_files_with_thread() {
         .to_str()
         .unwrap()
         .to_string();
-    let result = results.get(&css_path).unwrap();
+    let result = results.files[&css_path].meta().unwrap();
     assert_eq!(result.classes.len(), 10);
 
     let css_path = std::path::PathBuf::from("test/fixtures/page.css")
@@ -207,6 +518,229 @@ fn test_parse_css_files_with_thread() {
         .to_str()
         .unwrap()
         .to_string();
-    let result = results.get(&css_path).unwrap();
-    assert_eq!(result.classes.len() > 10, true);
+    let result = results.files[&css_path].meta().unwrap();
+    assert!(result.classes.len() > 10);
+}
+
+#[test]
+fn test_parse_hostile_files() {
+    let mut script_paths = vec![];
+    let mut css_paths = vec![];
+    for entry in std::fs::read_dir("test/fixtures/hostile").unwrap() {
+        let path = entry.unwrap().path().to_string_lossy().into_owned();
+        if path.ends_with(".css") {
+            css_paths.push(path);
+        } else {
+            script_paths.push(path);
+        }
+    }
+
+    let results = Parser::new().parse_script_files(&script_paths);
+    assert_eq!(results.summary.total, script_paths.len());
+    for (file_path, result) in &results.files {
+        if let Some(error) = result.error() {
+            assert_ne!(error.kind, ParseErrorKind::InternalError, "{}", file_path);
+        }
+    }
+    let result = &results.files["test/fixtures/hostile/component-wrap-no-args.js"];
+    assert_eq!(result.meta().unwrap().data.len(), 1);
+
+    let results = Parser::new().parse_css_files(&css_paths);
+    assert_eq!(results.summary.failed, 0);
+    let result = results.files["test/fixtures/hostile/unicode.css"]
+        .meta()
+        .unwrap();
+    let names: Vec<&str> = result.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["class-a", "class-b中"]);
+}
+
+#[test]
+fn test_parse_options() {
+    let parser = ParseOptions::new()
+        .cache(false)
+        .follow_css_imports(false)
+        .build();
+    assert!(!parser.options().cache);
+
+    let file_path = std::path::PathBuf::from("test/fixtures/import.css")
+        .canonicalize()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+    let results = parser.parse_css_files(std::slice::from_ref(&file_path));
+    assert_eq!(results.summary.total, 1);
+    assert_eq!(
+        results.files[&file_path]
+            .meta()
+            .unwrap()
+            .imports
+            .as_ref()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let result = parser.parse_script_source("a.js", "Page(wrap())");
+    assert_eq!(result.unwrap_err().kind, ParseErrorKind::ComponentNotFound);
+
+    // 公开的类型可以反序列化
+    let meta = parser.parse_script("test/fixtures/component.js").unwrap();
+    let json = serde_json::to_string(&meta).unwrap();
+    let meta: ComponentMeta = serde_json::from_str(&json).unwrap();
+    assert_eq!(meta.properties.len(), 5);
+}
+
+#[test]
+fn test_component_attributes() {
+    let parser = Parser::new();
+    let root = "test/fixtures/project";
+    let unit_path = "pages/index/index";
+    let attributes = |tag: &str| -> Result<Vec<AttributeCompletion>, ParseError> {
+        let analysis = Analysis::ComponentAttributes {
+            tag: String::from(tag),
+        };
+        match parser.analyze(root, unit_path, &analysis)? {
+            AnalysisResult::Attributes(attributes) => Ok(attributes),
+            result => panic!("unexpected result {:?}", result),
+        }
+    };
+    let names: Vec<String> = attributes("card")
+        .unwrap()
+        .into_iter()
+        .map(|a| a.name)
+        .collect();
+    assert_eq!(names, vec!["title", "name", "value", "bind:toggle"]);
+    let diagnostics = parser
+        .analyze(root, unit_path, &Analysis::CheckComponents)
+        .unwrap()
+        .into_diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "component `g-button` has no property `sise`"
+    );
+    assert_eq!(attributes("g-button").unwrap()[0].name, "type");
+    assert!(attributes("image")
+        .unwrap()
+        .iter()
+        .any(|a| a.name == "mode" && a.values.is_some()));
+    let error = attributes("missing").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ComponentNotFound);
+}
+
+#[test]
+fn test_template_usages() {
+    let parser = Parser::new();
+    let root = "test/fixtures/project";
+    let unit_path = "pages/logs/logs";
+    let result = parser.analyze(root, unit_path, &Analysis::TemplateImports);
+    let Ok(AnalysisResult::Imports(imports)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    assert_eq!(imports[0].path.as_deref(), Some("templates/log.swan"));
+    let result = parser.analyze(root, unit_path, &Analysis::TemplateUsages);
+    let Ok(AnalysisResult::Usages(usages)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    let definition = usages[0].definition.as_ref().unwrap();
+    assert_eq!(definition.file, "templates/log.swan");
+    assert_eq!(definition.params, vec!["time", "message"]);
+    assert!(parser
+        .analyze(root, unit_path, &Analysis::CheckTemplateImports)
+        .unwrap()
+        .into_diagnostics()
+        .is_empty());
+    assert!(parser
+        .analyze(root, "pages/missing/missing", &Analysis::TemplateImports)
+        .is_err());
+}
+
+#[test]
+fn test_template_modules() {
+    let parser = Parser::new();
+    let root = "test/fixtures/project";
+    let unit_path = "pages/logs/logs";
+    let result = parser.analyze(root, unit_path, &Analysis::TemplateModules);
+    let Ok(AnalysisResult::Modules(modules)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    assert_eq!(modules[0].name, "fmt");
+    assert_eq!(modules[0].path.as_deref(), Some("utils/log.filter.js"));
+    let functions = modules[0].functions.as_ref().unwrap();
+    assert_eq!(functions[0].params, vec!["value", "pattern"]);
+    assert!(parser
+        .analyze(root, unit_path, &Analysis::CheckModuleCalls)
+        .unwrap()
+        .into_diagnostics()
+        .is_empty());
+    let analysis = Analysis::ModuleCompletions {
+        position: Position {
+            line: 4,
+            column: 47,
+        },
+    };
+    let result = parser.analyze(root, unit_path, &analysis);
+    let Ok(AnalysisResult::Functions(completions)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    let names: Vec<&str> = completions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["time"]);
+}
+
+#[test]
+fn test_unit_analysis() {
+    let parser = Parser::new();
+    let root = "test/fixtures/project";
+    let unit_path = "pages/index/index";
+    let diagnostics = |analysis: Analysis| {
+        parser
+            .analyze(root, unit_path, &analysis)
+            .unwrap()
+            .into_diagnostics()
+    };
+    assert!(diagnostics(Analysis::CheckTemplate).is_empty());
+    let analysis = Analysis::TemplateVariables {
+        position: Position {
+            line: 2,
+            column: 60,
+        },
+    };
+    let result = parser.analyze(root, unit_path, &analysis);
+    let Ok(AnalysisResult::Variables(variables)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    let names: Vec<&str> = variables.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, vec!["index", "item", "list", "title"]);
+    let result = parser.analyze(root, unit_path, &Analysis::EventBindings);
+    let Ok(AnalysisResult::EventBindings(bindings)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    assert_eq!(bindings[0].method.as_ref().unwrap().name, "onTapCard");
+    let result = parser.analyze(root, unit_path, &Analysis::TemplateClasses);
+    let Ok(AnalysisResult::Classes(classes)) = result else {
+        panic!("unexpected result {:?}", result);
+    };
+    assert_eq!(
+        classes[0].definitions[0].file,
+        "test/fixtures/project/pages/index/index.css"
+    );
+    assert!(diagnostics(Analysis::CheckClasses).is_empty());
+    assert!(diagnostics(Analysis::CheckVersions {
+        version: String::from("1.0.0")
+    })
+    .is_empty());
+    let error = parser
+        .analyze(root, "pages/missing/missing", &Analysis::CheckTemplate)
+        .unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::NotFound);
+
+    // 分析请求和 js 传入的 JSON 一致
+    let analysis: Analysis =
+        serde_json::from_str(r#"{"kind":"checkVersions","version":"1.0.0"}"#).unwrap();
+    assert_eq!(
+        analysis,
+        Analysis::CheckVersions {
+            version: String::from("1.0.0")
+        }
+    );
 }
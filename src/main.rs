@@ -0,0 +1,672 @@
+//! 命令行工具，解析 js、css 和 swan 文件并输出元数据，`--check` 模式下有错误时以非零状态退出，
+//! `graph` 子命令输出项目依赖图
+
+use component_parser::{
+    set_concurrency, Analysis, ComponentMeta, CssMeta, DependencyGraph, Diagnostic, FileResult,
+    ParseErrorKind, ParseOptions, Parser, Severity, TemplateMeta,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "Usage: component-parser [options] <file|dir|glob>...
+       component-parser graph [options] <project>
+
+解析 js 中的 Page、Component 组件、css 类名和 swan 模板，输出解析结果
+
+Options:
+  -f, --format <format>     输出格式：json、ndjson、table，默认 json
+  -c, --check               只输出有错误的文件，有错误时以状态码 1 退出
+  -j, --concurrency <n>     解析线程数，默认为 CPU 核数减 1
+  -h, --help                显示帮助
+  -V, --version             显示版本
+
+目录会递归查找 .js、.css 和 .swan 文件，跳过 node_modules 和隐藏目录。
+js、css 解析失败，或模板中有 error 级别的诊断时视为错误，模板的诊断包括语法错误和同名 js 中
+未声明的变量，warning 级别的诊断只输出不算错误";
+
+const GRAPH_USAGE: &str = "Usage: component-parser graph [options] <project>
+
+输出项目依赖图，包含 usingComponents、behaviors、css @import 和 js require 关系，
+以及循环依赖和未被使用的页面、组件
+
+Options:
+  -f, --format <format>     输出格式：json、dot，默认 json
+  --dependents <id>         每行输出一个依赖该节点的页面、组件或文件
+  --dependencies <id>       每行输出一个该节点依赖的页面、组件或文件
+  --direct                  查询时只输出直接依赖，默认包含间接依赖
+  -h, --help                显示帮助
+
+节点 id 为相对项目根目录的路径，页面和组件不带扩展名，例如 components/card/card";
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Json,
+    Ndjson,
+    Table,
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    format: Format,
+    check: bool,
+    concurrency: usize,
+    inputs: Vec<String>,
+}
+
+/// 解析命令行参数，返回 `Err` 时输出错误信息并退出
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut result = Args {
+        format: Format::Json,
+        check: false,
+        concurrency: 0,
+        inputs: vec![],
+    };
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-V" | "--version" => {
+                println!("component-parser {}", env!("CARGO_PKG_VERSION"));
+                process::exit(0);
+            }
+            "-c" | "--check" => result.check = true,
+            "-f" | "--format" => {
+                result.format = match args.next().as_deref() {
+                    Some("json") => Format::Json,
+                    Some("ndjson") => Format::Ndjson,
+                    Some("table") => Format::Table,
+                    Some(format) => return Err(format!("unknown format `{}`", format)),
+                    None => return Err(format!("missing value for `{}`", arg)),
+                };
+            }
+            "-j" | "--concurrency" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", arg))?;
+                result.concurrency = value
+                    .parse()
+                    .map_err(|_| format!("invalid concurrency `{}`", value))?;
+            }
+            "--" => result.inputs.extend(args.by_ref()),
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            _ => result.inputs.push(arg),
+        }
+    }
+    if result.inputs.is_empty() {
+        return Err(String::from("no input files"));
+    }
+    Ok(Some(result))
+}
+
+#[derive(Debug, PartialEq)]
+enum GraphQuery {
+    All,
+    Dependents(String),
+    Dependencies(String),
+}
+
+#[derive(Debug, PartialEq)]
+struct GraphArgs {
+    dot: bool,
+    query: GraphQuery,
+    transitive: bool,
+    root: String,
+}
+
+/// 解析 `graph` 子命令的参数
+fn parse_graph_args(args: impl Iterator<Item = String>) -> Result<Option<GraphArgs>, String> {
+    let mut result = GraphArgs {
+        dot: false,
+        query: GraphQuery::All,
+        transitive: true,
+        root: String::new(),
+    };
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-f" | "--format" => {
+                result.dot = match args.next().as_deref() {
+                    Some("json") => false,
+                    Some("dot") => true,
+                    Some(format) => return Err(format!("unknown format `{}`", format)),
+                    None => return Err(format!("missing value for `{}`", arg)),
+                };
+            }
+            "--dependents" | "--dependencies" => {
+                let id = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", arg))?;
+                result.query = if arg == "--dependents" {
+                    GraphQuery::Dependents(id)
+                } else {
+                    GraphQuery::Dependencies(id)
+                };
+            }
+            "--direct" => result.transitive = false,
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            _ if result.root.is_empty() => result.root = arg,
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    if result.root.is_empty() {
+        return Err(String::from("no project root"));
+    }
+    Ok(Some(result))
+}
+
+fn format_graph(graph: &DependencyGraph, args: &GraphArgs) -> String {
+    let ids = match &args.query {
+        GraphQuery::All if args.dot => return graph.to_dot(),
+        GraphQuery::All => {
+            let mut output = serde_json::to_string_pretty(&graph.report()).unwrap_or_default();
+            output.push('\n');
+            return output;
+        }
+        GraphQuery::Dependents(id) => graph.dependents(id, args.transitive),
+        GraphQuery::Dependencies(id) => graph.dependencies(id, args.transitive),
+    };
+    ids.iter().map(|id| format!("{}\n", id)).collect()
+}
+
+fn run_graph(args: impl Iterator<Item = String>) {
+    let args = match parse_graph_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", GRAPH_USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("component-parser: {}\n\n{}", message, GRAPH_USAGE);
+            process::exit(2);
+        }
+    };
+    match DependencyGraph::build(&args.root) {
+        Ok(graph) => print!("{}", format_graph(&graph, &args)),
+        Err(error) => {
+            eprintln!("component-parser: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn is_css(file_path: &str) -> bool {
+    file_path.ends_with(".css")
+}
+
+fn is_template(file_path: &str) -> bool {
+    file_path.ends_with(".swan")
+}
+
+/// 递归查找目录下的 js、css 和 swan 文件
+fn walk_dir(dir: &Path, files: &mut Vec<String>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "node_modules" {
+                walk_dir(&path, files);
+            }
+        } else if name.ends_with(".js") || is_css(&name) || is_template(&name) {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+/// 展开输入的文件、目录和 glob，同一文件只出现一次
+fn collect_files(inputs: &[String]) -> Result<Vec<String>, String> {
+    let mut files = vec![];
+    for input in inputs {
+        if input.contains(['*', '?', '['].as_ref()) {
+            let paths =
+                glob::glob(input).map_err(|e| format!("invalid glob `{}`: {}", input, e))?;
+            for path in paths.flatten() {
+                if path.is_dir() {
+                    walk_dir(&path, &mut files);
+                } else {
+                    files.push(path.to_string_lossy().into_owned());
+                }
+            }
+        } else if Path::new(input).is_dir() {
+            walk_dir(Path::new(input), &mut files);
+        } else {
+            // 文件不存在时保留，解析结果中会给出 NotFound 错误
+            files.push(input.clone());
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    Ok(files)
+}
+
+/// json 输出中的一条记录，文件路径放在最前面
+#[derive(Serialize)]
+struct Record<'a, T> {
+    file: &'a str,
+    #[serde(flatten)]
+    result: &'a FileResult<T>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum EntryRecord<'a> {
+    Script(Record<'a, ComponentMeta>),
+    Css(Record<'a, CssMeta>),
+    Template(Record<'a, TemplateMeta>),
+}
+
+/// 单个文件的解析结果
+enum Entry {
+    Script(FileResult<ComponentMeta>),
+    Css(FileResult<CssMeta>),
+    Template(FileResult<TemplateMeta>),
+}
+
+impl Entry {
+    fn error_kind(&self) -> Option<&ParseErrorKind> {
+        match self {
+            Entry::Script(result) => result.error().map(|e| &e.kind),
+            Entry::Css(result) => result.error().map(|e| &e.kind),
+            Entry::Template(result) => result.error().map(|e| &e.kind),
+        }
+    }
+
+    /// 模板中 error 级别的诊断
+    fn errors(&self) -> Vec<&Diagnostic> {
+        match self {
+            Entry::Template(FileResult::Ok { meta, .. }) => meta
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// `--check` 模式下视为错误，工具函数等非组件 js 文件不算错误
+    fn is_diagnostic(&self) -> bool {
+        !matches!(
+            self.error_kind(),
+            None | Some(ParseErrorKind::ComponentNotFound)
+        ) || !self.errors().is_empty()
+    }
+
+    fn to_record<'a>(&'a self, file: &'a str) -> EntryRecord<'a> {
+        match self {
+            Entry::Script(result) => EntryRecord::Script(Record { file, result }),
+            Entry::Css(result) => EntryRecord::Css(Record { file, result }),
+            Entry::Template(result) => EntryRecord::Template(Record { file, result }),
+        }
+    }
+
+    /// 表格中的一行：类型、data、properties、methods、events、classes、状态
+    fn to_row(&self) -> [String; 7] {
+        let count = |n: usize| n.to_string();
+        let none = || String::from("-");
+        match self {
+            Entry::Script(FileResult::Ok { meta, .. }) => [
+                format!("{:?}", meta.r#type),
+                count(meta.data.len()),
+                count(meta.properties.len()),
+                count(meta.methods.len()),
+                meta.events.as_ref().map_or_else(none, |e| count(e.len())),
+                none(),
+                String::from("ok"),
+            ],
+            Entry::Css(FileResult::Ok { meta, .. }) => [
+                String::from("css"),
+                none(),
+                none(),
+                none(),
+                none(),
+                count(meta.classes.len()),
+                String::from("ok"),
+            ],
+            Entry::Template(FileResult::Ok { .. }) => {
+                let errors = self.errors();
+                let status = match errors.first() {
+                    Some(first) => format!(
+                        "{} errors {}:{} {}",
+                        errors.len(),
+                        first.loc.start.line,
+                        first.loc.start.column + 1,
+                        first.message
+                    ),
+                    None => String::from("ok"),
+                };
+                [
+                    String::from("swan"),
+                    none(),
+                    none(),
+                    none(),
+                    none(),
+                    none(),
+                    status,
+                ]
+            }
+            Entry::Script(FileResult::Error { error, .. })
+            | Entry::Css(FileResult::Error { error, .. })
+            | Entry::Template(FileResult::Error { error, .. }) => {
+                let loc = error.loc.as_ref().map_or_else(String::new, |loc| {
+                    format!(" {}:{}", loc.start.line, loc.start.column + 1)
+                });
+                let kind = match self {
+                    Entry::Css(_) => "css",
+                    Entry::Template(_) => "swan",
+                    Entry::Script(_) => "-",
+                };
+                [
+                    String::from(kind),
+                    none(),
+                    none(),
+                    none(),
+                    none(),
+                    none(),
+                    format!("{:?}{} {}", error.kind, loc, error.message),
+                ]
+            }
+        }
+    }
+}
+
+/// 模板所在的项目根目录，即最近的包含 app.json 的上级目录，没有时为模板所在目录，
+/// 返回根目录和模板相对根目录、不带扩展名的路径
+fn project_root(file_path: &str) -> (String, String) {
+    let path = Path::new(file_path);
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let root = parent
+        .ancestors()
+        .find(|dir| dir.join("app.json").is_file())
+        .unwrap_or(parent);
+    let relative = path.strip_prefix(root).unwrap_or(path).with_extension("");
+    let root = if root.as_os_str().is_empty() {
+        String::from(".")
+    } else {
+        root.to_string_lossy().into_owned()
+    };
+    (root, relative.to_string_lossy().into_owned())
+}
+
+/// 解析模板，合并分析器给出的诊断：不存在的 `<import>`、`<include>` 文件和模块文件，
+/// 对模块函数的错误调用，以及存在同名 js 组件时模板中未在 js 中声明的变量
+fn parse_template(parser: &Parser, file_path: &str) -> FileResult<TemplateMeta> {
+    FileResult::timed(|| {
+        let mut template = parser.parse_template(file_path)?;
+        let (root, unit_path) = project_root(file_path);
+        for analysis in [Analysis::CheckTemplateImports, Analysis::CheckModuleCalls] {
+            let result = parser.analyze(&root, &unit_path, &analysis)?;
+            template.diagnostics.extend(result.into_diagnostics());
+        }
+        if let Ok(result) = parser.analyze(&root, &unit_path, &Analysis::CheckTemplate) {
+            template.diagnostics.extend(result.into_diagnostics());
+        }
+        Ok(template)
+    })
+}
+
+fn parse(files: &[String]) -> BTreeMap<String, Entry> {
+    // 只解析传入的文件，被 @import 的文件列在 imports 中
+    let parser = ParseOptions::new().follow_css_imports(false).build();
+    let (template_files, files): (Vec<String>, Vec<String>) =
+        files.iter().cloned().partition(|file| is_template(file));
+    let (css_files, script_files): (Vec<String>, Vec<String>) =
+        files.into_iter().partition(|file| is_css(file));
+
+    let mut entries = BTreeMap::new();
+    for (file_path, result) in parser.parse_script_files(&script_files).files {
+        entries.insert(file_path, Entry::Script(result));
+    }
+    for (file_path, result) in parser.parse_css_files(&css_files).files {
+        entries.insert(file_path, Entry::Css(result));
+    }
+    for file_path in template_files {
+        let result = parse_template(&parser, &file_path);
+        entries.insert(file_path, Entry::Template(result));
+    }
+    entries
+}
+
+fn format_table(entries: &BTreeMap<String, Entry>) -> String {
+    let header = [
+        "FILE", "TYPE", "DATA", "PROPS", "METHODS", "EVENTS", "CLASSES", "STATUS",
+    ];
+    let mut rows: Vec<Vec<String>> = vec![header.iter().map(|s| s.to_string()).collect()];
+    for (file_path, entry) in entries {
+        let mut row = vec![file_path.clone()];
+        row.extend(entry.to_row());
+        rows.push(row);
+    }
+
+    let mut widths = vec![0; header.len()];
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    let mut output = String::new();
+    for row in &rows {
+        let mut line = String::new();
+        for (i, cell) in row.iter().enumerate() {
+            let width = widths[i];
+            match i {
+                // 文件、类型左对齐，状态列不补齐
+                0 | 1 => line.push_str(&format!("{:<width$}  ", cell, width = width)),
+                7 => line.push_str(cell),
+                _ => line.push_str(&format!("{:>width$}  ", cell, width = width)),
+            }
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+fn format_output(entries: &BTreeMap<String, Entry>, format: &Format) -> String {
+    match format {
+        Format::Json => {
+            let records: Vec<_> = entries.iter().map(|(f, e)| e.to_record(f)).collect();
+            let mut output = serde_json::to_string_pretty(&records).unwrap_or_default();
+            output.push('\n');
+            output
+        }
+        Format::Ndjson => entries
+            .iter()
+            .map(|(f, e)| {
+                let record = serde_json::to_string(&e.to_record(f)).unwrap_or_default();
+                format!("{}\n", record)
+            })
+            .collect(),
+        Format::Table => format_table(entries),
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("graph") {
+        args.next();
+        run_graph(args);
+        return;
+    }
+    let args = match parse_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("component-parser: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    if args.concurrency > 0 {
+        if let Err(message) = set_concurrency(args.concurrency) {
+            eprintln!("component-parser: {}", message);
+            process::exit(2);
+        }
+    }
+    let files = match collect_files(&args.inputs) {
+        Ok(files) => files,
+        Err(message) => {
+            eprintln!("component-parser: {}", message);
+            process::exit(2);
+        }
+    };
+
+    let mut entries = parse(&files);
+    let failed = entries.values().filter(|e| e.is_diagnostic()).count();
+    if args.check {
+        entries.retain(|_, entry| entry.is_diagnostic());
+    }
+    if !args.check || failed > 0 {
+        print!("{}", format_output(&entries, &args.format));
+    }
+    if args.check && failed > 0 {
+        eprintln!(
+            "component-parser: {} of {} files failed",
+            failed,
+            files.len()
+        );
+        process::exit(1);
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    let args = |list: &[&str]| parse_args(list.iter().map(|s| s.to_string()));
+
+    let result = args(&["-f", "table", "--check", "-j", "2", "a.js", "--", "-b.js"]);
+    assert_eq!(
+        result,
+        Ok(Some(Args {
+            format: Format::Table,
+            check: true,
+            concurrency: 2,
+            inputs: vec![String::from("a.js"), String::from("-b.js")],
+        }))
+    );
+    assert_eq!(args(&["--help", "a.js"]), Ok(None));
+    assert!(args(&[]).is_err());
+    assert!(args(&["-f", "xml", "a.js"]).is_err());
+    assert!(args(&["--unknown", "a.js"]).is_err());
+}
+
+#[test]
+fn test_graph() {
+    let args = |list: &[&str]| parse_graph_args(list.iter().map(|s| s.to_string()));
+    assert!(args(&[]).is_err());
+    assert!(args(&["a", "b"]).is_err());
+    assert!(args(&["-f", "svg", "a"]).is_err());
+
+    let graph_args = args(&[
+        "--dependents",
+        "utils/format.js",
+        "--direct",
+        "test/fixtures/project",
+    ])
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        graph_args,
+        GraphArgs {
+            dot: false,
+            query: GraphQuery::Dependents(String::from("utils/format.js")),
+            transitive: false,
+            root: String::from("test/fixtures/project"),
+        }
+    );
+    let graph = DependencyGraph::build(&graph_args.root).unwrap();
+    assert_eq!(
+        format_graph(&graph, &graph_args),
+        "behaviors/expandable.js\npages/index/index\n"
+    );
+
+    let graph_args = args(&["-f", "dot", "test/fixtures/project"])
+        .unwrap()
+        .unwrap();
+    assert!(format_graph(&graph, &graph_args).starts_with("digraph"));
+}
+
+#[test]
+fn test_collect_and_format() {
+    let files = collect_files(&[
+        String::from("test/fixtures"),
+        String::from("test/fixtures/page.*"),
+        String::from("test/fixtures/component.error-js"),
+    ])
+    .unwrap();
+    assert!(files.contains(&String::from("test/fixtures/page.js")));
+    assert!(files.contains(&String::from("test/fixtures/hostile/unicode.css")));
+    assert!(files.contains(&String::from("test/fixtures/component.error-js")));
+    assert_eq!(
+        files
+            .iter()
+            .filter(|f| f.as_str() == "test/fixtures/page.js")
+            .count(),
+        1
+    );
+
+    let entries = parse(&[
+        String::from("test/fixtures/page.js"),
+        String::from("test/fixtures/page.css"),
+        String::from("test/fixtures/error.js"),
+        String::from("test/fixtures/component.error-js"),
+        String::from("test/fixtures/component.swan"),
+        String::from("test/fixtures/error.swan"),
+    ]);
+    let failed: Vec<&str> = entries
+        .iter()
+        .filter(|(_, e)| e.is_diagnostic())
+        .map(|(f, _)| f.as_str())
+        .collect();
+    assert_eq!(
+        failed,
+        vec![
+            "test/fixtures/component.error-js",
+            "test/fixtures/error.swan"
+        ]
+    );
+    let errors: Vec<&str> = entries["test/fixtures/error.swan"]
+        .errors()
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            "unclosed tag `<text>`",
+            "cannot find template file `./missing.swan`"
+        ]
+    );
+
+    let table = format_table(&entries);
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].starts_with("FILE "));
+    assert!(lines[1].contains("SyntaxError 1:7"));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("test/fixtures/page.js ") && l.ends_with("ok")));
+
+    let output = format_output(&entries, &Format::Ndjson);
+    let value: serde_json::Value = serde_json::from_str(output.lines().next().unwrap()).unwrap();
+    assert_eq!(value["file"], "test/fixtures/component.error-js");
+    assert_eq!(value["status"], "error");
+    assert!(output.starts_with(r#"{"file":"#));
+
+    let output = format_output(&entries, &Format::Json);
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value.as_array().unwrap().len(), 6);
+}
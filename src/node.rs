@@ -0,0 +1,487 @@
+//! node 绑定，解析结果以 JSON 字符串返回给 js
+
+use crate::batch::{BatchContext, ProgressFn};
+use crate::{cache, catalog, pool, Analysis, DependencyGraph, Parser, Resolver};
+use neon::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 读取文件路径数组参数
+fn file_paths_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<Vec<String>> {
+    let arg: Handle<JsArray> = cx.argument(i)?;
+    let vec: Vec<Handle<JsValue>> = arg.to_vec(cx)?;
+    let mut file_paths = vec![];
+    for v in vec {
+        file_paths.push(v.to_string(cx)?.value(cx));
+    }
+    Ok(file_paths)
+}
+
+/// 将解析结果序列化为 JSON 字符串返回给 js
+fn to_json<'a>(
+    cx: &mut FunctionContext<'a>,
+    value: &impl serde::Serialize,
+) -> JsResult<'a, JsString> {
+    match serde_json::to_string(value) {
+        Ok(result) => Ok(cx.string(result)),
+        Err(e) => cx.throw_error(e.to_string()),
+    }
+}
+
+/// 将解析结果逐个属性构造为 js 对象，作为 JSON 传输的对照，见 `test/benchmark.js`
+fn to_js_value<'a>(
+    cx: &mut FunctionContext<'a>,
+    value: &serde_json::Value,
+) -> JsResult<'a, JsValue> {
+    use serde_json::Value;
+    Ok(match value {
+        Value::Null => cx.null().upcast(),
+        Value::Bool(b) => cx.boolean(*b).upcast(),
+        Value::Number(n) => cx.number(n.as_f64().unwrap_or_default()).upcast(),
+        Value::String(s) => cx.string(s).upcast(),
+        Value::Array(items) => {
+            let array = JsArray::new(cx, items.len() as u32);
+            for (i, item) in items.iter().enumerate() {
+                let item = to_js_value(cx, item)?;
+                array.set(cx, i as u32, item)?;
+            }
+            array.upcast()
+        }
+        Value::Object(map) => {
+            let object = cx.empty_object();
+            for (key, item) in map {
+                let item = to_js_value(cx, item)?;
+                object.set(cx, key.as_str(), item)?;
+            }
+            object.upcast()
+        }
+    })
+}
+
+/// 解析单个文件
+fn parse_script(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    // 在解析线程中执行，js 线程的栈不足以解析深层嵌套的代码
+    if let Ok(result) = Parser::new().parse_script(&file_path) {
+        return to_json(&mut cx, &result);
+    }
+    cx.throw_error("parse script error!")
+}
+
+/// 解析一组 js 文件
+fn parse_script_files(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_paths = file_paths_argument(&mut cx, 0)?;
+    let result_map = Parser::new().parse_script_files(&file_paths);
+    to_json(&mut cx, &result_map)
+}
+
+/// 解析一组 js 文件，结果直接构造为 js 对象，只用于和 JSON 传输对比
+fn parse_script_files_structured(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let file_paths = file_paths_argument(&mut cx, 0)?;
+    let result_map = Parser::new().parse_script_files(&file_paths);
+    match serde_json::to_value(&result_map) {
+        Ok(value) => to_js_value(&mut cx, &value),
+        Err(e) => cx.throw_error(e.to_string()),
+    }
+}
+
+/// 解析单个 css 文件
+fn parse_css(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    if let Ok(result) = Parser::new().parse_css(&file_path) {
+        return to_json(&mut cx, &result);
+    }
+    cx.throw_error("parse css error!")
+}
+
+/// 解析一组 css 文件
+fn parse_css_files(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_paths = file_paths_argument(&mut cx, 0)?;
+    let result_map = Parser::new().parse_css_files(&file_paths);
+    to_json(&mut cx, &result_map)
+}
+
+/// 使缓存失效，不传参数时清空所有缓存
+fn invalidate_cache(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    match cx.argument_opt(0) {
+        Some(arg0) if !arg0.is_a::<JsUndefined, _>(&mut cx) => {
+            let arg0 = arg0.downcast_or_throw::<JsArray, _>(&mut cx)?;
+            let vec: Vec<Handle<JsValue>> = arg0.to_vec(&mut cx)?;
+            let mut file_paths: Vec<String> = vec![];
+            for v in vec {
+                file_paths.push(v.to_string(&mut cx)?.value(&mut cx));
+            }
+            cache::invalidate(&file_paths);
+        }
+        _ => cache::clear(),
+    }
+    Ok(cx.undefined())
+}
+
+/// 设置缓存的文件数和源码字节数上限
+fn set_cache_limits(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let max_entries = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let max_bytes = cx.argument::<JsNumber>(1)?.value(&mut cx);
+    cache::set_limits(max_entries as usize, max_bytes as usize);
+    Ok(cx.undefined())
+}
+
+/// 将解析缓存写入磁盘索引
+fn save_cache(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let cache_dir = cx.argument::<JsString>(0)?.value(&mut cx);
+    if let Err(message) = cache::save_index(&cache_dir) {
+        return cx.throw_error(message);
+    }
+    Ok(cx.undefined())
+}
+
+/// 从磁盘索引载入解析缓存，返回载入统计
+fn load_cache(mut cx: FunctionContext) -> JsResult<JsString> {
+    let cache_dir = cx.argument::<JsString>(0)?.value(&mut cx);
+    // 已变化的文件在解析线程池中重新解析
+    match cache::load_index(&cache_dir) {
+        Ok(stats) => to_json(&mut cx, &stats),
+        Err(message) => cx.throw_error(message),
+    }
+}
+
+/// 设置解析线程池并发数，传 0 时使用默认并发数
+fn set_concurrency(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let concurrency = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    if let Err(message) = pool::set_concurrency(concurrency as usize) {
+        return cx.throw_error(message);
+    }
+    Ok(cx.undefined())
+}
+
+/// 异步任务的取消标记
+struct CancelToken(Arc<AtomicBool>);
+
+impl Finalize for CancelToken {}
+
+/// 创建取消标记，传给异步解析函数
+fn create_cancel_token(mut cx: FunctionContext) -> JsResult<JsBox<CancelToken>> {
+    Ok(cx.boxed(CancelToken(Arc::new(AtomicBool::new(false)))))
+}
+
+/// 取消使用该标记的异步任务
+fn cancel(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let token = cx.argument::<JsBox<CancelToken>>(0)?;
+    token.0.store(true, Ordering::Relaxed);
+    Ok(cx.undefined())
+}
+
+/// 读取可选参数，`undefined` 和 `null` 视为未传
+fn optional_argument<'a, V: Value>(
+    cx: &mut FunctionContext<'a>,
+    i: i32,
+) -> NeonResult<Option<Handle<'a, V>>> {
+    match cx.argument_opt(i) {
+        Some(arg) if !arg.is_a::<JsUndefined, _>(cx) && !arg.is_a::<JsNull, _>(cx) => {
+            Ok(Some(arg.downcast_or_throw::<V, _>(cx)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 读取可选的取消标记和进度回调参数
+fn batch_context_argument(cx: &mut FunctionContext, i: i32) -> NeonResult<BatchContext> {
+    let cancelled = optional_argument::<JsBox<CancelToken>>(cx, i)?.map(|token| token.0.clone());
+    let on_progress = match optional_argument::<JsFunction>(cx, i + 1)? {
+        Some(callback) => {
+            let callback = Arc::new(callback.root(cx));
+            let channel = cx.channel();
+            let on_progress: ProgressFn = Box::new(move |file_path, done, total| {
+                let callback = callback.clone();
+                let file_path = file_path.to_string();
+                channel.send(move |mut cx| {
+                    let callback = callback.to_inner(&mut cx);
+                    let this = cx.undefined();
+                    let args = vec![
+                        cx.string(file_path).upcast::<JsValue>(),
+                        cx.number(done as f64).upcast(),
+                        cx.number(total as f64).upcast(),
+                    ];
+                    callback.call(&mut cx, this, args)?;
+                    Ok(())
+                });
+            });
+            Some(on_progress)
+        }
+        None => None,
+    };
+    Ok(BatchContext::new(cancelled, on_progress))
+}
+
+/// 在解析线程池中执行任务，完成后在 js 线程中通过 `settle` 将结果转换为 js 值兑现 Promise
+fn spawn_task<'a, T, V, F, S>(
+    cx: &mut FunctionContext<'a>,
+    batch: BatchContext,
+    task: F,
+    settle: S,
+) -> JsResult<'a, JsPromise>
+where
+    T: Send + 'static,
+    V: Value,
+    F: FnOnce(&BatchContext) -> Result<T, String> + Send + 'static,
+    S: for<'b> FnOnce(&mut TaskContext<'b>, T) -> JsResult<'b, V> + Send + 'static,
+{
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+    pool::spawn(move || {
+        // 任务中的 panic 转为 Promise 拒绝，否则 Promise 永远不会兑现
+        let result = panic::catch_unwind(AssertUnwindSafe(|| task(&batch)))
+            .unwrap_or_else(|_| Err(String::from("internal error")));
+        let cancelled = batch.is_cancelled();
+        // 进度回调先于 Promise 兑现发送到 js 线程
+        drop(batch);
+        deferred.settle_with(&channel, move |mut cx| {
+            if cancelled {
+                return cx.throw_error("parse cancelled");
+            }
+            match result {
+                Ok(result) => settle(&mut cx, result),
+                Err(message) => cx.throw_error(message),
+            }
+        });
+    });
+    Ok(promise)
+}
+
+/// 在解析线程池中执行任务，完成后在 js 线程中以 JSON 字符串兑现 Promise
+fn spawn_promise<'a, T, F>(
+    cx: &mut FunctionContext<'a>,
+    batch: BatchContext,
+    task: F,
+) -> JsResult<'a, JsPromise>
+where
+    T: serde::Serialize,
+    F: FnOnce(&BatchContext) -> Result<T, String> + Send + 'static,
+{
+    // 序列化同样在解析线程中进行
+    let task = move |batch: &BatchContext| {
+        task(batch).and_then(|meta| serde_json::to_string(&meta).map_err(|e| e.to_string()))
+    };
+    spawn_task(cx, batch, task, |cx, result| Ok(cx.string(result)))
+}
+
+/// 异步解析单个 js 文件
+fn parse_script_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let batch = batch_context_argument(&mut cx, 1)?;
+    spawn_promise(&mut cx, batch, move |_| {
+        Parser::new()
+            .parse_script(&file_path)
+            .map_err(|_| String::from("parse script error!"))
+    })
+}
+
+/// 异步解析一组 js 文件
+fn parse_script_files_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let file_paths = file_paths_argument(&mut cx, 0)?;
+    let batch = batch_context_argument(&mut cx, 1)?;
+    spawn_promise(&mut cx, batch, move |batch| {
+        Ok(Parser::new().parse_script_files_with_context(&file_paths, batch))
+    })
+}
+
+/// 异步解析单个 css 文件
+fn parse_css_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let batch = batch_context_argument(&mut cx, 1)?;
+    spawn_promise(&mut cx, batch, move |_| {
+        Parser::new()
+            .parse_css(&file_path)
+            .map_err(|_| String::from("parse css error!"))
+    })
+}
+
+/// 异步解析一组 css 文件
+fn parse_css_files_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let file_paths = file_paths_argument(&mut cx, 0)?;
+    let batch = batch_context_argument(&mut cx, 1)?;
+    spawn_promise(&mut cx, batch, move |batch| {
+        Ok(Parser::new().parse_css_files_with_context(&file_paths, batch))
+    })
+}
+
+/// 解析页面或组件的 json 配置
+fn parse_config(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    if let Ok(result) = Parser::new().parse_config(&file_path) {
+        return to_json(&mut cx, &result);
+    }
+    cx.throw_error("parse config error!")
+}
+
+/// 解析页面或组件配置中的 usingComponents，第二个参数为相对项目根目录、不带扩展名的路径
+fn resolve_components(mut cx: FunctionContext) -> JsResult<JsString> {
+    let root = cx.argument::<JsString>(0)?.value(&mut cx);
+    let unit_path = cx.argument::<JsString>(1)?.value(&mut cx);
+    match Resolver::new(&root).resolve_unit(&unit_path) {
+        Ok(result) => to_json(&mut cx, &result),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 已构建的项目依赖图，多次查询时不再重新读取项目
+struct GraphHandle(DependencyGraph);
+
+impl Finalize for GraphHandle {}
+
+/// 构建项目依赖图，返回的依赖图用于 `graphReport`、`graphToDot` 和依赖查询
+fn build_graph(mut cx: FunctionContext) -> JsResult<JsBox<GraphHandle>> {
+    let root = cx.argument::<JsString>(0)?.value(&mut cx);
+    match DependencyGraph::build(&root) {
+        Ok(graph) => Ok(cx.boxed(GraphHandle(graph))),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 异步构建项目依赖图，只支持取消
+fn build_graph_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let root = cx.argument::<JsString>(0)?.value(&mut cx);
+    let batch = batch_context_argument(&mut cx, 1)?;
+    spawn_task(
+        &mut cx,
+        batch,
+        move |_| DependencyGraph::build(&root).map_err(|error| error.to_string()),
+        |cx, graph| Ok(cx.boxed(GraphHandle(graph))),
+    )
+}
+
+/// 输出依赖图的节点、边，以及其中的循环依赖和未使用的页面、组件
+fn graph_report(mut cx: FunctionContext) -> JsResult<JsString> {
+    let graph = cx.argument::<JsBox<GraphHandle>>(0)?;
+    to_json(&mut cx, &graph.0.report())
+}
+
+/// 输出项目依赖图的 DOT 格式
+fn graph_to_dot(mut cx: FunctionContext) -> JsResult<JsString> {
+    let graph = cx.argument::<JsBox<GraphHandle>>(0)?;
+    let dot = graph.0.to_dot();
+    Ok(cx.string(dot))
+}
+
+/// 查找依赖指定节点的页面、组件和文件
+fn find_dependents(mut cx: FunctionContext) -> JsResult<JsString> {
+    let graph = cx.argument::<JsBox<GraphHandle>>(0)?;
+    let id = cx.argument::<JsString>(1)?.value(&mut cx);
+    let transitive = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let dependents = graph.0.dependents(&id, transitive);
+    to_json(&mut cx, &dependents)
+}
+
+/// 查找指定节点依赖的页面、组件和文件
+fn find_dependencies(mut cx: FunctionContext) -> JsResult<JsString> {
+    let graph = cx.argument::<JsBox<GraphHandle>>(0)?;
+    let id = cx.argument::<JsString>(1)?.value(&mut cx);
+    let transitive = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let dependencies = graph.0.dependencies(&id, transitive);
+    to_json(&mut cx, &dependencies)
+}
+
+/// 解析 swan 模板
+fn parse_template(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    match Parser::new().parse_template(&file_path) {
+        Ok(result) => to_json(&mut cx, &result),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 解析 filter 或 sjs 模块
+fn parse_filter(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    match Parser::new().parse_filter(&file_path) {
+        Ok(result) => to_json(&mut cx, &result),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 解析 swan 模板中的绑定表达式
+fn parse_template_bindings(mut cx: FunctionContext) -> JsResult<JsString> {
+    let file_path = cx.argument::<JsString>(0)?.value(&mut cx);
+    match Parser::new().parse_template_bindings(&file_path) {
+        Ok(result) => to_json(&mut cx, &result),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 分析页面或组件的模板，第二个参数为相对项目根目录、不带扩展名的路径，
+/// 第三个参数为 `Analysis` 的 JSON 字符串，例如 `{"kind":"templateVariables","position":{"line":1,"column":0}}`
+fn analyze(mut cx: FunctionContext) -> JsResult<JsString> {
+    let root = cx.argument::<JsString>(0)?.value(&mut cx);
+    let unit_path = cx.argument::<JsString>(1)?.value(&mut cx);
+    let analysis = cx.argument::<JsString>(2)?.value(&mut cx);
+    let analysis: Analysis = match serde_json::from_str(&analysis) {
+        Ok(analysis) => analysis,
+        Err(e) => return cx.throw_error(e.to_string()),
+    };
+    match Parser::new().analyze(&root, &unit_path, &analysis) {
+        Ok(result) => to_json(&mut cx, &result),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 内置组件目录
+fn builtin_components(mut cx: FunctionContext) -> JsResult<JsString> {
+    to_json(&mut cx, catalog::catalog())
+}
+
+/// 解析项目中所有页面和组件
+fn parse_project(mut cx: FunctionContext) -> JsResult<JsString> {
+    let root = cx.argument::<JsString>(0)?.value(&mut cx);
+    match Parser::new().parse_project(&root) {
+        Ok(project) => to_json(&mut cx, &project),
+        Err(error) => cx.throw_error(error.to_string()),
+    }
+}
+
+/// 异步解析项目中所有页面和组件
+fn parse_project_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let root = cx.argument::<JsString>(0)?.value(&mut cx);
+    let batch = batch_context_argument(&mut cx, 1)?;
+    spawn_promise(&mut cx, batch, move |batch| {
+        Parser::new()
+            .parse_project_with_context(&root, batch)
+            .map_err(|error| error.to_string())
+    })
+}
+
+#[neon::main]
+fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    cx.export_function("parseScript", parse_script)?;
+    cx.export_function("parseScriptFiles", parse_script_files)?;
+    cx.export_function("parseScriptFilesStructured", parse_script_files_structured)?;
+    cx.export_function("parseCss", parse_css)?;
+    cx.export_function("parseCssFiles", parse_css_files)?;
+    cx.export_function("parseScriptAsync", parse_script_async)?;
+    cx.export_function("parseScriptFilesAsync", parse_script_files_async)?;
+    cx.export_function("parseCssAsync", parse_css_async)?;
+    cx.export_function("parseCssFilesAsync", parse_css_files_async)?;
+    cx.export_function("parseConfig", parse_config)?;
+    cx.export_function("parseTemplate", parse_template)?;
+    cx.export_function("parseTemplateBindings", parse_template_bindings)?;
+    cx.export_function("analyze", analyze)?;
+    cx.export_function("parseFilter", parse_filter)?;
+    cx.export_function("builtinComponents", builtin_components)?;
+    cx.export_function("resolveComponents", resolve_components)?;
+    cx.export_function("buildGraph", build_graph)?;
+    cx.export_function("buildGraphAsync", build_graph_async)?;
+    cx.export_function("graphReport", graph_report)?;
+    cx.export_function("graphToDot", graph_to_dot)?;
+    cx.export_function("findDependents", find_dependents)?;
+    cx.export_function("findDependencies", find_dependencies)?;
+    cx.export_function("parseProject", parse_project)?;
+    cx.export_function("parseProjectAsync", parse_project_async)?;
+    cx.export_function("createCancelToken", create_cancel_token)?;
+    cx.export_function("cancel", cancel)?;
+    cx.export_function("invalidateCache", invalidate_cache)?;
+    cx.export_function("setCacheLimits", set_cache_limits)?;
+    cx.export_function("saveCache", save_cache)?;
+    cx.export_function("loadCache", load_cache)?;
+    cx.export_function("setConcurrency", set_concurrency)?;
+    Ok(())
+}
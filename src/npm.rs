@@ -0,0 +1,76 @@
+//! 查找 npm 包中的文件，组件和 css `@import` 共用
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 将 `包名/包内路径` 拆分为包名和包内路径，包名可以带 scope，例如 `@scope/name`
+fn split_request(request: &str) -> Option<(&str, &str)> {
+    let request = request.trim_start_matches('/');
+    let index = if request.starts_with('@') {
+        let scope = request.find('/')?;
+        request[scope + 1..].find('/').map(|i| scope + 1 + i)
+    } else {
+        request.find('/')
+    };
+    match index {
+        Some(index) => Some((&request[..index], &request[index + 1..])),
+        None if request.is_empty() => None,
+        None => Some((request, "")),
+    }
+}
+
+/// 读取 `package.json` 中的 `miniprogram` 字段，即构建后的小程序代码目录
+fn miniprogram_dir(package_dir: &Path) -> Option<String> {
+    let text = fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let package: Value = serde_json::from_str(&text).ok()?;
+    Some(package["miniprogram"].as_str().unwrap_or("").to_string())
+}
+
+/// 从 `dir` 逐级向上，依次查找 `miniprogram_npm/<request>` 和
+/// `node_modules/<包名>/<miniprogram>/<包内路径>`，返回可能的路径，不带扩展名时由调用方补全
+///
+/// `dir` 和返回的路径都相对 `root`，`node_modules` 中的包只有存在 `package.json` 时才返回，
+/// 没有 `miniprogram` 字段时使用包的根目录
+pub fn candidates(root: &Path, dir: &Path, request: &str) -> Vec<PathBuf> {
+    let (name, rest) = match split_request(request) {
+        Some(parts) => parts,
+        None => return vec![],
+    };
+    let mut result = vec![];
+    for dir in dir.ancestors() {
+        result.push(dir.join("miniprogram_npm").join(request));
+        let package_dir = dir.join("node_modules").join(name);
+        if let Some(miniprogram) = miniprogram_dir(&root.join(&package_dir)) {
+            let mut path = package_dir.join(miniprogram);
+            // 避免 `join("")` 在末尾添加分隔符
+            if !rest.is_empty() {
+                path.push(rest);
+            }
+            result.push(path);
+        }
+    }
+    result
+}
+
+#[test]
+fn test_candidates() {
+    assert_eq!(split_request("ui-kit"), Some(("ui-kit", "")));
+    assert_eq!(
+        split_request("@scope/ui/button/button"),
+        Some(("@scope/ui", "button/button"))
+    );
+    assert_eq!(split_request("@scope"), None);
+
+    let root = Path::new("test/fixtures/project");
+    let paths = candidates(root, Path::new("pages/index"), "@demo/ui/tag/tag");
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("pages/index/miniprogram_npm/@demo/ui/tag/tag"),
+            PathBuf::from("pages/miniprogram_npm/@demo/ui/tag/tag"),
+            PathBuf::from("miniprogram_npm/@demo/ui/tag/tag"),
+            PathBuf::from("node_modules/@demo/ui/miniprogram_dist/tag/tag"),
+        ]
+    );
+}
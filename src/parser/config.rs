@@ -0,0 +1,282 @@
+use crate::parser::catch_panic;
+use crate::parser::json::{parse_json, JsonNode, JsonValue};
+use crate::parser::meta::{
+    ComponentGenericMeta, ConfigKeyMeta, ConfigMeta, Diagnostic, ParseError, UsingComponentMeta,
+};
+use std::collections::HashSet;
+use std::fs;
+
+/// 配置项的值类型
+enum KeyType {
+    Bool,
+    Number,
+    String,
+    Object,
+    /// 取值只能是其中之一的字符串
+    Enum(&'static [&'static str]),
+}
+
+/// 页面和组件配置中可用的配置项
+const KNOWN_KEYS: &[(&str, KeyType)] = &[
+    ("component", KeyType::Bool),
+    ("usingComponents", KeyType::Object),
+    ("componentGenerics", KeyType::Object),
+    (
+        "styleIsolation",
+        KeyType::Enum(&["isolated", "apply-shared", "shared"]),
+    ),
+    ("navigationBarBackgroundColor", KeyType::String),
+    ("navigationBarTextStyle", KeyType::Enum(&["white", "black"])),
+    ("navigationBarTitleText", KeyType::String),
+    ("navigationStyle", KeyType::Enum(&["default", "custom"])),
+    ("backgroundColor", KeyType::String),
+    ("backgroundTextStyle", KeyType::Enum(&["dark", "light"])),
+    ("backgroundColorTop", KeyType::String),
+    ("backgroundColorBottom", KeyType::String),
+    ("enablePullDownRefresh", KeyType::Bool),
+    ("onReachBottomDistance", KeyType::Number),
+    ("disableScroll", KeyType::Bool),
+    ("disableSwipeBack", KeyType::Bool),
+    ("textSizeAdjust", KeyType::Bool),
+];
+
+/// 检查值的类型，返回错误信息
+fn check_type(name: &str, key_type: &KeyType, node: &JsonNode) -> Option<String> {
+    let expected = match (key_type, &node.value) {
+        (KeyType::Bool, JsonValue::Bool(_))
+        | (KeyType::Number, JsonValue::Number(_))
+        | (KeyType::String, JsonValue::String(_))
+        | (KeyType::Object, JsonValue::Object(_)) => return None,
+        (KeyType::Enum(values), JsonValue::String(value)) => {
+            if values.contains(&value.as_str()) {
+                return None;
+            }
+            return Some(format!(
+                "invalid value `{}` for `{}`, expected one of {}",
+                value,
+                name,
+                values.join(", ")
+            ));
+        }
+        (KeyType::Bool, _) => "boolean",
+        (KeyType::Number, _) => "number",
+        (KeyType::String, _) | (KeyType::Enum(_), _) => "string",
+        (KeyType::Object, _) => "object",
+    };
+    Some(format!(
+        "`{}` should be a {}, found {}",
+        name,
+        expected,
+        node.type_name()
+    ))
+}
+
+fn parse_using_components(node: &JsonNode, meta: &mut ConfigMeta) {
+    for entry in node.entries() {
+        match entry.value.as_str() {
+            Some(path) if !path.trim().is_empty() => {
+                meta.using_components.insert(
+                    entry.key.clone(),
+                    UsingComponentMeta {
+                        name: entry.key.clone(),
+                        path: path.to_string(),
+                        loc: entry.key_loc.clone(),
+                        value_loc: entry.value.loc.clone(),
+                    },
+                );
+            }
+            Some(_) => meta.diagnostics.push(Diagnostic::error(
+                format!("empty path for component `{}`", entry.key),
+                entry.value.loc.clone(),
+            )),
+            None => meta.diagnostics.push(Diagnostic::error(
+                format!(
+                    "path of component `{}` should be a string, found {}",
+                    entry.key,
+                    entry.value.type_name()
+                ),
+                entry.value.loc.clone(),
+            )),
+        }
+    }
+}
+
+fn parse_component_generics(node: &JsonNode, meta: &mut ConfigMeta) {
+    for entry in node.entries() {
+        // 取值为 true，或者 `{"default": "path/to/component"}`
+        let default = match &entry.value.value {
+            JsonValue::Bool(true) => None,
+            JsonValue::Object(_) => match entry.value.get("default") {
+                Some(default) if default.as_str().is_some() => default.as_str().map(String::from),
+                None => None,
+                Some(default) => {
+                    meta.diagnostics.push(Diagnostic::error(
+                        format!(
+                            "default of generic `{}` should be a string, found {}",
+                            entry.key,
+                            default.type_name()
+                        ),
+                        default.loc.clone(),
+                    ));
+                    None
+                }
+            },
+            _ => {
+                meta.diagnostics.push(Diagnostic::error(
+                    format!(
+                        "generic `{}` should be true or an object with `default`",
+                        entry.key
+                    ),
+                    entry.value.loc.clone(),
+                ));
+                continue;
+            }
+        };
+        meta.component_generics.insert(
+            entry.key.clone(),
+            ComponentGenericMeta {
+                name: entry.key.clone(),
+                default,
+                loc: entry.key_loc.clone(),
+            },
+        );
+    }
+}
+
+/// 读取并解析页面或组件的 json 配置
+pub fn parse_config(file_path: &str) -> Result<ConfigMeta, ParseError> {
+    let read_result = fs::read_to_string(file_path);
+    if read_result.is_err() {
+        return Err(ParseError::not_found(file_path));
+    }
+    parse_config_source(file_path, &read_result.unwrap())
+}
+
+/// 解析已读取的 json 配置，json 语法错误时返回错误，配置项的问题记录在 `diagnostics` 中
+pub fn parse_config_source(file_path: &str, text: &str) -> Result<ConfigMeta, ParseError> {
+    catch_panic(file_path, || parse_source(file_path, text))
+}
+
+fn parse_source(file_path: &str, text: &str) -> Result<ConfigMeta, ParseError> {
+    let root = parse_json(file_path, text)?;
+    let mut meta = ConfigMeta::default();
+    if !matches!(root.value, JsonValue::Object(_)) {
+        meta.diagnostics.push(Diagnostic::error(
+            format!("config should be an object, found {}", root.type_name()),
+            root.loc.clone(),
+        ));
+        return Ok(meta);
+    }
+
+    let mut names = HashSet::new();
+    for entry in root.entries() {
+        meta.keys.push(ConfigKeyMeta {
+            name: entry.key.clone(),
+            loc: entry.key_loc.clone(),
+        });
+        if !names.insert(entry.key.as_str()) {
+            meta.diagnostics.push(Diagnostic::warning(
+                format!("duplicate key `{}`", entry.key),
+                entry.key_loc.clone(),
+            ));
+        }
+
+        let key_type = KNOWN_KEYS.iter().find(|(name, _)| *name == entry.key);
+        let key_type = match key_type {
+            Some((_, key_type)) => key_type,
+            None => {
+                meta.diagnostics.push(Diagnostic::warning(
+                    format!("unknown key `{}`", entry.key),
+                    entry.key_loc.clone(),
+                ));
+                continue;
+            }
+        };
+        if let Some(message) = check_type(&entry.key, key_type, &entry.value) {
+            meta.diagnostics
+                .push(Diagnostic::error(message, entry.value.loc.clone()));
+            continue;
+        }
+
+        match entry.key.as_str() {
+            "component" => meta.component = entry.value.as_bool().unwrap_or(false),
+            "navigationBarTitleText" => {
+                meta.navigation_bar_title_text = entry.value.as_str().map(String::from)
+            }
+            "usingComponents" => parse_using_components(&entry.value, &mut meta),
+            "componentGenerics" => parse_component_generics(&entry.value, &mut meta),
+            _ => (),
+        }
+    }
+    Ok(meta)
+}
+
+#[test]
+fn test_parse_config() {
+    use crate::parser::meta::{Location, ParseErrorKind, Severity};
+
+    let meta = parse_config("test/fixtures/project/components/card/card.json").unwrap();
+    assert!(meta.component);
+    let avatar = &meta.using_components["avatar"];
+    assert_eq!(avatar.path, "../avatar/avatar");
+    assert_eq!(avatar.loc, Location::from([4, 8], [4, 16]));
+    assert_eq!(avatar.value_loc, Location::from([4, 18], [4, 36]));
+    assert!(meta.diagnostics.is_empty());
+
+    let text = r#"{
+    "navigationBarTitleText": "首页",
+    "navigationStyle": "none",
+    "enablePullDownRefresh": "yes",
+    "unknownKey": 1,
+    "usingComponents": {"a": "/a/a", "b": 1, "c": ""},
+    "componentGenerics": {"g1": true, "g2": {"default": "/g/g"}, "g3": 1},
+    "component": false,
+    "component": true
+}"#;
+    let meta = parse_config_source("page.json", text).unwrap();
+    assert!(meta.component);
+    assert_eq!(meta.navigation_bar_title_text.as_deref(), Some("首页"));
+    assert_eq!(meta.keys.len(), 8);
+    assert_eq!(meta.using_components.len(), 1);
+    assert_eq!(meta.component_generics.len(), 2);
+    assert_eq!(
+        meta.component_generics["g2"].default.as_deref(),
+        Some("/g/g")
+    );
+
+    let messages: Vec<(&Severity, &str)> = meta
+        .diagnostics
+        .iter()
+        .map(|d| (&d.severity, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                &Severity::Error,
+                "invalid value `none` for `navigationStyle`, expected one of default, custom"
+            ),
+            (
+                &Severity::Error,
+                "`enablePullDownRefresh` should be a boolean, found string"
+            ),
+            (&Severity::Warning, "unknown key `unknownKey`"),
+            (
+                &Severity::Error,
+                "path of component `b` should be a string, found number"
+            ),
+            (&Severity::Error, "empty path for component `c`"),
+            (
+                &Severity::Error,
+                "generic `g3` should be true or an object with `default`"
+            ),
+            (&Severity::Warning, "duplicate key `component`"),
+        ]
+    );
+    assert_eq!(meta.diagnostics[2].loc, Location::from([5, 4], [5, 16]));
+
+    let error = parse_config_source("page.json", "{\"a\": }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::SyntaxError);
+    let meta = parse_config_source("page.json", "[]").unwrap();
+    assert_eq!(meta.diagnostics.len(), 1);
+}
@@ -1,37 +1,98 @@
-use crate::parser::meta::{CssClassMeta, CssMeta, Location};
-use regex::Regex;
+use crate::npm;
+use crate::parser::catch_panic;
+use crate::parser::meta::{CssClassMeta, CssMeta, Location, ParseError};
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::vec;
 
-pub fn parse_css_class(file_path: &str) -> Result<CssMeta, String> {
+/// 读取并解析 css 文件，不解析 `@import` 的文件
+pub fn parse_css_class(file_path: &str) -> Result<CssMeta, ParseError> {
     let read_result = fs::read_to_string(file_path);
-    if let Err(_) = read_result {
-        return Err(format!("No such file {}", file_path));
+    if read_result.is_err() {
+        return Err(ParseError::not_found(file_path));
     }
 
-    let text = read_result.unwrap();
-    let regex = Regex::new(r"\.([a-z_][\w-]+)").unwrap();
-    let regex_start = Regex::new(r"^[\w\s.,{}>+]$").unwrap();
-    let regex_end = Regex::new(r"^[\s.:,{>+]$").unwrap();
+    parse_css_source(file_path, &read_result.unwrap())
+}
+
+/// 解析已读取的 css 源码，`file_path` 用于定位 `@import` 的相对路径
+///
+/// 只保留磁盘上存在的 `@import` 文件
+pub fn parse_css_source(file_path: &str, text: &str) -> Result<CssMeta, ParseError> {
+    parse_css_source_with(file_path, text, resolve_import)
+}
+
+/// 解析已读取的 css 源码，`@import` 地址与 `file_path` 所在目录拼接后交给 `resolve`，
+/// 返回 `None` 的地址会被忽略，用于没有文件系统的环境
+///
+/// `~` 开头的地址依次尝试 npm 包中可能的路径，取第一个 `resolve` 成功的路径
+pub fn parse_css_source_with(
+    file_path: &str,
+    text: &str,
+    resolve: impl Fn(&Path) -> Option<String>,
+) -> Result<CssMeta, ParseError> {
+    catch_panic(file_path, || parse_source(file_path, text, &resolve))
+}
+
+fn resolve_import(path: &Path) -> Option<String> {
+    // 忽略指向目录的地址，例如 `@import ""`
+    path.canonicalize()
+        .ok()
+        .filter(|p| p.is_file())
+        .map(|p| p.to_string_lossy().into_owned())
+}
+
+/// 不访问文件系统，按字面处理路径中的 `.` 和 `..`
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // 根目录的上级仍是根目录
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => (),
+                _ => normalized.push(component),
+            },
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn parse_source(
+    file_path: &str,
+    text: &str,
+    resolve: &impl Fn(&Path) -> Option<String>,
+) -> Result<CssMeta, ParseError> {
+    let regex = regex!(r"\.(-?[_a-zA-Z][\w-]*)");
+    let regex_start = regex!(r"^[\w\s.,{}>+]$");
+    let regex_end = regex!(r"^[\s.:,{>+]$");
 
     let mut last_line = 1;
     let mut last_offset = 0;
     let mut classes_set: HashSet<String> = HashSet::new();
     let mut classes = vec![];
 
-    for caps in regex.captures_iter(&text) {
-        let match0 = caps.get(0).unwrap();
-        let start = match0.start();
-        let end = match0.end();
+    for caps in regex.captures_iter(text) {
+        let (start, end) = match caps.get(0) {
+            Some(match0) => (match0.start(), match0.end()),
+            None => continue,
+        };
         // front
-        if start > 0 && !regex_start.is_match(text.get(start - 1..start).unwrap()) {
-            continue;
+        if let Some(c) = text[..start].chars().next_back() {
+            if !regex_start.is_match(c.encode_utf8(&mut [0; 4])) {
+                continue;
+            }
         }
         // back
-        if end < text.len() && !regex_end.is_match(text.get(end..end + 1).unwrap()) {
-            continue;
+        if let Some(c) = text[end..].chars().next() {
+            if !regex_end.is_match(c.encode_utf8(&mut [0; 4])) {
+                continue;
+            }
         }
 
         let class_name = caps[1].to_string();
@@ -60,14 +121,19 @@ pub fn parse_css_class(file_path: &str) -> Result<CssMeta, String> {
 
     // 解析 imports 地址
     let mut meta = CssMeta::new(classes);
-    let regex = Regex::new(r#"@import\s+(?:url\()?["'](?P<url>[^"']+)["']"#).unwrap();
+    let regex = regex!(r#"@import\s+(?:url\()?["'](?P<url>[^"']+)["']"#);
     let mut imports: Vec<String> = vec![];
-    for caps in regex.captures_iter(&text) {
-        let path = Path::new(file_path).parent().unwrap().join(&caps["url"]);
-        let abs_path = path.canonicalize();
-
-        if let Ok(p) = abs_path {
-            imports.push(p.to_str().unwrap().to_string());
+    let path = Path::new(file_path);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for caps in regex.captures_iter(text) {
+        let url = &caps["url"];
+        // `~` 开头的地址为 npm 包中的文件
+        let candidates = match url.strip_prefix('~') {
+            Some(request) => npm::candidates(Path::new(""), dir, request),
+            None => vec![dir.join(url)],
+        };
+        if let Some(p) = candidates.iter().find_map(|path| resolve(path)) {
+            imports.push(p);
         }
     }
     meta.imports = Some(imports);
@@ -80,7 +146,7 @@ fn test_parse_css() {
     let css_meta = parse_css_class("test/fixtures/component.css").unwrap();
     assert_eq!(css_meta.classes.len(), 10);
 
-    let class0 = css_meta.classes.get(0).unwrap();
+    let class0 = css_meta.classes.first().unwrap();
     assert_eq!(class0.name, "class-0");
     assert_eq!(class0.loc, Location::from([1, 0], [1, 7]));
 
@@ -92,7 +158,9 @@ fn test_parse_css() {
     assert_eq!(class9.name, "class-9");
     assert_eq!(class9.loc, Location::from([30, 16], [30, 23]));
 
-    let not_found = parse_css_class("test/fixtures/component-notfound.css").err();
+    let not_found = parse_css_class("test/fixtures/component-notfound.css")
+        .err()
+        .map(|e| e.message);
     assert_eq!(
         Some(String::from(
             "No such file test/fixtures/component-notfound.css"
@@ -120,3 +188,35 @@ fn test_parse_css() {
         ])
     );
 }
+
+#[test]
+fn test_parse_css_class_names() {
+    let text = ".a {}\n.Header .-b, ._c {}\n.d { background: url(x.png); width: 0.5em; }";
+    let css_meta = parse_css_source_with("a.css", text, |_| None).unwrap();
+    let names: Vec<&str> = css_meta.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["a", "Header", "-b", "_c", "d"]);
+    assert_eq!(css_meta.classes[1].loc, Location::from([2, 1], [2, 7]));
+}
+
+#[test]
+fn test_parse_css_source_with() {
+    let text = "@import './a.css';\n@import url(\"../b/./c.css\");\n.item {}";
+    let css_meta = parse_css_source_with("/app/pages/index.css", text, |path| {
+        Some(normalize_path(path).to_string_lossy().into_owned())
+    })
+    .unwrap();
+    assert_eq!(
+        css_meta.imports.unwrap(),
+        vec!["/app/pages/a.css", "/app/b/c.css"]
+    );
+    assert_eq!(css_meta.classes.len(), 1);
+
+    let css_meta = parse_css_class("test/fixtures/project/pages/index/index.css").unwrap();
+    let imports = css_meta.imports.unwrap();
+    assert_eq!(imports.len(), 2);
+    assert!(imports[1].ends_with("node_modules/@demo/ui/miniprogram_dist/common/base.css"));
+    assert_eq!(
+        normalize_path(Path::new("../a/../../b")),
+        Path::new("../../b")
+    );
+}
@@ -0,0 +1,983 @@
+//! 解析模板中 `{{ }}`、`s-if`、`s-for` 等绑定的表达式，提取其中引用的变量和成员访问路径，
+//! 以及 `class` 中的静态类名和表达式中的字符串类名
+
+use crate::parser::meta::{
+    AliasMeta, AttributeMeta, BindingKind, BindingMeta, CallMeta, ClassUsageMeta, Diagnostic,
+    ElementMeta, ExpressionMeta, ForMeta, Location, Position, ReferenceMeta, TemplateBindings,
+    TemplateMeta, TemplateNode, ValuePart,
+};
+use crate::parser::script::{exceeds_nesting_depth, MAX_NESTING_DEPTH};
+use swc_common::{BytePos, Spanned};
+use swc_ecma_ast::{
+    BinaryOp, EsVersion, Expr, ExprOrSpread, Ident, Lit, MemberExpr, MemberProp, OptChainBase,
+    OptChainExpr, Prop, PropName, PropOrSpread, Stmt,
+};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+
+/// 值为类名的属性
+const CLASS_ATTRIBUTES: &[&str] = &["class", "hover-class", "s-bind:class"];
+
+/// 解析绑定表达式，`start` 为 `source` 在模板中的开始位置
+pub fn parse_expression(source: &str, start: &Position) -> Result<ExpressionMeta, Diagnostic> {
+    parse_wrapped(source, start, "(")
+}
+
+/// 解析省略了花括号的对象，例如 `<template is="x" data="{{a: 1, ...b}}">`，不是对象时按普通表达式解析
+pub fn parse_object_expression(
+    source: &str,
+    start: &Position,
+) -> Result<ExpressionMeta, Diagnostic> {
+    parse_wrapped(source, start, "({").or_else(|_| parse_expression(source, start))
+}
+
+/// 解析表达式中对模块函数的调用，例如 `utils.format(a, b)`，省略了花括号的对象按对象解析
+pub fn parse_calls(source: &str, start: &Position) -> Result<Vec<CallMeta>, Diagnostic> {
+    let calls = |expr: &Expr, collector: &mut Collector| {
+        collector.expr(expr);
+        std::mem::take(&mut collector.calls)
+    };
+    parse_ast(source, start, "(", calls)
+        .or_else(|error| parse_ast(source, start, "({", calls).map_err(|_| error))
+}
+
+/// 解析 `<template is="x" data="{{a: 1, b}}">` 中传入的 key，
+/// 有展开、计算属性名或者不是对象时无法确定，返回 `None`
+pub fn parse_template_data(
+    source: &str,
+    start: &Position,
+) -> Result<Option<Vec<String>>, Diagnostic> {
+    let keys = |expr: &Expr, _: &mut Collector| object_keys(expr);
+    parse_ast(source, start, "({", keys).or_else(|_| parse_ast(source, start, "(", keys))
+}
+
+fn object_keys(expr: &Expr) -> Option<Vec<String>> {
+    let object = match expr {
+        Expr::Paren(paren) => return object_keys(&paren.expr),
+        Expr::Object(object) => object,
+        _ => return None,
+    };
+    let mut keys = vec![];
+    for prop in &object.props {
+        let key = match prop {
+            PropOrSpread::Spread(_) => return None,
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::Shorthand(ident) => ident.sym.to_string(),
+                Prop::KeyValue(key_value) => match &key_value.key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(s) => s.value.to_string(),
+                    PropName::Num(n) => n.value.to_string(),
+                    _ => return None,
+                },
+                _ => return None,
+            },
+        };
+        keys.push(key);
+    }
+    Some(keys)
+}
+
+/// 解析 `s-for` 的值，支持 `item, index in list`、`(item, index) in list`、`list`、
+/// `{{list}}` 以及末尾的 `trackBy item.id`
+pub fn parse_for(value: &str, start: &Position) -> Result<ForMeta, Diagnostic> {
+    let alias_re =
+        regex!(r"^\s*\(?\s*([A-Za-z_$][\w$]*)\s*(?:,\s*([A-Za-z_$][\w$]*)\s*)?\)?\s*\bin\s");
+    let (item, index, list_start) = match alias_re.captures(value) {
+        Some(captures) => {
+            let alias = |index| {
+                captures.get(index).map(|m: regex::Match| AliasMeta {
+                    name: m.as_str().to_string(),
+                    loc: location(value, start, m.start(), m.end()),
+                })
+            };
+            (alias(1), alias(2), captures.get(0).unwrap().end())
+        }
+        None => (None, None, 0),
+    };
+    let (list_end, track_by) = match regex!(r"\strackBy\s").find(&value[list_start..]) {
+        Some(m) => {
+            let (expression_start, expression_end) =
+                trim_range(value, list_start + m.end(), value.len());
+            let track_by = parse_expression(
+                &value[expression_start..expression_end],
+                &position(value, start, expression_start),
+            )?;
+            (list_start + m.start(), Some(track_by))
+        }
+        None => (value.len(), None),
+    };
+    let (list_start, list_end) = trim_range(value, list_start, list_end);
+    let list = parse_expression(
+        &value[list_start..list_end],
+        &position(value, start, list_start),
+    )?;
+    Ok(ForMeta {
+        item,
+        index,
+        list,
+        track_by,
+    })
+}
+
+/// 解析 `class` 中插值的表达式，提取可能成为类名的字符串
+///
+/// 只取条件表达式的分支、`&&` 右侧、`||` 和 `??` 两侧、数组元素以及对象的 key，
+/// `a === 'x'` 中比较的字符串和 `'item-' + a` 拼接的字符串不是类名
+pub fn parse_class_expression(
+    source: &str,
+    start: &Position,
+) -> Result<Vec<(String, Location)>, Diagnostic> {
+    parse_ast(source, start, "(", |expr, collector| {
+        let mut classes = vec![];
+        collector.classes(expr, &mut classes);
+        classes
+    })
+}
+
+/// 提取模板中所有的绑定表达式
+pub fn extract_bindings(template: &TemplateMeta) -> TemplateBindings {
+    let mut result = TemplateBindings::default();
+    for node in &template.nodes {
+        collect_node(node, &mut result);
+    }
+    result
+}
+
+impl TemplateBindings {
+    fn push(
+        &mut self,
+        kind: BindingKind,
+        attribute: Option<&str>,
+        expression: Result<ExpressionMeta, Diagnostic>,
+    ) {
+        match expression {
+            Ok(expression) => self.bindings.push(BindingMeta {
+                kind,
+                attribute: attribute.map(String::from),
+                expression,
+            }),
+            Err(diagnostic) => self.diagnostics.push(diagnostic),
+        }
+    }
+}
+
+fn collect_node(node: &TemplateNode, result: &mut TemplateBindings) {
+    match node {
+        TemplateNode::Element(element) => {
+            for attribute in &element.attributes {
+                attribute_bindings(element, attribute, result);
+            }
+            for child in &element.children {
+                collect_node(child, result);
+            }
+        }
+        TemplateNode::Interpolation(interpolation) => result.push(
+            BindingKind::Text,
+            None,
+            parse_expression(
+                &interpolation.expression,
+                &interpolation.expression_loc.start,
+            ),
+        ),
+        _ => (),
+    }
+}
+
+/// 提取元素的一个属性中的绑定表达式
+pub(crate) fn attribute_bindings(
+    element: &ElementMeta,
+    attribute: &AttributeMeta,
+    result: &mut TemplateBindings,
+) {
+    let name = attribute.name.as_str();
+    let kind = match name {
+        "s-if" => BindingKind::If,
+        "s-elif" => BindingKind::Elif,
+        "s-show" => BindingKind::Show,
+        "s-for" => {
+            if let (Some(value), Some(value_loc)) = (&attribute.value, &attribute.value_loc) {
+                match parse_for(value, &value_loc.start) {
+                    Ok(for_meta) => {
+                        result.push(BindingKind::For, Some(name), Ok(for_meta.list));
+                        if let Some(track_by) = for_meta.track_by {
+                            result.push(BindingKind::TrackBy, Some(name), Ok(track_by));
+                        }
+                    }
+                    Err(diagnostic) => result.diagnostics.push(diagnostic),
+                }
+            }
+            return;
+        }
+        _ => BindingKind::Attribute,
+    };
+
+    let has_interpolation = attribute
+        .parts
+        .iter()
+        .any(|part| matches!(part, ValuePart::Interpolation(_)));
+    // 指令的值可以省略 `{{ }}`
+    if kind != BindingKind::Attribute && !has_interpolation {
+        if let (Some(value), Some(value_loc)) = (&attribute.value, &attribute.value_loc) {
+            result.push(kind, Some(name), parse_expression(value, &value_loc.start));
+        }
+        return;
+    }
+
+    let object = element.name == "template" && name == "data";
+    for part in &attribute.parts {
+        if let ValuePart::Interpolation(interpolation) = part {
+            let start = &interpolation.expression_loc.start;
+            let expression = if object {
+                parse_object_expression(&interpolation.expression, start)
+            } else {
+                parse_expression(&interpolation.expression, start)
+            };
+            result.push(kind.clone(), Some(name), expression);
+        }
+    }
+}
+
+/// 提取模板中 `class`、`hover-class` 和 `s-bind:class` 使用的类名，无法解析的表达式忽略
+///
+/// 与插值直接相连的文本是动态拼接的类名，例如 `item-{{index}}`，不在结果中
+pub fn extract_classes(template: &TemplateMeta) -> Vec<ClassUsageMeta> {
+    let mut result = vec![];
+    collect_classes(&template.nodes, &mut result);
+    result
+}
+
+fn collect_classes(nodes: &[TemplateNode], result: &mut Vec<ClassUsageMeta>) {
+    for node in nodes {
+        if let TemplateNode::Element(element) = node {
+            for attribute in &element.attributes {
+                attribute_classes(attribute, result);
+            }
+            collect_classes(&element.children, result);
+        }
+    }
+}
+
+fn attribute_classes(attribute: &AttributeMeta, result: &mut Vec<ClassUsageMeta>) {
+    let name = attribute.name.as_str();
+    if !CLASS_ATTRIBUTES.contains(&name) {
+        return;
+    }
+    let mut push = |classes: Vec<(String, Location)>| {
+        result.extend(classes.into_iter().map(|(class, loc)| ClassUsageMeta {
+            name: class,
+            loc,
+            attribute: name.to_string(),
+        }));
+    };
+
+    let parts = &attribute.parts;
+    let has_interpolation = parts
+        .iter()
+        .any(|part| matches!(part, ValuePart::Interpolation(_)));
+    // `s-bind:class` 的值可以省略 `{{ }}`
+    if name == "s-bind:class" && !has_interpolation {
+        if let (Some(value), Some(value_loc)) = (&attribute.value, &attribute.value_loc) {
+            push(parse_class_expression(value, &value_loc.start).unwrap_or_default());
+        }
+        return;
+    }
+
+    let text_at = |index: Option<usize>| match index.and_then(|i| parts.get(i)) {
+        Some(ValuePart::Text { value, .. }) => Some(value.as_str()),
+        _ => None,
+    };
+    for (index, part) in parts.iter().enumerate() {
+        let glued_before = index > 0 && !ends_with_space(text_at(index.checked_sub(1)));
+        let glued_after = index + 1 < parts.len() && !starts_with_space(text_at(Some(index + 1)));
+        match part {
+            ValuePart::Text { value, loc } => {
+                let mut words = split_words(value, &loc.start);
+                if glued_after && !value.ends_with(char::is_whitespace) {
+                    words.pop();
+                }
+                if glued_before && !value.starts_with(char::is_whitespace) && !words.is_empty() {
+                    words.remove(0);
+                }
+                push(words);
+            }
+            ValuePart::Interpolation(interpolation) => {
+                if glued_before || glued_after {
+                    continue;
+                }
+                let start = &interpolation.expression_loc.start;
+                push(parse_class_expression(&interpolation.expression, start).unwrap_or_default());
+            }
+        }
+    }
+}
+
+/// 前一个部分是插值或以空白结尾的文本时，插值与它相连
+fn ends_with_space(text: Option<&str>) -> bool {
+    text.is_some_and(|text| text.ends_with(char::is_whitespace))
+}
+
+fn starts_with_space(text: Option<&str>) -> bool {
+    text.is_some_and(|text| text.starts_with(char::is_whitespace))
+}
+
+/// 按空白拆分类名，`start` 为 `value` 开头的位置
+fn split_words(value: &str, start: &Position) -> Vec<(String, Location)> {
+    regex!(r"\S+")
+        .find_iter(value)
+        .map(|m| {
+            (
+                m.as_str().to_string(),
+                location(value, start, m.start(), m.end()),
+            )
+        })
+        .collect()
+}
+
+/// 去掉 `[start, end)` 两端的空白和 `{{ }}`
+fn trim_range(value: &str, start: usize, end: usize) -> (usize, usize) {
+    let trim = |start: usize, end: usize| {
+        let text = &value[start..end];
+        (
+            start + text.len() - text.trim_start().len(),
+            end - (text.len() - text.trim_end().len()),
+        )
+    };
+    let (start, end) = trim(start, end);
+    let text = &value[start..end];
+    if text.len() >= 4 && text.starts_with("{{") && text.ends_with("}}") {
+        trim(start + 2, end - 2)
+    } else {
+        (start, end)
+    }
+}
+
+/// `source` 中字节偏移 `offset` 处在模板中的位置，`start` 为 `source` 开头的位置
+fn position(source: &str, start: &Position, offset: usize) -> Position {
+    let before = &source[..offset];
+    match before.rfind('\n') {
+        Some(i) => Position {
+            line: start.line + before.matches('\n').count(),
+            column: before[i + 1..].chars().count(),
+        },
+        None => Position {
+            line: start.line,
+            column: start.column + before.chars().count(),
+        },
+    }
+}
+
+fn location(source: &str, start: &Position, lo: usize, hi: usize) -> Location {
+    Location {
+        start: position(source, start, lo),
+        end: position(source, start, hi),
+    }
+}
+
+/// 用 `open` 和对应的右括号包裹表达式后按 js 解析
+fn parse_wrapped(source: &str, start: &Position, open: &str) -> Result<ExpressionMeta, Diagnostic> {
+    parse_ast(source, start, open, |expr, collector| {
+        collector.expr(expr);
+        ExpressionMeta {
+            source: source.to_string(),
+            loc: location(source, start, 0, source.len()),
+            references: std::mem::take(&mut collector.references),
+        }
+    })
+}
+
+/// 包裹后解析表达式，由 `visit` 从语法树中提取结果
+fn parse_ast<T>(
+    source: &str,
+    start: &Position,
+    open: &str,
+    visit: impl FnOnce(&Expr, &mut Collector) -> T,
+) -> Result<T, Diagnostic> {
+    let whole = location(source, start, 0, source.len());
+    if source.trim().is_empty() {
+        return Err(Diagnostic::error(String::from("empty expression"), whole));
+    }
+    if exceeds_nesting_depth(source, MAX_NESTING_DEPTH) {
+        return Err(Diagnostic::error(
+            format!("expression nesting exceeds {} levels", MAX_NESTING_DEPTH),
+            whole,
+        ));
+    }
+
+    // 换行避免表达式末尾的 `//` 注释掉右括号
+    let close: String = open
+        .chars()
+        .rev()
+        .map(|c| if c == '(' { ')' } else { '}' })
+        .collect();
+    let code = format!("{}{}\n{}", open, source, close);
+    let mut collector = Collector {
+        source,
+        start,
+        offset: open.len(),
+        depth: 0,
+        references: vec![],
+        calls: vec![],
+    };
+    let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        EsVersion::Es2022,
+        StringInput::new(&code, BytePos(0), BytePos(code.len() as u32)),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    let script = parser.parse_script();
+    let error = match script {
+        Ok(script) => match parser.take_errors().into_iter().next() {
+            Some(error) => error,
+            None => match script.body.as_slice() {
+                // `a) + (b` 包裹后也是合法的表达式，括号必须包含整个源码
+                [Stmt::Expr(stmt)] if is_wrapped(&stmt.expr, code.len()) => {
+                    return Ok(visit(&stmt.expr, &mut collector));
+                }
+                _ => return Err(Diagnostic::error(String::from("invalid expression"), whole)),
+            },
+        },
+        Err(error) => error,
+    };
+    let span = error.span();
+    Err(Diagnostic::error(
+        format!("invalid expression: {}", error.kind().msg()),
+        collector.location(span.lo, span.hi),
+    ))
+}
+
+/// 最外层是包裹源码的括号
+fn is_wrapped(expr: &Expr, len: usize) -> bool {
+    matches!(expr, Expr::Paren(paren) if paren.span.lo == BytePos(0) && paren.span.hi == BytePos(len as u32))
+}
+
+/// 静态的成员访问属性名，`a.b`、`a['b']`、`a[0]`
+fn static_prop(prop: &MemberProp) -> Option<String> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        MemberProp::Computed(computed) => match &*computed.expr {
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            Expr::Lit(Lit::Num(n)) => Some(n.value.to_string()),
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+/// 收集表达式中的变量引用
+struct Collector<'a> {
+    source: &'a str,
+    start: &'a Position,
+    /// 包裹表达式的左括号长度
+    offset: usize,
+    depth: usize,
+    references: Vec<ReferenceMeta>,
+    /// `module.function()` 形式的调用
+    calls: Vec<CallMeta>,
+}
+
+impl<'a> Collector<'a> {
+    fn location(&self, lo: BytePos, hi: BytePos) -> Location {
+        let offset = |pos: BytePos| {
+            (pos.0 as usize)
+                .saturating_sub(self.offset)
+                .min(self.source.len())
+        };
+        location(self.source, self.start, offset(lo), offset(hi))
+    }
+
+    fn reference(&mut self, ident: &Ident, path: Vec<String>, hi: BytePos) {
+        let reference = ReferenceMeta {
+            name: ident.sym.to_string(),
+            path,
+            loc: self.location(ident.span.lo, ident.span.hi),
+            path_loc: self.location(ident.span.lo, hi),
+        };
+        self.references.push(reference);
+    }
+
+    fn args(&mut self, args: &[ExprOrSpread]) {
+        for arg in args {
+            self.expr(&arg.expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        // 嵌套层级在解析前已经检查过，这里防止 `a + a + ...` 等左递归的长链
+        if self.depth >= MAX_NESTING_DEPTH {
+            return;
+        }
+        self.depth += 1;
+        match expr {
+            Expr::Ident(ident) => self.reference(ident, vec![ident.sym.to_string()], ident.span.hi),
+            Expr::Member(member) => self.member(member),
+            Expr::OptChain(chain) => match &chain.base {
+                OptChainBase::Member(member) => self.member(member),
+                OptChainBase::Call(call) => {
+                    self.expr(&call.callee);
+                    self.args(&call.args);
+                }
+            },
+            Expr::Array(array) => {
+                for elem in array.elems.iter().flatten() {
+                    self.expr(&elem.expr);
+                }
+            }
+            Expr::Object(object) => {
+                for prop in &object.props {
+                    match prop {
+                        PropOrSpread::Spread(spread) => self.expr(&spread.expr),
+                        PropOrSpread::Prop(prop) => match &**prop {
+                            Prop::Shorthand(ident) => {
+                                self.reference(ident, vec![ident.sym.to_string()], ident.span.hi)
+                            }
+                            Prop::KeyValue(key_value) => {
+                                if let PropName::Computed(computed) = &key_value.key {
+                                    self.expr(&computed.expr);
+                                }
+                                self.expr(&key_value.value);
+                            }
+                            Prop::Assign(assign) => self.expr(&assign.value),
+                            _ => (),
+                        },
+                    }
+                }
+            }
+            Expr::Unary(unary) => self.expr(&unary.arg),
+            Expr::Update(update) => self.expr(&update.arg),
+            Expr::Bin(bin) => {
+                self.expr(&bin.left);
+                self.expr(&bin.right);
+            }
+            Expr::Assign(assign) => self.expr(&assign.right),
+            Expr::Cond(cond) => {
+                self.expr(&cond.test);
+                self.expr(&cond.cons);
+                self.expr(&cond.alt);
+            }
+            Expr::Call(call) => {
+                if let Some(callee) = call.callee.as_expr() {
+                    self.call(callee, &call.args);
+                    self.expr(callee);
+                }
+                self.args(&call.args);
+            }
+            Expr::New(new) => {
+                self.expr(&new.callee);
+                self.args(new.args.as_deref().unwrap_or_default());
+            }
+            Expr::Seq(seq) => {
+                for expr in &seq.exprs {
+                    self.expr(expr);
+                }
+            }
+            Expr::Tpl(tpl) => {
+                for expr in &tpl.exprs {
+                    self.expr(expr);
+                }
+            }
+            Expr::TaggedTpl(tagged) => {
+                self.expr(&tagged.tag);
+                for expr in &tagged.tpl.exprs {
+                    self.expr(expr);
+                }
+            }
+            Expr::Paren(paren) => self.expr(&paren.expr),
+            // 字面量、this 以及模板中不支持的函数、类等
+            _ => (),
+        }
+        self.depth -= 1;
+    }
+
+    /// 字符串中的类名，含有转义字符的字符串与源码对应不上，忽略
+    fn string_classes(&self, lo: BytePos, hi: BytePos, classes: &mut Vec<(String, Location)>) {
+        let start = (lo.0 as usize).saturating_sub(self.offset) + 1;
+        let end = (hi.0 as usize).saturating_sub(self.offset + 1);
+        if start > end || end > self.source.len() {
+            return;
+        }
+        let raw = &self.source[start..end];
+        if !raw.contains('\\') {
+            let position = position(self.source, self.start, start);
+            classes.extend(split_words(raw, &position));
+        }
+    }
+
+    /// 收集可能成为类名的字符串，见 [`parse_class_expression`]
+    fn classes(&mut self, expr: &Expr, classes: &mut Vec<(String, Location)>) {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return;
+        }
+        self.depth += 1;
+        match expr {
+            Expr::Lit(Lit::Str(s)) => self.string_classes(s.span.lo, s.span.hi, classes),
+            Expr::Cond(cond) => {
+                self.classes(&cond.cons, classes);
+                self.classes(&cond.alt, classes);
+            }
+            Expr::Bin(bin) => match bin.op {
+                BinaryOp::LogicalAnd => self.classes(&bin.right, classes),
+                BinaryOp::LogicalOr | BinaryOp::NullishCoalescing => {
+                    self.classes(&bin.left, classes);
+                    self.classes(&bin.right, classes);
+                }
+                _ => (),
+            },
+            Expr::Array(array) => {
+                for elem in array.elems.iter().flatten() {
+                    self.classes(&elem.expr, classes);
+                }
+            }
+            // `{active: isActive}` 的 key 为类名
+            Expr::Object(object) => {
+                for prop in &object.props {
+                    if let PropOrSpread::Prop(prop) = prop {
+                        match &**prop {
+                            Prop::KeyValue(key_value) => match &key_value.key {
+                                PropName::Ident(ident) => classes.push((
+                                    ident.sym.to_string(),
+                                    self.location(ident.span.lo, ident.span.hi),
+                                )),
+                                PropName::Str(s) => {
+                                    self.string_classes(s.span.lo, s.span.hi, classes)
+                                }
+                                _ => (),
+                            },
+                            Prop::Shorthand(ident) => classes.push((
+                                ident.sym.to_string(),
+                                self.location(ident.span.lo, ident.span.hi),
+                            )),
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            Expr::Seq(seq) => {
+                if let Some(last) = seq.exprs.last() {
+                    self.classes(last, classes);
+                }
+            }
+            Expr::Paren(paren) => self.classes(&paren.expr, classes),
+            _ => (),
+        }
+        self.depth -= 1;
+    }
+
+    /// 记录 `module.function(...)` 形式的调用
+    fn call(&mut self, callee: &Expr, args: &[ExprOrSpread]) {
+        let member = match callee {
+            Expr::Member(member) => member,
+            _ => return,
+        };
+        if let (Expr::Ident(module), MemberProp::Ident(function)) = (&*member.obj, &member.prop) {
+            let call = CallMeta {
+                module: module.sym.to_string(),
+                function: function.sym.to_string(),
+                arguments: (!args.iter().any(|arg| arg.spread.is_some())).then_some(args.len()),
+                loc: self.location(function.span.lo, function.span.hi),
+            };
+            self.calls.push(call);
+        }
+    }
+
+    /// `a.b[c].d` 记录 `a.b` 的访问路径，计算属性 `c` 单独收集
+    fn member(&mut self, member: &MemberExpr) {
+        // 从外到内展开成员访问
+        let mut chain = vec![member];
+        let mut object = &*member.obj;
+        while let Expr::Member(inner)
+        | Expr::OptChain(OptChainExpr {
+            base: OptChainBase::Member(inner),
+            ..
+        }) = object
+        {
+            chain.push(inner);
+            object = &inner.obj;
+        }
+        chain.reverse();
+
+        let static_len = match object {
+            Expr::Ident(ident) => {
+                let props: Vec<String> = chain.iter().map_while(|m| static_prop(&m.prop)).collect();
+                let len = props.len();
+                let hi = match len {
+                    0 => ident.span.hi,
+                    len => chain[len - 1].span.hi,
+                };
+                let mut path = vec![ident.sym.to_string()];
+                path.extend(props);
+                self.reference(ident, path, hi);
+                len
+            }
+            object => {
+                self.expr(object);
+                0
+            }
+        };
+        for m in &chain[static_len..] {
+            if let MemberProp::Computed(computed) = &m.prop {
+                self.expr(&computed.expr);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_expression() {
+    let start = Position {
+        line: 3,
+        column: 10,
+    };
+    let expression = parse_expression(
+        " a.b[0]['c'] + fn(x)[y].z ? {k: v, s, [key]: 1} : list?.[i].name",
+        &start,
+    )
+    .unwrap();
+    let names: Vec<&str> = expression
+        .references
+        .iter()
+        .map(|r| r.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["a", "fn", "x", "y", "v", "s", "key", "list", "i"]
+    );
+
+    let a = &expression.references[0];
+    assert_eq!(a.path, vec!["a", "b", "0", "c"]);
+    assert_eq!(a.loc, Location::from([3, 11], [3, 12]));
+    assert_eq!(a.path_loc, Location::from([3, 11], [3, 22]));
+    assert_eq!(expression.references[1].path, vec!["fn"]);
+    // 计算属性中断访问路径
+    assert_eq!(expression.references[7].path, vec!["list"]);
+    assert_eq!(
+        expression.references[7].loc,
+        Location::from([3, 60], [3, 64])
+    );
+
+    // 多行表达式按模板中的行列计算位置
+    let expression = parse_expression("a &&\n  标题.长度 > b", &start).unwrap();
+    assert_eq!(expression.references[1].path, vec!["标题", "长度"]);
+    assert_eq!(expression.references[1].loc, Location::from([4, 2], [4, 4]));
+    assert_eq!(
+        expression.references[1].path_loc,
+        Location::from([4, 2], [4, 7])
+    );
+    assert_eq!(
+        expression.references[2].loc,
+        Location::from([4, 10], [4, 11])
+    );
+
+    let diagnostic = parse_expression("a +", &start).unwrap_err();
+    assert!(diagnostic.message.starts_with("invalid expression"));
+    assert_eq!(diagnostic.loc.start.line, 3);
+    let diagnostic = parse_expression("a) + (b", &start).unwrap_err();
+    assert_eq!(diagnostic.message, "invalid expression");
+    assert_eq!(
+        parse_expression("  ", &start).unwrap_err().message,
+        "empty expression"
+    );
+    // 末尾的注释不影响解析
+    assert_eq!(
+        parse_expression("a // b", &start).unwrap().references.len(),
+        1
+    );
+
+    let expression = parse_object_expression("a: 1, ...b, c", &start).unwrap();
+    let names: Vec<&str> = expression
+        .references
+        .iter()
+        .map(|r| r.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["b", "c"]);
+    assert_eq!(
+        expression.references[0].loc,
+        Location::from([3, 19], [3, 20])
+    );
+    let expression = parse_object_expression("item", &start).unwrap();
+    assert_eq!(expression.references[0].name, "item");
+}
+
+#[test]
+fn test_parse_for() {
+    let start = Position { line: 1, column: 0 };
+    let for_meta = parse_for("(item, idx) in {{list}} trackBy item.id", &start).unwrap();
+    assert_eq!(for_meta.item.as_ref().unwrap().name, "item");
+    assert_eq!(for_meta.item.unwrap().loc, Location::from([1, 1], [1, 5]));
+    assert_eq!(for_meta.index.unwrap().name, "idx");
+    assert_eq!(for_meta.list.source, "list");
+    assert_eq!(for_meta.list.loc, Location::from([1, 17], [1, 21]));
+    let track_by = for_meta.track_by.unwrap();
+    assert_eq!(track_by.references[0].path, vec!["item", "id"]);
+    assert_eq!(track_by.loc, Location::from([1, 32], [1, 39]));
+
+    let for_meta = parse_for("item in data.list", &start).unwrap();
+    assert_eq!(for_meta.item.unwrap().name, "item");
+    assert!(for_meta.index.is_none());
+    assert_eq!(for_meta.list.references[0].path, vec!["data", "list"]);
+
+    // 省略 item 和 index
+    let for_meta = parse_for("{{ list }}", &start).unwrap();
+    assert!(for_meta.item.is_none());
+    assert_eq!(for_meta.list.loc, Location::from([1, 3], [1, 7]));
+    let for_meta = parse_for("inputs", &start).unwrap();
+    assert_eq!(for_meta.list.references[0].name, "inputs");
+
+    assert_eq!(
+        parse_for("item in ", &start).unwrap_err().message,
+        "empty expression"
+    );
+}
+
+#[test]
+fn test_extract_bindings() {
+    use crate::parser::parse_template;
+
+    let template = parse_template("test/fixtures/component.swan").unwrap();
+    let bindings = extract_bindings(&template);
+    assert!(bindings.diagnostics.is_empty());
+    let summary: Vec<(BindingKind, Option<&str>, &str)> = bindings
+        .bindings
+        .iter()
+        .map(|b| {
+            (
+                b.kind.clone(),
+                b.attribute.as_deref(),
+                b.expression.source.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (BindingKind::If, Some("s-if"), "ifOpen"),
+            (
+                BindingKind::Attribute,
+                Some("class"),
+                r#"data1 < 10 ? "data1" : data2 > 10 ? "data2" : """#
+            ),
+            (BindingKind::For, Some("s-for"), "list"),
+            (BindingKind::Text, None, "data2"),
+        ]
+    );
+    let class = &bindings.bindings[1].expression;
+    assert_eq!(class.references.len(), 2);
+    assert_eq!(class.references[1].loc, Location::from([2, 50], [2, 55]));
+
+    let template = crate::parser::parse_template_source(
+        "page.swan",
+        "<template is=\"card\" data=\"{{title, count: n + 1}}\" />\n\
+         <view s-show=\"{{visible}}\" bindtap=\"onTap\">{{a +}}</view>",
+    )
+    .unwrap();
+    let bindings = extract_bindings(&template);
+    let names: Vec<&str> = bindings
+        .bindings
+        .iter()
+        .flat_map(|b| b.expression.references.iter().map(|r| r.name.as_str()))
+        .collect();
+    assert_eq!(names, vec!["title", "n", "visible"]);
+    assert_eq!(bindings.bindings[1].kind, BindingKind::Show);
+    assert_eq!(bindings.diagnostics.len(), 1);
+    assert_eq!(bindings.diagnostics[0].loc.start.line, 2);
+}
+
+#[test]
+fn test_extract_classes() {
+    use crate::parser::parse_template_source;
+
+    let start = Position { line: 1, column: 0 };
+    let names = |classes: &[(String, Location)]| -> Vec<String> {
+        classes.iter().map(|(name, _)| name.clone()).collect()
+    };
+    let classes = parse_class_expression(
+        "a === 'x' ? 'on big' : b && \"off\" || ['c', {d: 1, 'e': 2}]",
+        &start,
+    )
+    .unwrap();
+    assert_eq!(names(&classes), vec!["on", "big", "off", "c", "d", "e"]);
+    assert_eq!(classes[1].1, Location::from([1, 16], [1, 19]));
+    assert_eq!(classes[5].1, Location::from([1, 51], [1, 52]));
+    assert!(parse_class_expression("'item-' + type", &start)
+        .unwrap()
+        .is_empty());
+    assert!(parse_class_expression("'a\\'b'", &start)
+        .unwrap()
+        .is_empty());
+
+    let template = parse_template_source(
+        "component.swan",
+        r#"<view s-if="ifOpen">
+    <view class='class-0 {{data1 < 10 ? "data1" : data2 > 10 ? "data2" : ""}}' s-for="list">
+        <text class="item-{{index}} title {{a}}-x last" hover-class="hover" />
+        <text s-bind:class="{active: on}" data-class="no" />
+    </view>
+</view>"#,
+    )
+    .unwrap();
+    let classes = extract_classes(&template);
+    let names: Vec<&str> = classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["class-0", "data1", "data2", "title", "last", "hover", "active"]
+    );
+    assert_eq!(classes[0].loc, Location::from([2, 17], [2, 24]));
+    assert_eq!(classes[1].loc, Location::from([2, 41], [2, 46]));
+    assert_eq!(classes[5].attribute, "hover-class");
+    assert_eq!(classes[6].loc, Location::from([4, 29], [4, 35]));
+}
+
+#[test]
+fn test_parse_template_data() {
+    let start = Position { line: 1, column: 0 };
+    let keys = parse_template_data("time: log.time, message, 'a-b': 1", &start).unwrap();
+    assert_eq!(
+        keys,
+        Some(vec![
+            String::from("time"),
+            String::from("message"),
+            String::from("a-b")
+        ])
+    );
+    let keys = parse_template_data("{title}", &start).unwrap();
+    assert_eq!(keys, Some(vec![String::from("title")]));
+    assert_eq!(parse_template_data("...item, a: 1", &start).unwrap(), None);
+    // 省略花括号时 `item` 是简写属性
+    assert_eq!(
+        parse_template_data("item", &start).unwrap(),
+        Some(vec![String::from("item")])
+    );
+    assert_eq!(parse_template_data("a ? b : c", &start).unwrap(), None);
+    assert!(parse_template_data("a: ", &start).is_err());
+}
+
+#[test]
+fn test_parse_calls() {
+    let start = Position {
+        line: 2,
+        column: 10,
+    };
+    let calls = parse_calls("utils.format(date, 'YYYY') + tools.join(...list)", &start).unwrap();
+    assert_eq!(
+        calls,
+        vec![
+            CallMeta {
+                module: String::from("utils"),
+                function: String::from("format"),
+                arguments: Some(2),
+                loc: Location::from([2, 16], [2, 22]),
+            },
+            CallMeta {
+                module: String::from("tools"),
+                function: String::from("join"),
+                arguments: None,
+                loc: Location::from([2, 45], [2, 49]),
+            },
+        ]
+    );
+    let calls = parse_calls("a.b.c(x, utils.upper(y))", &start).unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].function, "upper");
+    let calls = parse_calls("title: utils.upper(title)", &start).unwrap();
+    assert_eq!(calls[0].arguments, Some(1));
+    assert!(parse_calls("utils.(", &start).is_err());
+}
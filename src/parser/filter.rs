@@ -0,0 +1,442 @@
+//! 解析模板中 `<filter>`、`<import-sjs>` 引用的模块，提取导出的函数及其参数和注释
+
+use crate::parser::meta::{
+    FilterFunctionMeta, FilterModuleMeta, Location, ParseError, ParseErrorKind, Position,
+};
+use crate::parser::script::{
+    convert_bytepos_pos, exceeds_nesting_depth, get_comment, MAX_NESTING_DEPTH,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, BytePos, Spanned};
+use swc_common::{FileName, SourceFile, SourceMap};
+use swc_ecma_ast::{
+    BlockStmtOrExpr, Decl, Expr, FnExpr, MemberExpr, MemberProp, ModuleDecl, ModuleItem, ObjectLit,
+    Pat, PatOrExpr, Prop, PropName, PropOrSpread, Stmt,
+};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+
+use super::catch_panic;
+
+/// 读取并解析 filter 或 sjs 文件
+pub fn parse_filter(file_path: &str) -> Result<FilterModuleMeta, ParseError> {
+    let source = fs::read_to_string(file_path).map_err(|_| ParseError::not_found(file_path))?;
+    parse_filter_source(file_path, source)
+}
+
+/// 解析 filter 或 sjs 源码，`file_path` 仅用于错误信息
+pub fn parse_filter_source(
+    file_path: &str,
+    source: String,
+) -> Result<FilterModuleMeta, ParseError> {
+    catch_panic(file_path, || parse_source(file_path, source))
+}
+
+/// 解析模板中内联的 `<filter>` 源码，`start` 为源码在模板中的开始位置，结果中的位置都是模板中的位置
+pub fn parse_inline_filter(
+    file_path: &str,
+    source: &str,
+    start: &Position,
+) -> Result<FilterModuleMeta, ParseError> {
+    let shift = |loc: &Location| {
+        let shift = |position: &Position| Position {
+            line: start.line + position.line - 1,
+            column: match position.line {
+                1 => start.column + position.column,
+                _ => position.column,
+            },
+        };
+        Location {
+            start: shift(&loc.start),
+            end: shift(&loc.end),
+        }
+    };
+    match parse_filter_source(file_path, source.to_string()) {
+        Ok(mut meta) => {
+            for function in &mut meta.functions {
+                function.loc = shift(&function.loc);
+            }
+            Ok(meta)
+        }
+        Err(mut error) => {
+            error.loc = error.loc.as_ref().map(shift);
+            Err(error)
+        }
+    }
+}
+
+fn parse_source(file_path: &str, source: String) -> Result<FilterModuleMeta, ParseError> {
+    if exceeds_nesting_depth(&source, MAX_NESTING_DEPTH) {
+        return Err(ParseError::new(
+            ParseErrorKind::NestingTooDeep,
+            format!(
+                "failed to parse {}, nesting exceeds {} levels",
+                file_path, MAX_NESTING_DEPTH
+            ),
+        ));
+    }
+
+    let cm: Lrc<SourceMap> = Default::default();
+    let sf = cm.new_source_file(FileName::Real(PathBuf::from(file_path)), source);
+    let comments: SingleThreadedComments = Default::default();
+    let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        Default::default(),
+        StringInput::from(&*sf),
+        Some(&comments),
+    );
+    let mut parser = Parser::new_from(lexer);
+    let module = parser.parse_module().map_err(|e| {
+        let mut error = ParseError::new(
+            ParseErrorKind::SyntaxError,
+            format!("failed to parse {}", file_path),
+        );
+        error.loc = Some(convert_bytepos_pos(e.span().lo(), e.span().hi(), &sf));
+        error
+    })?;
+
+    let mut exports = Exports {
+        comments: &comments,
+        sf: &sf,
+        locals: HashMap::new(),
+        functions: vec![],
+    };
+    for item in &module.body {
+        if let ModuleItem::Stmt(stmt) = item {
+            exports.local(stmt);
+        }
+    }
+    for item in &module.body {
+        exports.item(item);
+    }
+    Ok(FilterModuleMeta {
+        functions: exports.functions,
+    })
+}
+
+/// 函数的参数
+struct Signature {
+    params: Vec<String>,
+    required: usize,
+    variadic: bool,
+    comment: Option<String>,
+}
+
+/// 收集模块导出的函数，导出的值是标识符时查找顶层声明的同名函数
+struct Exports<'a> {
+    comments: &'a SingleThreadedComments,
+    sf: &'a SourceFile,
+    /// 顶层的 `function name() {}` 和 `var name = function () {}`
+    locals: HashMap<String, Signature>,
+    functions: Vec<FilterFunctionMeta>,
+}
+
+impl Exports<'_> {
+    fn source(&self, lo: BytePos, hi: BytePos) -> &str {
+        let start = (lo.0 - self.sf.start_pos.0) as usize;
+        let end = (hi.0 - self.sf.start_pos.0) as usize;
+        &self.sf.src[start..end]
+    }
+
+    /// 函数或箭头函数的参数，其他表达式返回 `None`
+    fn signature(&self, expr: &Expr, comment: Option<String>) -> Option<Signature> {
+        let (params, body): (Vec<&Pat>, _) = match expr {
+            Expr::Paren(paren) => return self.signature(&paren.expr, comment),
+            Expr::Fn(function) => (
+                function.function.params.iter().map(|p| &p.pat).collect(),
+                function.function.body.as_ref().map(|b| b.span()),
+            ),
+            Expr::Arrow(arrow) => (
+                arrow.params.iter().collect(),
+                Some(match &arrow.body {
+                    BlockStmtOrExpr::BlockStmt(block) => block.span(),
+                    BlockStmtOrExpr::Expr(expr) => expr.span(),
+                }),
+            ),
+            _ => return None,
+        };
+        let uses_arguments = body.is_some_and(|span| {
+            regex!(r"\barguments\b").is_match(self.source(span.lo(), span.hi()))
+        });
+        Some(Signature {
+            params: params
+                .iter()
+                .map(|pat| self.source(pat.span().lo(), pat.span().hi()).to_string())
+                .collect(),
+            required: params
+                .iter()
+                .take_while(|pat| !matches!(pat, Pat::Assign(_) | Pat::Rest(_)))
+                .count(),
+            variadic: uses_arguments || params.iter().any(|pat| matches!(pat, Pat::Rest(_))),
+            comment,
+        })
+    }
+
+    fn push(&mut self, name: String, lo: BytePos, hi: BytePos, signature: Signature) {
+        self.functions.push(FilterFunctionMeta {
+            name,
+            params: signature.params,
+            required: signature.required,
+            variadic: signature.variadic,
+            comment: signature.comment,
+            loc: convert_bytepos_pos(lo, hi, self.sf),
+        });
+    }
+
+    /// 记录顶层声明的函数
+    fn local(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Decl(Decl::Fn(function)) => {
+                let comment = get_comment(function.span().lo(), self.comments);
+                let expr = Expr::Fn(FnExpr {
+                    ident: None,
+                    function: function.function.clone(),
+                });
+                if let Some(signature) = self.signature(&expr, comment) {
+                    self.locals
+                        .insert(function.ident.sym.to_string(), signature);
+                }
+            }
+            Stmt::Decl(Decl::Var(var)) => {
+                let comment = get_comment(var.span.lo(), self.comments);
+                for declarator in &var.decls {
+                    if let (Pat::Ident(ident), Some(init)) = (&declarator.name, &declarator.init) {
+                        if let Some(signature) = self.signature(init, comment.clone()) {
+                            self.locals.insert(ident.id.sym.to_string(), signature);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// 导出的值，标识符取同名的顶层函数，导出处没有注释时使用函数声明的注释
+    fn export(
+        &mut self,
+        name: String,
+        lo: BytePos,
+        hi: BytePos,
+        value: &Expr,
+        comment: Option<String>,
+    ) {
+        let signature = match value {
+            Expr::Ident(ident) => self.locals.get(&*ident.sym).map(|local| Signature {
+                params: local.params.clone(),
+                required: local.required,
+                variadic: local.variadic,
+                comment: comment.clone().or_else(|| local.comment.clone()),
+            }),
+            value => self.signature(value, comment),
+        };
+        if let Some(signature) = signature {
+            self.push(name, lo, hi, signature);
+        }
+    }
+
+    fn object(&mut self, object: &ObjectLit) {
+        for prop in &object.props {
+            let prop = match prop {
+                PropOrSpread::Prop(prop) => prop,
+                PropOrSpread::Spread(_) => continue,
+            };
+            match &**prop {
+                Prop::KeyValue(key_value) => {
+                    let (name, span) = match &key_value.key {
+                        PropName::Ident(ident) => (ident.sym.to_string(), ident.span),
+                        PropName::Str(s) => (s.value.to_string(), s.span),
+                        _ => continue,
+                    };
+                    let comment = get_comment(span.lo(), self.comments);
+                    self.export(name, span.lo(), span.hi(), &key_value.value, comment);
+                }
+                Prop::Shorthand(ident) => {
+                    let comment = get_comment(ident.span.lo(), self.comments);
+                    let value = Expr::Ident(ident.clone());
+                    self.export(
+                        ident.sym.to_string(),
+                        ident.span.lo(),
+                        ident.span.hi(),
+                        &value,
+                        comment,
+                    );
+                }
+                Prop::Method(method) => {
+                    if let PropName::Ident(ident) = &method.key {
+                        let comment = get_comment(ident.span.lo(), self.comments);
+                        let value = Expr::Fn(FnExpr {
+                            ident: None,
+                            function: method.function.clone(),
+                        });
+                        self.export(
+                            ident.sym.to_string(),
+                            ident.span.lo(),
+                            ident.span.hi(),
+                            &value,
+                            comment,
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn item(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+                if let Some(object) = unwrap_object(&export.expr) {
+                    self.object(object);
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                let comment = get_comment(export.span.lo(), self.comments);
+                match &export.decl {
+                    Decl::Fn(function) => {
+                        let ident = &function.ident;
+                        let value = Expr::Fn(FnExpr {
+                            ident: None,
+                            function: function.function.clone(),
+                        });
+                        self.export(
+                            ident.sym.to_string(),
+                            ident.span.lo(),
+                            ident.span.hi(),
+                            &value,
+                            comment,
+                        );
+                    }
+                    Decl::Var(var) => {
+                        for declarator in &var.decls {
+                            if let (Pat::Ident(ident), Some(init)) =
+                                (&declarator.name, &declarator.init)
+                            {
+                                let span = ident.id.span;
+                                self.export(
+                                    ident.id.sym.to_string(),
+                                    span.lo(),
+                                    span.hi(),
+                                    init,
+                                    comment.clone(),
+                                );
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            ModuleItem::Stmt(Stmt::Expr(stmt)) => {
+                if let Expr::Assign(assign) = &*stmt.expr {
+                    let left = match &assign.left {
+                        PatOrExpr::Expr(expr) => &**expr,
+                        PatOrExpr::Pat(pat) => match &**pat {
+                            Pat::Expr(expr) => &**expr,
+                            _ => return,
+                        },
+                    };
+                    let member = match left {
+                        Expr::Member(member) => member,
+                        _ => return,
+                    };
+                    let comment = get_comment(stmt.span.lo(), self.comments);
+                    match member_path(member).as_deref() {
+                        Some(["module", "exports"]) => {
+                            if let Some(object) = unwrap_object(&assign.right) {
+                                self.object(object);
+                            }
+                        }
+                        Some(["module", "exports", _] | ["exports", _]) => {
+                            if let MemberProp::Ident(ident) = &member.prop {
+                                self.export(
+                                    ident.sym.to_string(),
+                                    ident.span.lo(),
+                                    ident.span.hi(),
+                                    &assign.right,
+                                    comment,
+                                );
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn unwrap_object(expr: &Expr) -> Option<&ObjectLit> {
+    match expr {
+        Expr::Paren(paren) => unwrap_object(&paren.expr),
+        Expr::Object(object) => Some(object),
+        _ => None,
+    }
+}
+
+/// `module.exports.name` 的访问路径
+fn member_path(member: &MemberExpr) -> Option<Vec<&str>> {
+    let mut path = match &*member.obj {
+        Expr::Ident(ident) => vec![&*ident.sym],
+        Expr::Member(inner) => member_path(inner)?,
+        _ => return None,
+    };
+    match &member.prop {
+        MemberProp::Ident(ident) => path.push(&*ident.sym),
+        _ => return None,
+    }
+    Some(path)
+}
+
+#[test]
+fn test_parse_filter() {
+    let source = r#"
+function pad(value, length = 2) {
+    return String(value).padStart(length, '0');
+}
+
+export default {
+    // 格式化日期
+    format: function (date, pattern) {
+        return date;
+    },
+    upper: value => value.toUpperCase(),
+    join() {
+        return Array.prototype.join.call(arguments, ',');
+    },
+    pad,
+    version: '1.0'
+};
+"#;
+    let meta = parse_filter_source("utils.filter.js", source.to_string()).unwrap();
+    let names: Vec<&str> = meta.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["format", "upper", "join", "pad"]);
+    let format = &meta.functions[0];
+    assert_eq!(format.params, vec!["date", "pattern"]);
+    assert_eq!(format.required, 2);
+    assert!(!format.variadic);
+    assert_eq!(format.comment.as_deref(), Some("// 格式化日期"));
+    assert_eq!(format.loc, Location::from([8, 4], [8, 10]));
+    assert!(meta.functions[2].variadic);
+    let pad = &meta.functions[3];
+    assert_eq!(pad.params, vec!["value", "length = 2"]);
+    assert_eq!(pad.required, 1);
+
+    let source = "/* 拼接 */\nmodule.exports.concat = (...items) => items.join('');\nexports.trim = function (s) { return s.trim(); };";
+    let meta = parse_filter_source("tools.sjs", source.to_string()).unwrap();
+    assert_eq!(meta.functions[0].name, "concat");
+    assert!(meta.functions[0].variadic);
+    assert_eq!(meta.functions[0].comment.as_deref(), Some("/* 拼接 */"));
+    assert_eq!(meta.functions[1].name, "trim");
+
+    let source = "module.exports = {\n    add: function (a, b) { return a + b; }\n};";
+    let start = Position {
+        line: 3,
+        column: 20,
+    };
+    let meta = parse_inline_filter("page.swan", source, &start).unwrap();
+    assert_eq!(meta.functions[0].loc, Location::from([4, 4], [4, 7]));
+    let error = parse_inline_filter("page.swan", "export default {", &start).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::SyntaxError);
+    assert_eq!(error.loc.unwrap().start.line, 3);
+}
@@ -0,0 +1,372 @@
+use crate::parser::meta::{Location, ParseError, ParseErrorKind, Position};
+
+/// json 最大嵌套层级，解析是递归的，避免栈溢出
+const MAX_JSON_DEPTH: usize = 128;
+
+/// 带位置信息的 json 值，用于定位配置项
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonNode>),
+    /// 保留源码中的顺序和重复的 key
+    Object(Vec<JsonEntry>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonNode {
+    pub value: JsonValue,
+    pub loc: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonEntry {
+    pub key: String,
+    /// key 的位置，包含引号
+    pub key_loc: Location,
+    pub value: JsonNode,
+}
+
+impl JsonNode {
+    /// 按 key 查找对象中的值，重复的 key 取最后一个
+    pub fn get(&self, key: &str) -> Option<&JsonNode> {
+        match &self.value {
+            JsonValue::Object(entries) => entries
+                .iter()
+                .rev()
+                .find(|e| e.key == key)
+                .map(|e| &e.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match &self.value {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[JsonEntry] {
+        match &self.value {
+            JsonValue::Object(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    /// 值的类型名称，用于错误信息
+    pub fn type_name(&self) -> &'static str {
+        match &self.value {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "boolean",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    file_path: &'a str,
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, kind: ParseErrorKind, message: &str) -> ParseError {
+        let mut error = ParseError::new(
+            kind,
+            format!("failed to parse {}: {}", self.file_path, message),
+        );
+        let position = self.position();
+        error.loc = Some(Location {
+            start: position.clone(),
+            end: position,
+        });
+        error
+    }
+
+    fn unexpected(&self) -> ParseError {
+        let message = match self.peek() {
+            Some(c) => format!("unexpected character `{}`", c),
+            None => String::from("unexpected end of input"),
+        };
+        self.error(ParseErrorKind::SyntaxError, &message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r' | '\u{feff}')) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.peek() == Some(c) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), ParseError> {
+        for c in word.chars() {
+            self.expect(c)?;
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<JsonNode, ParseError> {
+        if depth > MAX_JSON_DEPTH {
+            return Err(self.error(ParseErrorKind::NestingTooDeep, "nesting too deep"));
+        }
+        self.skip_whitespace();
+        let start = self.position();
+        let value = match self.peek() {
+            Some('{') => self.parse_object(depth)?,
+            Some('[') => self.parse_array(depth)?,
+            Some('"') => JsonValue::String(self.parse_string()?),
+            Some('t') => {
+                self.expect_word("true")?;
+                JsonValue::Bool(true)
+            }
+            Some('f') => {
+                self.expect_word("false")?;
+                JsonValue::Bool(false)
+            }
+            Some('n') => {
+                self.expect_word("null")?;
+                JsonValue::Null
+            }
+            Some('-' | '0'..='9') => self.parse_number()?,
+            _ => return Err(self.unexpected()),
+        };
+        Ok(JsonNode {
+            value,
+            loc: Location {
+                start,
+                end: self.position(),
+            },
+        })
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<JsonValue, ParseError> {
+        self.expect('{')?;
+        let mut entries = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let start = self.position();
+            if self.peek() != Some('"') {
+                return Err(self.unexpected());
+            }
+            let key = self.parse_string()?;
+            let key_loc = Location {
+                start,
+                end: self.position(),
+            };
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.parse_value(depth + 1)?;
+            entries.push(JsonEntry {
+                key,
+                key_loc,
+                value,
+            });
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(entries)),
+                _ => return Err(self.error(ParseErrorKind::SyntaxError, "expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<JsonValue, ParseError> {
+        self.expect('[')?;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value(depth + 1)?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(items)),
+                _ => return Err(self.error(ParseErrorKind::SyntaxError, "expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(result),
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.parse_unicode_escape()?,
+                        _ => return Err(self.error(ParseErrorKind::SyntaxError, "invalid escape")),
+                    };
+                    result.push(c);
+                }
+                Some('\n') | None => {
+                    return Err(self.error(ParseErrorKind::SyntaxError, "unterminated string"))
+                }
+                Some(c) => result.push(c),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error(ParseErrorKind::SyntaxError, "invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let code = self.parse_hex4()?;
+        // 代理对
+        if (0xd800..0xdc00).contains(&code) && self.peek() == Some('\\') {
+            self.next();
+            self.expect('u')?;
+            let low = self.parse_hex4()?;
+            let code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+            return Ok(char::from_u32(code).unwrap_or('\u{fffd}'));
+        }
+        Ok(char::from_u32(code).unwrap_or('\u{fffd}'))
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, ParseError> {
+        let start = self.index;
+        while matches!(self.peek(), Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9')) {
+            self.next();
+        }
+        let text: String = self.chars[start..self.index].iter().collect();
+        text.parse().map(JsonValue::Number).map_err(|_| {
+            self.error(
+                ParseErrorKind::SyntaxError,
+                &format!("invalid number `{}`", text),
+            )
+        })
+    }
+}
+
+/// 解析 json 源码，保留每个值和 key 的位置，`file_path` 仅用于错误信息
+pub fn parse_json(file_path: &str, text: &str) -> Result<JsonNode, ParseError> {
+    let mut parser = JsonParser {
+        file_path,
+        chars: text.chars().collect(),
+        index: 0,
+        line: 1,
+        column: 0,
+    };
+    let node = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.unexpected());
+    }
+    Ok(node)
+}
+
+#[test]
+fn test_parse_json() {
+    let text = "{\n    \"a\": [1, -2.5e1, true, null],\n    \"中\": {\"b\": \"x\\u4e2d\\n\"}\n}";
+    let node = parse_json("a.json", text).unwrap();
+    let entries = node.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].key_loc, Location::from([2, 4], [2, 7]));
+    assert_eq!(
+        entries[0].value.value,
+        JsonValue::Array(vec![
+            JsonNode {
+                value: JsonValue::Number(1.0),
+                loc: Location::from([2, 10], [2, 11]),
+            },
+            JsonNode {
+                value: JsonValue::Number(-25.0),
+                loc: Location::from([2, 13], [2, 19]),
+            },
+            JsonNode {
+                value: JsonValue::Bool(true),
+                loc: Location::from([2, 21], [2, 25]),
+            },
+            JsonNode {
+                value: JsonValue::Null,
+                loc: Location::from([2, 27], [2, 31]),
+            },
+        ])
+    );
+    assert_eq!(entries[1].key_loc, Location::from([3, 4], [3, 7]));
+    assert_eq!(
+        node.get("中").unwrap().get("b").unwrap().as_str(),
+        Some("x中\n")
+    );
+
+    let error = parse_json("a.json", "{\"a\": 1,}").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::SyntaxError);
+    assert_eq!(error.loc, Some(Location::from([1, 8], [1, 8])));
+    assert!(parse_json("a.json", "{} {}").is_err());
+    assert!(parse_json("a.json", "\"abc").is_err());
+
+    let text = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+    let error = parse_json("a.json", &text).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+}
@@ -1,27 +1,32 @@
-#[derive(Debug, Serialize)]
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ComponentType {
     Component,
     Page,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Location {
     pub start: Position,
     pub end: Position,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataMeta {
     pub name: String,
     pub comment: Option<String>,
     pub loc: Location,
     pub children: Option<Vec<DataMeta>>,
+    /// 值为数组时，数组中对象元素的字段
+    #[serde(default)]
+    pub items: Option<Vec<DataMeta>>,
 }
 
 impl DataMeta {
@@ -37,11 +42,12 @@ impl DataMeta {
             comment: Option::None,
             loc: Location::default(),
             children: Option::None,
+            items: Option::None,
         }
     }
 }
 
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum PropertyValue {
     String(String),
@@ -49,15 +55,7 @@ pub enum PropertyValue {
     Boolean(bool),
 }
 
-#[allow(dead_code)]
 impl Location {
-    pub fn default() -> Self {
-        Location {
-            start: Position { line: 0, column: 0 },
-            end: Position { line: 0, column: 0 },
-        }
-    }
-
     pub fn from(start: [usize; 2], end: [usize; 2]) -> Self {
         Location {
             start: Position {
@@ -72,7 +70,7 @@ impl Location {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PropertyMeta {
     pub name: String,
     pub r#type: String,
@@ -99,7 +97,7 @@ impl PropertyMeta {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MethodMeta {
     pub name: String,
     pub comment: Option<String>,
@@ -122,7 +120,7 @@ impl MethodMeta {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventMeta {
     pub name: String,
     pub comment: Option<String>,
@@ -145,28 +143,40 @@ impl EventMeta {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentMeta {
     pub r#type: ComponentType,
     pub data: Vec<DataMeta>,
     pub properties: Vec<PropertyMeta>,
     pub methods: Vec<MethodMeta>,
+    /// `computed` 中定义的计算属性
+    #[serde(default)]
+    pub computed: Vec<MethodMeta>,
     pub events: Option<Vec<EventMeta>>,
+    /// `behaviors` 中的内置 behavior 和引用的变量名，例如 `swan://form-field`
+    #[serde(default)]
+    pub behaviors: Vec<String>,
+    /// `externalClasses` 中声明的外部样式类
+    #[serde(default, rename = "externalClasses")]
+    pub external_classes: Vec<String>,
 }
 
 impl ComponentMeta {
     pub fn new(r#type: ComponentType) -> Self {
         ComponentMeta {
-            r#type: r#type,
+            r#type,
             data: vec![],
             properties: vec![],
             methods: vec![],
+            computed: vec![],
             events: None,
+            behaviors: vec![],
+            external_classes: vec![],
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CssClassMeta {
     pub name: String,
     pub loc: Location,
@@ -187,7 +197,7 @@ impl CssClassMeta {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CssMeta {
     pub classes: Vec<CssClassMeta>,
     pub imports: Option<Vec<String>>,
@@ -201,3 +211,327 @@ impl CssMeta {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ParseErrorKind {
+    /// 文件不存在或无法读取
+    NotFound,
+    /// 语法错误
+    SyntaxError,
+    /// 未找到 Page 或 Component 调用
+    ComponentNotFound,
+    /// 嵌套层级过深，为避免栈溢出不再解析
+    NestingTooDeep,
+    /// 解析器内部错误，解析过程发生了 panic
+    InternalError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub message: String,
+    pub loc: Option<Location>,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, message: String) -> Self {
+        ParseError {
+            kind,
+            message,
+            loc: None,
+        }
+    }
+
+    pub fn not_found(file_path: &str) -> Self {
+        Self::new(
+            ParseErrorKind::NotFound,
+            format!("No such file {}", file_path),
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 文件中可以继续解析的问题，例如未知或无效的配置项
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub loc: Location,
+    /// 可能的修正，例如拼写错误时相近的名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, loc: Location) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            loc,
+            suggestion: None,
+        }
+    }
+
+    pub fn warning(message: String, loc: Location) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message,
+            loc,
+            suggestion: None,
+        }
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Option<String>) -> Self {
+        self.suggestion = suggestion;
+        self
+    }
+}
+
+/// `usingComponents` 中的一项，`loc` 为自定义标签名的位置，`value_loc` 为组件路径的位置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsingComponentMeta {
+    pub name: String,
+    pub path: String,
+    pub loc: Location,
+    #[serde(rename = "valueLoc")]
+    pub value_loc: Location,
+}
+
+/// `componentGenerics` 中的一项，`default` 为默认使用的组件路径
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComponentGenericMeta {
+    pub name: String,
+    pub default: Option<String>,
+    pub loc: Location,
+}
+
+/// 配置文件中的一个顶层配置项
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigKeyMeta {
+    pub name: String,
+    pub loc: Location,
+}
+
+/// 页面或组件的 json 配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConfigMeta {
+    pub component: bool,
+    #[serde(rename = "usingComponents")]
+    pub using_components: BTreeMap<String, UsingComponentMeta>,
+    #[serde(rename = "componentGenerics")]
+    pub component_generics: BTreeMap<String, ComponentGenericMeta>,
+    #[serde(rename = "navigationBarTitleText")]
+    pub navigation_bar_title_text: Option<String>,
+    /// 所有顶层配置项的位置，用于跳转到定义
+    pub keys: Vec<ConfigKeyMeta>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 模板中的插值，`{{ }}` 为单向绑定，`{= =}` 为双向绑定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InterpolationMeta {
+    /// 去掉定界符后的表达式源码
+    pub expression: String,
+    #[serde(rename = "twoWay")]
+    pub two_way: bool,
+    /// 包含定界符的位置
+    pub loc: Location,
+    /// 表达式源码的位置
+    #[serde(rename = "expressionLoc")]
+    pub expression_loc: Location,
+}
+
+/// 属性值中的文本或插值
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum ValuePart {
+    Text { value: String, loc: Location },
+    Interpolation(InterpolationMeta),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttributeMeta {
+    pub name: String,
+    /// 引号内的原始值，没有值的属性为 `None`，例如 `<input disabled />`
+    pub value: Option<String>,
+    /// 从属性名到值结束的位置
+    pub loc: Location,
+    #[serde(rename = "nameLoc")]
+    pub name_loc: Location,
+    /// 引号内的位置
+    #[serde(rename = "valueLoc")]
+    pub value_loc: Option<Location>,
+    /// 按插值拆分的属性值
+    pub parts: Vec<ValuePart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ElementMeta {
+    pub name: String,
+    pub attributes: Vec<AttributeMeta>,
+    pub children: Vec<TemplateNode>,
+    /// 从开始标签到结束标签的位置，未闭合的标签到父元素结束或文件末尾
+    pub loc: Location,
+    #[serde(rename = "nameLoc")]
+    pub name_loc: Location,
+    /// 结束标签中标签名的位置，自闭合或未闭合时为 `None`
+    #[serde(rename = "endNameLoc")]
+    pub end_name_loc: Option<Location>,
+    #[serde(rename = "selfClosing")]
+    pub self_closing: bool,
+}
+
+impl ElementMeta {
+    pub fn attribute(&self, name: &str) -> Option<&AttributeMeta> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum TemplateNode {
+    Element(ElementMeta),
+    /// 原始文本，不解码实体，只有空白的文本不会出现在结果中
+    Text {
+        value: String,
+        loc: Location,
+    },
+    Interpolation(InterpolationMeta),
+    Comment {
+        value: String,
+        loc: Location,
+    },
+}
+
+/// `.swan` 模板的解析结果，语法错误记录在 `diagnostics` 中，不影响其他节点
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TemplateMeta {
+    pub nodes: Vec<TemplateNode>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 表达式中引用的变量
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReferenceMeta {
+    /// 变量名，即访问路径的第一项
+    pub name: String,
+    /// 静态的成员访问路径，`item.user['name']` 为 `["item", "user", "name"]`，遇到 `a[b]` 时结束
+    pub path: Vec<String>,
+    /// 变量名的位置
+    pub loc: Location,
+    /// 整个访问路径的位置
+    #[serde(rename = "pathLoc")]
+    pub path_loc: Location,
+}
+
+/// 模板中的绑定表达式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExpressionMeta {
+    pub source: String,
+    pub loc: Location,
+    /// 按出现顺序排列的变量引用，不包括对象字面量的 key 和成员访问的属性名
+    pub references: Vec<ReferenceMeta>,
+}
+
+/// `s-for` 中声明的 item 或 index 变量
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AliasMeta {
+    pub name: String,
+    pub loc: Location,
+}
+
+/// `s-for="item, index in list trackBy item.id"`，省略时 `item` 和 `index` 为 `None`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ForMeta {
+    pub item: Option<AliasMeta>,
+    pub index: Option<AliasMeta>,
+    pub list: ExpressionMeta,
+    #[serde(rename = "trackBy")]
+    pub track_by: Option<ExpressionMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum BindingKind {
+    /// 文本中的插值
+    Text,
+    /// 普通属性值中的插值
+    Attribute,
+    If,
+    Elif,
+    Show,
+    /// `s-for` 遍历的列表
+    For,
+    /// `s-for` 的 `trackBy` 表达式
+    TrackBy,
+}
+
+/// 模板中的一个绑定表达式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BindingMeta {
+    pub kind: BindingKind,
+    /// 所在的属性名，文本中的插值为 `None`
+    pub attribute: Option<String>,
+    pub expression: ExpressionMeta,
+}
+
+/// 模板中使用的类名
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClassUsageMeta {
+    pub name: String,
+    pub loc: Location,
+    /// 所在的属性，例如 `class`、`hover-class`
+    pub attribute: String,
+}
+
+/// 模板中所有的绑定表达式，无法解析的表达式记录在 `diagnostics` 中
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TemplateBindings {
+    pub bindings: Vec<BindingMeta>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// filter 或 sjs 模块导出的函数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FilterFunctionMeta {
+    pub name: String,
+    /// 参数的源码，例如 `value`、`format = 'YYYY'`、`...args`
+    pub params: Vec<String>,
+    /// 必须传入的参数个数，即第一个带默认值或剩余参数之前的参数个数
+    pub required: usize,
+    /// 有剩余参数或使用了 `arguments`，可以传入任意个参数
+    pub variadic: bool,
+    pub comment: Option<String>,
+    /// 导出名称的位置
+    pub loc: Location,
+}
+
+/// filter 或 sjs 模块，支持 `export default {}`、`module.exports = {}`、
+/// `module.exports.name = function () {}` 和 `export function name() {}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FilterModuleMeta {
+    pub functions: Vec<FilterFunctionMeta>,
+}
+
+/// 表达式中对模块函数的调用，例如 `utils.format(a, b)`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CallMeta {
+    pub module: String,
+    pub function: String,
+    /// 实参个数，有展开时为 `None`
+    pub arguments: Option<usize>,
+    /// 函数名的位置
+    pub loc: Location,
+}
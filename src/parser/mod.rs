@@ -1,7 +1,59 @@
+/// 编译一次正则表达式，之后在所有解析线程中共享
+macro_rules! regex {
+    ($re:literal $(,)?) => {{
+        static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+        RE.get_or_init(|| regex::Regex::new($re).unwrap())
+    }};
+}
+
+mod config;
 mod css;
+mod expression;
+mod filter;
+pub mod json;
 mod meta;
 mod script;
+mod template;
+
+use std::panic::{self, AssertUnwindSafe};
 
-pub use css::parse_css_class;
+pub use config::{parse_config, parse_config_source};
+#[cfg(any(feature = "wasm", test))]
+pub use css::parse_css_source_with;
+pub use css::{normalize_path, parse_css_class, parse_css_source};
+pub(crate) use expression::attribute_bindings;
+pub use expression::{
+    extract_bindings, extract_classes, parse_calls, parse_expression, parse_for,
+    parse_template_data,
+};
+pub use filter::{parse_filter, parse_filter_source, parse_inline_filter};
 pub use meta::*;
-pub use script::parse_component;
+pub use script::{parse_component, parse_component_source, MAX_NESTING_DEPTH};
+pub use template::{parse_template, parse_template_source};
+
+/// 隔离单个文件的解析，解析过程中的 panic 转换为 `InternalError`，不影响其他文件和宿主进程
+fn catch_panic<T>(
+    file_path: &str,
+    parse: impl FnOnce() -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    panic::catch_unwind(AssertUnwindSafe(parse)).unwrap_or_else(|payload| {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown panic"));
+        Err(ParseError::new(
+            ParseErrorKind::InternalError,
+            format!("failed to parse {}: {}", file_path, reason),
+        ))
+    })
+}
+
+#[test]
+fn test_catch_panic() {
+    let result: Result<(), ParseError> = catch_panic("a.js", || panic!("boom"));
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InternalError);
+    assert_eq!(error.message, "failed to parse a.js: boom");
+    assert_eq!(catch_panic("a.js", || Ok(1)), Ok(1));
+}
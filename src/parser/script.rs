@@ -1,41 +1,205 @@
 use crate::parser::meta::{
-    ComponentMeta, ComponentType, DataMeta, Location, MethodMeta, PropertyMeta, PropertyValue,
+    ComponentMeta, ComponentType, DataMeta, Location, MethodMeta, ParseError, ParseErrorKind,
+    PropertyMeta, PropertyValue,
 };
-use regex::Regex;
-use std::path::Path;
+use std::fs;
+use std::path::PathBuf;
 use std::{collections::HashSet, ops::Deref};
 use swc_common::{
     comments::{CommentKind, Comments, SingleThreadedComments},
     sync::Lrc,
-    BytePos,
+    BytePos, Spanned,
 };
 use swc_common::{
     errors::{ColorConfig, Handler},
-    SourceFile, SourceMap,
+    FileName, SourceFile, SourceMap,
 };
 use swc_ecma_ast::{
-    Callee, Expr, Ident, KeyValueProp, Lit, MethodProp, Module, ModuleItem, ObjectLit, Prop,
-    PropName, PropOrSpread, Stmt,
+    ArrayLit, Callee, Expr, Ident, KeyValueProp, Lit, MethodProp, Module, ModuleItem, ObjectLit,
+    Prop, PropName, PropOrSpread, Stmt,
 };
 use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
 
-use super::EventMeta;
+use super::{catch_panic, EventMeta};
 
-pub fn parse_component(file_path: &str) -> Result<ComponentMeta, String> {
-    let cm: Lrc<SourceMap> = Default::default();
-    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+/// 读取并解析 js 文件
+pub fn parse_component(file_path: &str) -> Result<ComponentMeta, ParseError> {
+    let read_result = fs::read_to_string(file_path);
+    if read_result.is_err() {
+        return Err(ParseError::not_found(file_path));
+    }
+    parse_component_source(file_path, read_result.unwrap())
+}
+
+/// js 源码允许的最大嵌套层级，swc 递归解析表达式，过深的嵌套会导致栈溢出
+///
+/// release 构建每层约占用 10KB 栈，debug 构建约 50KB，解析线程的栈大小见 `pool`
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// `Page(wrap(wrap(...)))` 最多向内查找的层数
+const MAX_GUESS_DEPTH: usize = 16;
+
+/// data 子项最多解析的层数
+const MAX_DATA_DEPTH: usize = 32;
+
+/// 解析已读取的 js 源码，`file_path` 仅用于错误信息
+pub fn parse_component_source(
+    file_path: &str,
+    source: String,
+) -> Result<ComponentMeta, ParseError> {
+    catch_panic(file_path, || parse_source(file_path, source))
+}
+
+/// `/` 前面的 token 为这些关键字时是正则表达式的开始，而不是除号
+const REGEX_KEYWORDS: [&str; 14] = [
+    "return",
+    "typeof",
+    "instanceof",
+    "case",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "yield",
+    "await",
+    "else",
+    "do",
+];
+
+/// 根据 `/` 前一个非空白字符判断 `/` 是否为正则表达式的开始，`last` 为该字符的位置
+fn starts_regex(bytes: &[u8], last: Option<usize>) -> bool {
+    let last = match last {
+        Some(last) => last,
+        None => return true,
+    };
+    let c = bytes[last];
+    if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' {
+        let start = bytes[..=last]
+            .iter()
+            .rposition(|b| !(b.is_ascii_alphanumeric() || *b == b'_' || *b == b'$'))
+            .map_or(0, |p| p + 1);
+        let word = &bytes[start..=last];
+        return REGEX_KEYWORDS.iter().any(|k| k.as_bytes() == word);
+    }
+    !matches!(c, b')' | b']' | b'}' | b'\'' | b'"' | b'`')
+}
+
+/// 跳过从 `i` 开始的正则表达式，返回结尾 `/` 的位置，字符类 `[...]` 中的 `/` 不结束正则
+fn skip_regex(bytes: &[u8], mut i: usize) -> usize {
+    let mut class = false;
+    i += 1;
+    while i < bytes.len() && bytes[i] != b'\n' {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => class = true,
+            b']' => class = false,
+            b'/' if !class => break,
+            _ => (),
+        }
+        i += 1;
+    }
+    i
+}
+
+/// 估算括号的最大嵌套层级，跳过字符串、模板字符串、正则表达式和注释，超过 `limit` 立即返回
+pub(crate) fn exceeds_nesting_depth(source: &str, limit: usize) -> bool {
+    let bytes = source.as_bytes();
+    // 记录每个未闭合的 `{`、`(`、`[` 和模板字符串，`b'`'` 表示处于模板字符串中
+    let mut stack: Vec<u8> = vec![];
+    // 上一个不在注释中的非空白字符，用于区分正则表达式和除号
+    let mut last: Option<usize> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if stack.last() == Some(&b'`') {
+            match c {
+                b'\\' => i += 1,
+                b'`' => {
+                    stack.pop();
+                }
+                b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                    stack.push(b'{');
+                    i += 1;
+                }
+                _ => (),
+            }
+        } else {
+            match c {
+                b'\'' | b'"' => {
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != c && bytes[i] != b'\n' {
+                        if bytes[i] == b'\\' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                }
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                    continue;
+                }
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    i += 2;
+                    while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                        i += 1;
+                    }
+                    i += 2;
+                    continue;
+                }
+                b'/' if starts_regex(bytes, last) => {
+                    i = skip_regex(bytes, i);
+                }
+                b'{' | b'(' | b'[' | b'`' => {
+                    stack.push(c);
+                    if stack.len() > limit {
+                        return true;
+                    }
+                }
+                b'}' | b')' | b']' => {
+                    stack.pop();
+                }
+                _ => (),
+            }
+        }
+        if i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+            last = Some(i);
+        }
+        i += 1;
+    }
+    false
+}
 
-    // Real usage
-    let sf_result = cm.load_file(Path::new(file_path));
-    if let Err(_) = sf_result {
-        return Err(format!("No such file {}", file_path));
+/// `.ts` 文件按 TypeScript 解析，其他文件按 ecmascript 解析
+fn syntax_for(file_path: &str) -> Syntax {
+    if file_path.ends_with(".ts") {
+        Syntax::Typescript(Default::default())
+    } else {
+        Syntax::Es(Default::default())
     }
+}
+
+fn parse_source(file_path: &str, source: String) -> Result<ComponentMeta, ParseError> {
+    if exceeds_nesting_depth(&source, MAX_NESTING_DEPTH) {
+        return Err(ParseError::new(
+            ParseErrorKind::NestingTooDeep,
+            format!(
+                "failed to parse {}, nesting exceeds {} levels",
+                file_path, MAX_NESTING_DEPTH
+            ),
+        ));
+    }
+
+    let cm: Lrc<SourceMap> = Default::default();
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+    let sf = cm.new_source_file(FileName::Real(PathBuf::from(file_path)), source);
 
-    let sf = sf_result.unwrap();
     let comments_map: SingleThreadedComments = Default::default();
     let lexer = Lexer::new(
-        // We want to parse ecmascript
-        Syntax::Es(Default::default()),
+        syntax_for(file_path),
         // EsVersion defaults to es5
         Default::default(),
         StringInput::from(&*sf),
@@ -48,36 +212,46 @@ pub fn parse_component(file_path: &str) -> Result<ComponentMeta, String> {
         e.into_diagnostic(&handler).emit();
     }
 
-    let module_result = parser.parse_module();
-    if let Err(_) = module_result {
-        return Err(format!("failed to parse {}", file_path));
-    }
-
-    let module = module_result.unwrap();
+    let module = match parser.parse_module() {
+        Ok(module) => module,
+        Err(e) => {
+            let mut error = ParseError::new(
+                ParseErrorKind::SyntaxError,
+                format!("failed to parse {}", file_path),
+            );
+            error.loc = Some(convert_bytepos_pos(e.span().lo(), e.span().hi(), &sf));
+            return Err(error);
+        }
+    };
 
     if let Ok(result) = get_component_call(&module) {
         if let Some(expr) = result.expr {
             let mut component_meta = ComponentMeta::new(result.r#type);
 
-            if let Ok(result) = find_property_with_object_value("data", &expr) {
-                if let Ok(res) = get_data_meta(&result, &comments_map, &sf) {
+            if let Ok(result) = find_property_with_object_value("data", expr) {
+                if let Ok(res) = get_data_meta(result, &comments_map, &sf) {
                     component_meta.data = res;
                 }
             }
+            if let Ok(result) = find_property_with_object_value("computed", expr) {
+                if let Ok(res) = get_methods_meta(result, &comments_map, &sf) {
+                    component_meta.computed = res;
+                }
+            }
             match component_meta.r#type {
                 ComponentType::Page => {
-                    if let Ok(res) = get_methods_meta(&expr, &comments_map, &sf) {
+                    if let Ok(res) = get_methods_meta(expr, &comments_map, &sf) {
                         component_meta.methods = res;
                     }
                 }
                 ComponentType::Component => {
-                    if let Ok(result) = find_property_with_object_value("properties", &expr) {
-                        if let Ok(res) = get_properties_meta(&result, &comments_map, &sf) {
+                    if let Ok(result) = find_property_with_object_value("properties", expr) {
+                        if let Ok(res) = get_properties_meta(result, &comments_map, &sf) {
                             component_meta.properties = res;
                         }
                     }
-                    if let Ok(result) = find_property_with_object_value("methods", &expr) {
-                        if let Ok(res) = get_methods_meta(&result, &comments_map, &sf) {
+                    if let Ok(result) = find_property_with_object_value("methods", expr) {
+                        if let Ok(res) = get_methods_meta(result, &comments_map, &sf) {
                             component_meta.methods = res;
                         }
                     }
@@ -85,31 +259,34 @@ pub fn parse_component(file_path: &str) -> Result<ComponentMeta, String> {
                     if let Ok(res) = parse_trigger_event(&comments_map, &sf) {
                         component_meta.events = Some(res);
                     }
+                    component_meta.behaviors = get_string_list("behaviors", expr);
+                    component_meta.external_classes = get_string_list("externalClasses", expr);
                 }
             }
             return Ok(component_meta);
         }
     }
 
-    Err("component not found!".to_string())
+    Err(ParseError::new(
+        ParseErrorKind::ComponentNotFound,
+        "component not found!".to_string(),
+    ))
 }
 
 /// 转换 字节位置到字符位置
-fn convert_bytepos_pos(start: BytePos, end: BytePos, sf: &SourceFile) -> Location {
-    let line_index = sf.lookup_line(start).unwrap();
+pub(crate) fn convert_bytepos_pos(start: BytePos, end: BytePos, sf: &SourceFile) -> Location {
+    let line_index = sf.lookup_line(start).unwrap_or(0);
     let line_pos = sf.line_begin_pos(start);
     let mut loc = Location::default();
     loc.start.line = line_index + 1;
-    loc.start.column = (&sf.src[line_pos.0 as usize..start.0 as usize])
+    loc.start.column = sf.src[line_pos.0 as usize..start.0 as usize]
         .chars()
         .count();
 
-    let line_index = sf.lookup_line(end).unwrap();
+    let line_index = sf.lookup_line(end).unwrap_or(0);
     let line_pos = sf.line_begin_pos(end);
     loc.end.line = line_index + 1;
-    loc.end.column = (&sf.src[line_pos.0 as usize..end.0 as usize])
-        .chars()
-        .count();
+    loc.end.column = sf.src[line_pos.0 as usize..end.0 as usize].chars().count();
     loc
 }
 
@@ -117,15 +294,13 @@ fn find_property_with_object_value<'a>(
     name: &str,
     properties: &'a ObjectLit,
 ) -> Result<&'a ObjectLit, ()> {
-    if let Ok(prop_value) = find_property_by_name(name, properties) {
-        if let Expr::Object(object) = &**prop_value {
-            return Ok(object);
-        }
+    if let Ok(Expr::Object(object)) = find_property_by_name(name, properties) {
+        return Ok(object);
     }
     Err(())
 }
 
-fn find_property_by_name<'a>(name: &str, properties: &'a ObjectLit) -> Result<&'a Box<Expr>, ()> {
+fn find_property_by_name<'a>(name: &str, properties: &'a ObjectLit) -> Result<&'a Expr, ()> {
     for prop_or_spread in &properties.props {
         if let PropOrSpread::Prop(prop) = prop_or_spread {
             if let Prop::KeyValue(key_value) = &**prop {
@@ -150,26 +325,26 @@ fn find_prop_name(
         PropName::Ident(prop_name) => Ok(PropNameMeta {
             name: prop_name.sym.to_string(),
             comment: get_comment(prop_name.span.lo(), comments_map),
-            loc: convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), &sf),
+            loc: convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), sf),
         }),
         PropName::Str(prop_name) => Ok(PropNameMeta {
             name: prop_name.value.to_string(),
             comment: get_comment(prop_name.span.lo(), comments_map),
-            loc: convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), &sf),
+            loc: convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), sf),
         }),
         PropName::Num(prop_name) => Ok(PropNameMeta {
             name: prop_name.value.to_string(),
             comment: get_comment(prop_name.span.lo(), comments_map),
-            loc: convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), &sf),
+            loc: convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), sf),
         }),
         _ => Err(()),
     }
 }
 
-fn get_comment(pos: BytePos, comments_map: &dyn Comments) -> Option<String> {
+pub(crate) fn get_comment(pos: BytePos, comments_map: &dyn Comments) -> Option<String> {
     let res = comments_map.get_leading(pos);
     if let Some(comments) = res {
-        if let Some(comment) = comments.get(0) {
+        if let Some(comment) = comments.first() {
             return match comment.kind {
                 CommentKind::Line => Some(format!("//{}", comment.text)),
                 CommentKind::Block => Some(format!("/*{}*/", comment.text)),
@@ -193,38 +368,42 @@ fn get_properties_meta(
     let mut result = vec![];
     for prop_or_spread in &properties.props {
         if let PropOrSpread::Prop(prop) = prop_or_spread {
-            match &**prop {
-                Prop::KeyValue(KeyValueProp { key, value }) => {
-                    if let Ok(name) = find_prop_name(key, comments_map, sf) {
-                        if let Expr::Object(expr) = &**value {
-                            let mut property = PropertyMeta::new(name.name);
-                            property.comment = name.comment;
-                            property.loc = name.loc;
-                            if let Ok(prop_value) = find_property_by_name("type", &expr) {
-                                if let Expr::Ident(Ident { sym, .. }) = &**prop_value {
-                                    property.r#type = sym.to_string();
+            if let Prop::KeyValue(KeyValueProp { key, value }) = &**prop {
+                if let Ok(name) = find_prop_name(key, comments_map, sf) {
+                    if let Expr::Object(expr) = &**value {
+                        let mut property = PropertyMeta::new(name.name);
+                        property.comment = name.comment;
+                        property.loc = name.loc;
+                        if let Ok(Expr::Ident(Ident { sym, .. })) =
+                            find_property_by_name("type", expr)
+                        {
+                            property.r#type = sym.to_string();
+                        }
+                        if let Ok(prop_value) = find_property_by_name("value", expr) {
+                            match prop_value {
+                                Expr::Lit(Lit::Str(value)) => {
+                                    property.value =
+                                        Some(PropertyValue::String(value.value.to_string()))
                                 }
-                            }
-                            if let Ok(prop_value) = find_property_by_name("value", &expr) {
-                                match &**prop_value {
-                                    Expr::Lit(Lit::Str(value)) => {
-                                        property.value =
-                                            Some(PropertyValue::String(value.value.to_string()))
-                                    }
-                                    Expr::Lit(Lit::Num(value)) => {
-                                        property.value = Some(PropertyValue::Number(value.value))
-                                    }
-                                    Expr::Lit(Lit::Bool(value)) => {
-                                        property.value = Some(PropertyValue::Boolean(value.value))
-                                    }
-                                    _ => (),
+                                Expr::Lit(Lit::Num(value)) => {
+                                    property.value = Some(PropertyValue::Number(value.value))
                                 }
+                                Expr::Lit(Lit::Bool(value)) => {
+                                    property.value = Some(PropertyValue::Boolean(value.value))
+                                }
+                                _ => (),
                             }
-                            result.push(property);
                         }
+                        result.push(property);
+                    } else if let Expr::Ident(Ident { sym, .. }) = &**value {
+                        // `title: String` 只声明类型
+                        let mut property = PropertyMeta::new(name.name);
+                        property.comment = name.comment;
+                        property.loc = name.loc;
+                        property.r#type = sym.to_string();
+                        result.push(property);
                     }
                 }
-                _ => (),
             }
         }
     }
@@ -236,30 +415,60 @@ fn parse_data_children(
     properties: &ObjectLit,
     comments_map: &dyn Comments,
     sf: &SourceFile,
+    depth: usize,
 ) -> Option<Vec<DataMeta>> {
+    if depth >= MAX_DATA_DEPTH {
+        return None;
+    }
     let mut output = vec![];
     for prop_or_spread in &properties.props {
         if let PropOrSpread::Prop(prop) = prop_or_spread {
-            match &**prop {
-                Prop::KeyValue(KeyValueProp { key, value, .. }) => {
-                    if let Ok(name) = find_prop_name(key, comments_map, sf) {
-                        let mut data = DataMeta::new(name.name);
-                        data.comment = name.comment;
-                        data.loc = name.loc;
-
-                        if let Expr::Object(object) = &**value {
-                            data.children = parse_data_children(&object, comments_map, &sf);
+            if let Prop::KeyValue(KeyValueProp { key, value, .. }) = &**prop {
+                if let Ok(name) = find_prop_name(key, comments_map, sf) {
+                    let mut data = DataMeta::new(name.name);
+                    data.comment = name.comment;
+                    data.loc = name.loc;
+
+                    match &**value {
+                        Expr::Object(object) => {
+                            data.children =
+                                parse_data_children(object, comments_map, sf, depth + 1);
                         }
-                        output.push(data);
+                        Expr::Array(array) => {
+                            data.items = parse_array_items(array, comments_map, sf, depth + 1);
+                        }
+                        _ => (),
                     }
+                    output.push(data);
                 }
-                _ => (),
             }
         }
     }
     Some(output)
 }
 
+/// 数组中对象元素的字段，用于 `s-for` 中 item 的提示，多个元素的字段合并在一起
+fn parse_array_items(
+    array: &ArrayLit,
+    comments_map: &dyn Comments,
+    sf: &SourceFile,
+    depth: usize,
+) -> Option<Vec<DataMeta>> {
+    let mut items: Option<Vec<DataMeta>> = None;
+    for elem in array.elems.iter().flatten() {
+        if let Expr::Object(object) = &*elem.expr {
+            let fields = parse_data_children(object, comments_map, sf, depth)?;
+            let items = items.get_or_insert_with(Vec::new);
+            for field in fields {
+                if !items.iter().any(|item| item.name == field.name) {
+                    items.push(field);
+                }
+            }
+        }
+    }
+    items
+}
+
 fn get_data_meta(
     properties: &ObjectLit,
     comments_map: &dyn Comments,
@@ -268,26 +477,47 @@ fn get_data_meta(
     let mut result = vec![];
     for prop_or_spread in &properties.props {
         if let PropOrSpread::Prop(prop) = prop_or_spread {
-            match &**prop {
-                Prop::KeyValue(KeyValueProp { key, value, .. }) => {
-                    if let Ok(name) = find_prop_name(key, comments_map, sf) {
-                        let mut data = DataMeta::new(name.name);
-                        data.comment = name.comment;
-                        data.loc = name.loc;
-
-                        if let Expr::Object(object) = &**value {
-                            data.children = parse_data_children(&object, comments_map, &sf);
+            if let Prop::KeyValue(KeyValueProp { key, value, .. }) = &**prop {
+                if let Ok(name) = find_prop_name(key, comments_map, sf) {
+                    let mut data = DataMeta::new(name.name);
+                    data.comment = name.comment;
+                    data.loc = name.loc;
+
+                    match &**value {
+                        Expr::Object(object) => {
+                            data.children = parse_data_children(object, comments_map, sf, 1);
+                        }
+                        Expr::Array(array) => {
+                            data.items = parse_array_items(array, comments_map, sf, 1);
                         }
-                        result.push(data);
+                        _ => (),
                     }
+                    result.push(data);
                 }
-                _ => (),
             }
         }
     }
     Ok(result)
 }
 
+/// 数组中的字符串和变量名，例如 `behaviors: [expandable, 'swan://form-field']`
+fn get_string_list(name: &str, properties: &ObjectLit) -> Vec<String> {
+    let array = match find_property_by_name(name, properties) {
+        Ok(Expr::Array(array)) => array,
+        _ => return vec![],
+    };
+    array
+        .elems
+        .iter()
+        .flatten()
+        .filter_map(|elem| match &*elem.expr {
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
 fn get_methods_meta(
     properties: &ObjectLit,
     comments_map: &dyn Comments,
@@ -310,14 +540,14 @@ fn get_methods_meta(
                         }
                     }
                 }
-                Prop::Method(MethodProp { key, .. }) => {
-                    if let PropName::Ident(prop_name) = key {
-                        let mut method = MethodMeta::new(prop_name.sym.to_string());
-                        method.comment = get_comment(prop_name.span.lo(), comments_map);
-                        method.loc =
-                            convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), &sf);
-                        result.push(method);
-                    }
+                Prop::Method(MethodProp {
+                    key: PropName::Ident(prop_name),
+                    ..
+                }) => {
+                    let mut method = MethodMeta::new(prop_name.sym.to_string());
+                    method.comment = get_comment(prop_name.span.lo(), comments_map);
+                    method.loc = convert_bytepos_pos(prop_name.span.lo(), prop_name.span.hi(), sf);
+                    result.push(method);
                 }
                 _ => (),
             }
@@ -331,11 +561,11 @@ struct GetComponentCallResult<'a> {
     expr: Option<&'a ObjectLit>,
 }
 
-fn get_component_call(module: &Module) -> Result<GetComponentCallResult, ()> {
+fn get_component_call(module: &Module) -> Result<GetComponentCallResult<'_>, ()> {
     for module_item in &module.body {
         if let ModuleItem::Stmt(Stmt::Expr(expr_stmt)) = module_item {
             if let Expr::Call(call_expr) = &*expr_stmt.expr {
-                if  call_expr.args.len() == 0 {
+                if call_expr.args.is_empty() {
                     continue;
                 }
 
@@ -351,11 +581,10 @@ fn get_component_call(module: &Module) -> Result<GetComponentCallResult, ()> {
                                 result.expr = Option::Some(object);
                             } else {
                                 result.expr =
-                                    guess_component_params(ComponentType::Component, &*args.expr);
+                                    guess_component_params(ComponentType::Component, &args.expr);
                             }
                             return Ok(result);
-                        }
-                        else if sym.eq("Page") {
+                        } else if sym.eq("Page") {
                             let mut result = GetComponentCallResult {
                                 r#type: ComponentType::Page,
                                 expr: Option::None,
@@ -365,7 +594,7 @@ fn get_component_call(module: &Module) -> Result<GetComponentCallResult, ()> {
                                 result.expr = Option::Some(object);
                             } else {
                                 result.expr =
-                                    guess_component_params(ComponentType::Page, &*args.expr);
+                                    guess_component_params(ComponentType::Page, &args.expr);
                             }
                             return Ok(result);
                         }
@@ -373,7 +602,10 @@ fn get_component_call(module: &Module) -> Result<GetComponentCallResult, ()> {
                         else if sym.ends_with("Component") {
                             let args = &call_expr.args[0];
                             if let Expr::Object(_) = &*args.expr {
-                                if let Some(expr) = guess_component_params(ComponentType::Component, &*expr_stmt.expr) {
+                                if let Some(expr) = guess_component_params(
+                                    ComponentType::Component,
+                                    &expr_stmt.expr,
+                                ) {
                                     let result = GetComponentCallResult {
                                         r#type: ComponentType::Component,
                                         expr: Some(expr),
@@ -386,7 +618,9 @@ fn get_component_call(module: &Module) -> Result<GetComponentCallResult, ()> {
                         else if sym.ends_with("Page") {
                             let args = &call_expr.args[0];
                             if let Expr::Object(_) = &*args.expr {
-                                if let Some(expr) = guess_component_params(ComponentType::Page, &*expr_stmt.expr) {
+                                if let Some(expr) =
+                                    guess_component_params(ComponentType::Page, &expr_stmt.expr)
+                                {
                                     let result = GetComponentCallResult {
                                         r#type: ComponentType::Page,
                                         expr: Some(expr),
@@ -403,7 +637,7 @@ fn get_component_call(module: &Module) -> Result<GetComponentCallResult, ()> {
     Err(())
 }
 
-fn find_and_count_properties<'a>(names: &HashSet<&str>, properties: &'a ObjectLit) -> usize {
+fn find_and_count_properties(names: &HashSet<&str>, properties: &ObjectLit) -> usize {
     let mut count = 0;
     for prop_or_spread in &properties.props {
         if let PropOrSpread::Prop(prop) = prop_or_spread {
@@ -420,7 +654,7 @@ fn find_and_count_properties<'a>(names: &HashSet<&str>, properties: &'a ObjectLi
             }
         }
     }
-    return count;
+    count
 }
 
 /// 解析非标准组件注册方式，例如：
@@ -431,28 +665,44 @@ fn find_and_count_properties<'a>(names: &HashSet<&str>, properties: &'a ObjectLi
 ///
 /// Page(wrapper(wrapper({ data: {} })))
 fn guess_component_params(r#type: ComponentType, expr: &Expr) -> Option<&ObjectLit> {
+    guess_component_params_with_depth(r#type, expr, 0)
+}
+
+fn guess_component_params_with_depth(
+    r#type: ComponentType,
+    expr: &Expr,
+    depth: usize,
+) -> Option<&ObjectLit> {
+    if depth >= MAX_GUESS_DEPTH {
+        return None;
+    }
     if let Expr::Call(call_expr) = expr {
-        let args = &call_expr.args[0];
+        let args = call_expr.args.first()?;
         if let Expr::Object(object) = &*args.expr {
             let names = match r#type {
                 // Component 检测到 properties, data, methods 则认为是配置项
-                ComponentType::Component => HashSet::from(["properties", "data", "methods", "attached", "ready"]),
+                ComponentType::Component => {
+                    HashSet::from(["properties", "data", "methods", "attached", "ready"])
+                }
                 // Page 检测到 data, onLoad, onShow 则认为是配置项
-                ComponentType::Page => HashSet::from(["data", "onInit", "onLoad", "onReady", "onShow"]),
+                ComponentType::Page => {
+                    HashSet::from(["data", "onInit", "onLoad", "onReady", "onShow"])
+                }
             };
 
             if find_and_count_properties(&names, object) >= 2 {
                 return Some(object);
             }
         } else {
-            return guess_component_params(r#type, &*args.expr);
+            return guess_component_params_with_depth(r#type, &args.expr, depth + 1);
         }
     }
     None
 }
 
 fn parse_trigger_event(comments_map: &dyn Comments, sf: &SourceFile) -> Result<Vec<EventMeta>, ()> {
-    let regex = Regex::new(r#"this\.triggerEvent\s*\(\s*["'](?P<name>\w+)["']"#).unwrap();
+    // `this.triggerEvent` 以及 `const self = this` 之后的 `self.triggerEvent`
+    let regex = regex!(r#"[\w$]+\.triggerEvent\s*\(\s*["'](?P<name>\w+)["']"#);
 
     let mut last_line = 1;
     let mut last_offset = 0;
@@ -460,7 +710,7 @@ fn parse_trigger_event(comments_map: &dyn Comments, sf: &SourceFile) -> Result<V
     let mut events = vec![];
     let text = &sf.src;
 
-    for caps in regex.captures_iter(&text) {
+    for caps in regex.captures_iter(text) {
         let match0 = caps.get(0).unwrap();
         let start = match0.start();
         let end = match0.end();
@@ -495,13 +745,13 @@ fn parse_trigger_event(comments_map: &dyn Comments, sf: &SourceFile) -> Result<V
 #[test]
 fn test_parse_component() {
     let result = parse_component("test/fixtures/component.js").unwrap();
-    assert_eq!(matches!(result.r#type, ComponentType::Component), true);
+    assert!(matches!(result.r#type, ComponentType::Component));
     assert_eq!(result.data.len(), 4);
     assert_eq!(result.methods.len(), 6);
     assert_eq!(result.properties.len(), 5);
     assert_eq!(result.events.as_ref().unwrap().len(), 2);
 
-    let data = result.data.get(0).unwrap();
+    let data = result.data.first().unwrap();
     assert_eq!(data.name, "data1");
     assert_eq!(data.comment.as_ref().unwrap(), "// data1 属性");
     assert_eq!(data.loc, Location::from([8, 8], [8, 13]));
@@ -511,17 +761,17 @@ fn test_parse_component() {
         .children
         .as_ref()
         .unwrap()
-        .get(0)
+        .first()
         .unwrap()
         .children
         .as_ref()
         .unwrap()
-        .get(0)
+        .first()
         .unwrap();
     assert_eq!(child_data.name, "data111");
 
     // property
-    let property = result.properties.get(0).unwrap();
+    let property = result.properties.first().unwrap();
     assert_eq!(property.name, "theme");
     assert_eq!(
         property.comment.as_ref().unwrap(),
@@ -554,11 +804,13 @@ fn test_parse_component() {
     );
 
     // method
-    let method = result.methods.get(0).unwrap();
+    let method = result.methods.first().unwrap();
     assert_eq!(method.name, "method1");
     assert_eq!(method.comment.as_ref().unwrap(), "// method1 方法");
 
-    let not_found = parse_component("test/fixtures/component-notfound.js").err();
+    let not_found = parse_component("test/fixtures/component-notfound.js")
+        .err()
+        .map(|e| e.message);
     assert_eq!(
         Some(String::from(
             "No such file test/fixtures/component-notfound.js"
@@ -566,16 +818,18 @@ fn test_parse_component() {
         not_found
     );
     // parse error
-    let parse_error = parse_component("test/fixtures/component.error-js").err();
+    let parse_error = parse_component("test/fixtures/component.error-js")
+        .err()
+        .unwrap();
+    assert_eq!(parse_error.kind, ParseErrorKind::SyntaxError);
     assert_eq!(
-        Some(String::from(
-            "failed to parse test/fixtures/component.error-js"
-        )),
-        parse_error
+        parse_error.message,
+        "failed to parse test/fixtures/component.error-js"
     );
+    assert_eq!(parse_error.loc, Some(Location::from([1, 6], [1, 7])));
 
     // events
-    let event = result.events.as_ref().unwrap().get(0).unwrap();
+    let event = result.events.as_ref().unwrap().first().unwrap();
     println!("{:?}", event);
     assert_eq!(event.name, "event1");
     assert_eq!(event.comment.as_ref().unwrap(), "// event1 事件");
@@ -583,28 +837,49 @@ fn test_parse_component() {
     let event = result.events.as_ref().unwrap().get(1).unwrap();
     println!("{:?}", event);
     assert_eq!(event.name, "event2");
-    assert_eq!(matches!(event.comment, None), true);
+    assert!(event.comment.is_none());
+}
+
+#[test]
+fn test_parse_trigger_event_alias() {
+    let source = r#"
+Component({
+    methods: {
+        select() {
+            const self = this;
+            setTimeout(function () {
+                self.triggerEvent("select", {});
+            });
+        }
+    }
+});"#;
+    let result = parse_component_source("card.js", source.to_string()).unwrap();
+    let events = result.events.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name, "select");
 }
 
 #[test]
 fn test_parse_page() {
     let result = parse_component("test/fixtures/page.js").unwrap();
-    assert_eq!(matches!(result.r#type, ComponentType::Page), true);
+    assert!(matches!(result.r#type, ComponentType::Page));
     assert_eq!(result.data.len(), 4);
     assert_eq!(result.methods.len(), 6);
     assert_eq!(result.properties.len(), 0);
-    assert_eq!(matches!(result.events, None), true);
+    assert!(result.events.is_none());
 
-    let data = result.data.get(0).unwrap();
+    let data = result.data.first().unwrap();
     assert_eq!(data.name, "data1");
     assert_eq!(data.comment.as_ref().unwrap(), "// data1 属性");
     assert_eq!(data.loc, Location::from([8, 8], [8, 13]));
 
-    let method = result.methods.get(0).unwrap();
+    let method = result.methods.first().unwrap();
     assert_eq!(method.name, "method1");
     assert_eq!(method.comment.as_ref().unwrap(), "// method1 方法");
 
-    let not_found = parse_component("test/fixtures/page-notfound.js").err();
+    let not_found = parse_component("test/fixtures/page-notfound.js")
+        .err()
+        .map(|e| e.message);
     assert_eq!(
         Some(String::from("No such file test/fixtures/page-notfound.js")),
         not_found
@@ -628,8 +903,8 @@ Page(guess.wrapOptions({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module).unwrap();
-    assert_eq!(matches!(call_module.r#type, ComponentType::Page), true);
-    assert_eq!(matches!(call_module.expr, Some(_)), true);
+    assert!(matches!(call_module.r#type, ComponentType::Page));
+    assert!(call_module.expr.is_some());
 
     // Page
     let source_code = r#"
@@ -646,8 +921,8 @@ myPage({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module).unwrap();
-    assert_eq!(matches!(call_module.r#type, ComponentType::Page), true);
-    assert_eq!(matches!(call_module.expr, Some(_)), true);
+    assert!(matches!(call_module.r#type, ComponentType::Page));
+    assert!(call_module.expr.is_some());
 
     // Component
     let source_code = r#"
@@ -664,8 +939,8 @@ Component(wrap(wrap1({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module).unwrap();
-    assert_eq!(matches!(call_module.r#type, ComponentType::Component), true);
-    assert_eq!(matches!(call_module.expr, Some(_)), true);
+    assert!(matches!(call_module.r#type, ComponentType::Component));
+    assert!(call_module.expr.is_some());
 
     // Component
     let source_code = r#"
@@ -682,8 +957,8 @@ myComponent({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module).unwrap();
-    assert_eq!(matches!(call_module.r#type, ComponentType::Component), true);
-    assert_eq!(matches!(call_module.expr, Some(_)), true);
+    assert!(matches!(call_module.r#type, ComponentType::Component));
+    assert!(call_module.expr.is_some());
 
     // None
     let source_code = r#"
@@ -700,8 +975,8 @@ Component([{
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module).unwrap();
-    assert_eq!(matches!(call_module.r#type, ComponentType::Component), true);
-    assert_eq!(matches!(call_module.expr, None), true);
+    assert!(matches!(call_module.r#type, ComponentType::Component));
+    assert!(call_module.expr.is_none());
 
     // None
     let source_code = r#"
@@ -717,8 +992,8 @@ Page(wrap({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module).unwrap();
-    assert_eq!(matches!(call_module.r#type, ComponentType::Page), true);
-    assert_eq!(matches!(call_module.expr, None), true);
+    assert!(matches!(call_module.r#type, ComponentType::Page));
+    assert!(call_module.expr.is_none());
 
     // Error
     let source_code = r#"
@@ -732,7 +1007,7 @@ Page();"#;
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module);
-    assert_eq!(matches!(call_module, Err(_)), true);
+    assert!(call_module.is_err());
 
     // Error
     let source_code = r#"
@@ -749,7 +1024,7 @@ myPage(wrap({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module);
-    assert_eq!(matches!(call_module, Err(_)), true);
+    assert!(call_module.is_err());
 
     // Error
     let source_code = r#"
@@ -765,13 +1040,142 @@ wrap({
     let mut parser = Parser::new_from(lexer);
     let module = parser.parse_module().unwrap();
     let call_module = get_component_call(&module);
-    assert_eq!(matches!(call_module, Err(_)), true);
+    assert!(call_module.is_err());
 }
 
 #[test]
 fn test_guess_component_params() {
     let result = parse_component("test/fixtures/guess-page.js").unwrap();
-    assert_eq!(matches!(result.r#type, ComponentType::Page), true);
+    assert!(matches!(result.r#type, ComponentType::Page));
     assert_eq!(result.data.len(), 1);
     assert_eq!(result.methods.len(), 1);
 }
+
+#[test]
+fn test_parse_deep_nesting() {
+    use crate::pool;
+
+    // 超过嵌套上限直接返回错误，不交给 swc 解析
+    let source = format!("Page({}{})", "[".repeat(10_000), "]".repeat(10_000));
+    let error = parse_component_source("deep.js", source).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+
+    // 字符串和注释中的括号不计入嵌套
+    let source = format!(
+        "Page({{data: {{a: '{0}', b: `{0}`}}}}) // {0}",
+        "(".repeat(10_000)
+    );
+    assert!(parse_component_source("deep.js", source).is_ok());
+
+    // 正则表达式中未配对的括号不计入嵌套，除号后面的括号正常计入
+    let source = format!(
+        "Page({{data: {{}}, f(a) {{ return a{}; }}}})",
+        r".replace(/\(+/g, '').split(/[(/]/)".repeat(300)
+    );
+    assert!(parse_component_source("deep.js", source).is_ok());
+    assert!(!exceeds_nesting_depth("typeof /(/ === 'object'", 0));
+    assert!(exceeds_nesting_depth("a / (b) / 2", 0));
+
+    // 上限以内的嵌套在解析线程中可以正常解析
+    let depth = MAX_NESTING_DEPTH - 2;
+    let source = format!("Page({}0{})", "(".repeat(depth), ")".repeat(depth));
+    let error = pool::install(|| parse_component_source("deep.js", source)).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ComponentNotFound);
+
+    let source = format!(
+        "Page({}{{data: {{a: 1}}, onLoad() {{}}}}{})",
+        "wrap(".repeat(100),
+        ")".repeat(100)
+    );
+    let error = pool::install(|| parse_component_source("deep.js", source)).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ComponentNotFound);
+
+    let data = format!("{}1{}", "{a: ".repeat(100), "}".repeat(100));
+    let source = format!("Page({{data: {{a: {}}}, onLoad() {{}}}})", data);
+    let result = pool::install(|| parse_component_source("deep.js", source)).unwrap();
+    let mut depth = 0;
+    let mut children = &result.data[0].children;
+    while let Some(data) = children.as_ref().and_then(|c| c.first()) {
+        depth += 1;
+        children = &data.children;
+    }
+    assert_eq!(depth, MAX_DATA_DEPTH - 1);
+}
+
+#[test]
+fn test_parse_typescript() {
+    let source = "interface Data { a: number }\nPage({data: {a: 1} as Data, onLoad(): void {}});";
+    let result = parse_component_source("page.ts", source.to_string()).unwrap();
+    assert!(matches!(result.r#type, ComponentType::Page));
+    assert_eq!(result.methods.len(), 1);
+
+    let error = parse_component_source("page.js", source.to_string()).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::SyntaxError);
+}
+
+#[test]
+fn test_parse_computed() {
+    let source = r#"
+Component({
+    properties: {},
+    computed: {
+        // 完整名称
+        fullName() {
+            return this.data.firstName + this.data.lastName;
+        },
+        count: function () {}
+    }
+});"#;
+    let result = parse_component_source("component.js", source.to_string()).unwrap();
+    let names: Vec<&str> = result.computed.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["fullName", "count"]);
+    assert_eq!(result.computed[0].comment.as_deref(), Some("// 完整名称"));
+    assert_eq!(result.computed[0].loc, Location::from([6, 8], [6, 16]));
+}
+
+#[test]
+fn test_parse_array_items() {
+    let source = r#"
+Page({
+    data: {
+        list: [
+            {title: 'a', tags: [{name: 'x'}]},
+            {title: 'b', id: 2},
+            1
+        ],
+        empty: [],
+        group: {
+            users: [{name: 'c'}]
+        }
+    },
+    onLoad() {}
+});"#;
+    let result = parse_component_source("page.js", source.to_string()).unwrap();
+    let items = result.data[0].items.as_ref().unwrap();
+    let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, vec!["title", "tags", "id"]);
+    assert_eq!(items[1].items.as_ref().unwrap()[0].name, "name");
+    assert_eq!(items[0].loc, Location::from([5, 13], [5, 18]));
+    assert!(result.data[1].items.is_none());
+    let users = &result.data[2].children.as_ref().unwrap()[0];
+    assert_eq!(users.items.as_ref().unwrap()[0].name, "name");
+}
+
+#[test]
+fn test_parse_component_options() {
+    let source = r#"
+Component({
+    behaviors: [expandable, 'swan://form-field'],
+    externalClasses: ['custom-class'],
+    properties: {
+        title: String,
+        count: {type: Number, value: 0}
+    }
+});"#;
+    let result = parse_component_source("component.js", source.to_string()).unwrap();
+    assert_eq!(result.behaviors, vec!["expandable", "swan://form-field"]);
+    assert_eq!(result.external_classes, vec!["custom-class"]);
+    assert_eq!(result.properties.len(), 2);
+    assert_eq!(result.properties[0].r#type, "String");
+    assert_eq!(result.properties[0].loc, Location::from([6, 8], [6, 13]));
+}
@@ -0,0 +1,794 @@
+use crate::parser::catch_panic;
+use crate::parser::meta::{
+    AttributeMeta, Diagnostic, ElementMeta, InterpolationMeta, Location, ParseError,
+    ParseErrorKind, Position, TemplateMeta, TemplateNode, ValuePart,
+};
+use crate::parser::script::MAX_NESTING_DEPTH;
+use std::fs;
+
+/// 不需要结束标签的元素，多余的结束标签会被忽略
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "input", "import", "include"];
+
+/// 内容为脚本源码的元素，内容作为文本，不解析其中的标签和插值
+const RAW_TEXT_ELEMENTS: &[&str] = &["filter", "import-sjs"];
+
+/// 读取并解析 swan 模板
+pub fn parse_template(file_path: &str) -> Result<TemplateMeta, ParseError> {
+    let read_result = fs::read_to_string(file_path);
+    if read_result.is_err() {
+        return Err(ParseError::not_found(file_path));
+    }
+    parse_template_source(file_path, &read_result.unwrap())
+}
+
+/// 解析已读取的模板源码，`file_path` 仅用于错误信息
+///
+/// 解析是容错的，未闭合的标签、插值等记录在 `diagnostics` 中，只有嵌套过深时返回错误
+pub fn parse_template_source(file_path: &str, text: &str) -> Result<TemplateMeta, ParseError> {
+    catch_panic(file_path, || TemplateParser::new(file_path, text).parse())
+}
+
+/// 带引号的属性值的结束位置
+enum ValueEnd {
+    /// 结束引号的位置
+    Closed(usize),
+    /// 没有结束引号，值到下一个标签开始或文件末尾为止
+    Unclosed(usize),
+}
+
+struct TemplateParser<'a> {
+    file_path: &'a str,
+    chars: Vec<char>,
+    /// 每行第一个字符的下标
+    line_starts: Vec<usize>,
+    index: usize,
+    /// 尚未闭合的元素
+    stack: Vec<ElementMeta>,
+    nodes: Vec<TemplateNode>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> TemplateParser<'a> {
+    fn new(file_path: &'a str, text: &str) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let mut line_starts = vec![0];
+        for (i, c) in chars.iter().enumerate() {
+            if *c == '\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        TemplateParser {
+            file_path,
+            chars,
+            line_starts,
+            index: 0,
+            stack: vec![],
+            nodes: vec![],
+            diagnostics: vec![],
+        }
+    }
+
+    fn position(&self, index: usize) -> Position {
+        let line = match self.line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        Position {
+            line: line + 1,
+            column: index - self.line_starts[line],
+        }
+    }
+
+    fn loc(&self, start: usize, end: usize) -> Location {
+        Location {
+            start: self.position(start),
+            end: self.position(end),
+        }
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.chars[start..end].iter().collect()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn starts_with(&self, index: usize, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(index + i) == Some(&c))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    fn error(&mut self, message: String, start: usize, end: usize) {
+        let loc = self.loc(start, end);
+        self.diagnostics.push(Diagnostic::error(message, loc));
+    }
+
+    /// `<` 后跟 `/`、`!`，或者跟着以字母开头、之后是空白、`>`、`/` 或文件末尾的标签名时才是标签，
+    /// `a < b`、`a<b</text>` 中的 `<` 是文本
+    fn is_tag_start(&self, index: usize) -> bool {
+        if self.chars.get(index) != Some(&'<') {
+            return false;
+        }
+        match self.chars.get(index + 1) {
+            Some('/' | '!') => true,
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name_end = (index + 1..self.chars.len())
+                    .find(|&i| {
+                        let c = self.chars[i];
+                        !(c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+                    })
+                    .unwrap_or(self.chars.len());
+                matches!(
+                    self.chars.get(name_end),
+                    None | Some('>' | '/') | Some(' ' | '\t' | '\r' | '\n')
+                )
+            }
+            _ => false,
+        }
+    }
+
+    fn is_interpolation_start(&self, index: usize) -> bool {
+        self.starts_with(index, "{{") || self.starts_with(index, "{=")
+    }
+
+    /// `index` 处插值的结束位置，即结束定界符之后，字符串中的定界符不结束插值，例如 `{{ '}}' }}`，
+    /// 对象字面量中的 `}` 也不结束插值，例如 `{{{...item}}}` 的表达式为 `{...item}`
+    ///
+    /// 在 `limit` 之前没有结束定界符，或者先遇到了下一个插值时，视为未闭合，返回 `None`
+    fn interpolation_end(&self, index: usize, limit: usize) -> Option<usize> {
+        let close = if self.starts_with(index, "{{") {
+            "}}"
+        } else {
+            "=}"
+        };
+        let mut depth = 0;
+        let mut i = index + 2;
+        while i < limit {
+            if depth == 0 && self.starts_with(i, close) {
+                return Some(i + 2);
+            }
+            if self.is_interpolation_start(i) {
+                return None;
+            }
+            match self.chars[i] {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                '\'' | '"' | '`' => {
+                    if let Some(end) = self.string_end(i, limit) {
+                        i = end;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// `start` 处字符串的结束位置，即结束引号之后，没有闭合的引号（普通字符串遇到换行）返回 `None`
+    fn string_end(&self, start: usize, limit: usize) -> Option<usize> {
+        let quote = self.chars[start];
+        let mut i = start + 1;
+        while i < limit {
+            match self.chars[i] {
+                '\\' => i += 1,
+                '\n' if quote != '`' => return None,
+                c if c == quote => return Some(i + 1),
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// 将 `[start, end)` 拆分为文本和插值
+    fn split_parts(&mut self, start: usize, end: usize) -> Vec<ValuePart> {
+        let mut parts = vec![];
+        let mut text_start = start;
+        let mut i = start;
+        while i < end {
+            if !self.is_interpolation_start(i) {
+                i += 1;
+                continue;
+            }
+            let (expression_end, part_end) = match self.interpolation_end(i, end) {
+                Some(part_end) => (part_end - 2, part_end),
+                None => {
+                    // 正在输入的插值，到下一个插值或结尾为止
+                    let next = (i + 2..end)
+                        .find(|&j| self.is_interpolation_start(j))
+                        .unwrap_or(end);
+                    self.error(String::from("unclosed interpolation"), i, i + 2);
+                    (next, next)
+                }
+            };
+            if text_start < i {
+                parts.push(ValuePart::Text {
+                    value: self.text(text_start, i),
+                    loc: self.loc(text_start, i),
+                });
+            }
+            parts.push(ValuePart::Interpolation(InterpolationMeta {
+                expression: self.text(i + 2, expression_end),
+                two_way: self.chars[i + 1] == '=',
+                loc: self.loc(i, part_end),
+                expression_loc: self.loc(i + 2, expression_end),
+            }));
+            i = part_end;
+            text_start = part_end;
+        }
+        if text_start < end {
+            parts.push(ValuePart::Text {
+                value: self.text(text_start, end),
+                loc: self.loc(text_start, end),
+            });
+        }
+        parts
+    }
+
+    fn push_node(&mut self, node: TemplateNode) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.nodes.push(node),
+        }
+    }
+
+    fn close(&mut self, mut element: ElementMeta, end: usize) {
+        element.loc.end = self.position(end);
+        self.push_node(TemplateNode::Element(element));
+    }
+
+    /// 文本到下一个标签开始或文件末尾为止，跳过插值中的 `<`
+    fn parse_text(&mut self) {
+        let start = self.index;
+        let len = self.chars.len();
+        let mut end = start;
+        while end < len {
+            if self.is_interpolation_start(end) {
+                if let Some(interpolation_end) = self.interpolation_end(end, len) {
+                    end = interpolation_end;
+                    continue;
+                }
+            }
+            if self.is_tag_start(end) {
+                break;
+            }
+            end += 1;
+        }
+        self.index = end;
+
+        for part in self.split_parts(start, end) {
+            match part {
+                ValuePart::Text { value, loc } => {
+                    if !value.trim().is_empty() {
+                        self.push_node(TemplateNode::Text { value, loc });
+                    }
+                }
+                ValuePart::Interpolation(interpolation) => {
+                    self.push_node(TemplateNode::Interpolation(interpolation))
+                }
+            }
+        }
+    }
+
+    /// `<!-- -->` 注释，`<!DOCTYPE>` 等到 `>` 为止
+    fn parse_comment(&mut self) {
+        let start = self.index;
+        let len = self.chars.len();
+        let (content_start, close) = if self.starts_with(start, "<!--") {
+            (start + 4, "-->")
+        } else {
+            (start + 2, ">")
+        };
+        let content_end = (content_start..len)
+            .find(|&i| self.starts_with(i, close))
+            .unwrap_or(len);
+        self.index = if content_end == len {
+            self.error(String::from("unclosed comment"), start, content_start);
+            len
+        } else {
+            content_end + close.len()
+        };
+        self.push_node(TemplateNode::Comment {
+            value: self.text(content_start, content_end),
+            loc: self.loc(start, self.index),
+        });
+    }
+
+    fn read_name(&mut self) -> (String, usize, usize) {
+        let start = self.index;
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, '/' | '>' | '<' | '=' | '"' | '\'') {
+                break;
+            }
+            self.index += 1;
+        }
+        (self.text(start, self.index), start, self.index)
+    }
+
+    fn parse_end_tag(&mut self) {
+        let start = self.index;
+        self.index += 2;
+        let (name, name_start, name_end) = self.read_name();
+        self.skip_whitespace();
+        if self.peek() == Some('>') {
+            self.index += 1;
+        } else {
+            self.error(format!("unclosed end tag `</{}>`", name), start, self.index);
+        }
+        let end = self.index;
+
+        // 从栈顶查找同名元素，中间未闭合的元素在结束标签前结束
+        match self.stack.iter().rposition(|e| e.name == name) {
+            Some(position) => {
+                while self.stack.len() > position + 1 {
+                    let element = self.stack.pop().unwrap();
+                    let loc = element.name_loc.clone();
+                    self.diagnostics.push(Diagnostic::error(
+                        format!("unclosed tag `<{}>`", element.name),
+                        loc,
+                    ));
+                    self.close(element, start);
+                }
+                let mut element = self.stack.pop().unwrap();
+                element.end_name_loc = Some(self.loc(name_start, name_end));
+                self.close(element, end);
+            }
+            None if VOID_ELEMENTS.contains(&name.as_str()) => (),
+            None => self.error(format!("unexpected closing tag `</{}>`", name), start, end),
+        }
+    }
+
+    fn parse_start_tag(&mut self) -> Result<(), ParseError> {
+        let start = self.index;
+        self.index += 1;
+        let (name, name_start, name_end) = self.read_name();
+        let mut element = ElementMeta {
+            name,
+            attributes: vec![],
+            children: vec![],
+            loc: self.loc(start, start),
+            name_loc: self.loc(name_start, name_end),
+            end_name_loc: None,
+            self_closing: false,
+        };
+
+        let closed = loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break false,
+                Some('>') => {
+                    self.index += 1;
+                    break true;
+                }
+                Some('/') if self.starts_with(self.index, "/>") => {
+                    self.index += 2;
+                    element.self_closing = true;
+                    break true;
+                }
+                Some('<') if self.is_tag_start(self.index) => break false,
+                _ => match self.parse_attribute() {
+                    Some(attribute) => element.attributes.push(attribute),
+                    // 跳过属性之间多余的字符
+                    None => self.index += 1,
+                },
+            }
+        };
+
+        // 正在输入的开始标签没有子节点
+        if !closed {
+            let loc = element.name_loc.clone();
+            self.diagnostics.push(Diagnostic::error(
+                format!("unclosed start tag `<{}>`", element.name),
+                loc,
+            ));
+            self.close(element, self.index);
+            return Ok(());
+        }
+        if element.self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+            self.close(element, self.index);
+            return Ok(());
+        }
+
+        if self.stack.len() >= MAX_NESTING_DEPTH {
+            let mut error = ParseError::new(
+                ParseErrorKind::NestingTooDeep,
+                format!(
+                    "failed to parse {}, nesting exceeds {} levels",
+                    self.file_path, MAX_NESTING_DEPTH
+                ),
+            );
+            error.loc = Some(element.name_loc);
+            return Err(error);
+        }
+        if RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+            self.parse_raw_text(&mut element);
+        }
+        self.stack.push(element);
+        Ok(())
+    }
+
+    /// 读取到结束标签之前的内容作为文本，结束标签由主循环处理
+    fn parse_raw_text(&mut self, element: &mut ElementMeta) {
+        let close = format!("</{}", element.name);
+        let start = self.index;
+        let len = self.chars.len();
+        let end = (start..len)
+            .find(|&i| self.starts_with(i, &close))
+            .unwrap_or(len);
+        let value = self.text(start, end);
+        if !value.trim().is_empty() {
+            element.children.push(TemplateNode::Text {
+                value,
+                loc: self.loc(start, end),
+            });
+        }
+        self.index = end;
+    }
+
+    /// 值中的 `<` 不结束属性值，只有换行之后出现标签时才认为是正在输入、没有闭合的引号
+    fn quoted_value_end(&self, start: usize, quote: char) -> ValueEnd {
+        let len = self.chars.len();
+        let mut multiline = false;
+        let mut i = start;
+        while i < len {
+            if self.is_interpolation_start(i) {
+                if let Some(end) = self.interpolation_end(i, len) {
+                    i = end;
+                    continue;
+                }
+            }
+            if self.chars[i] == quote {
+                return ValueEnd::Closed(i);
+            }
+            // 换行后插值外的标签说明引号没有闭合
+            if multiline && self.is_tag_start(i) {
+                return ValueEnd::Unclosed(i);
+            }
+            multiline |= self.chars[i] == '\n';
+            i += 1;
+        }
+        ValueEnd::Unclosed(len)
+    }
+
+    fn unquoted_value_end(&self, start: usize) -> usize {
+        let len = self.chars.len();
+        let mut i = start;
+        while i < len {
+            if self.is_interpolation_start(i) {
+                if let Some(end) = self.interpolation_end(i, len) {
+                    i = end;
+                    continue;
+                }
+            }
+            let c = self.chars[i];
+            if c.is_whitespace() || c == '>' || self.starts_with(i, "/>") || self.is_tag_start(i) {
+                break;
+            }
+            i += 1;
+        }
+        i
+    }
+
+    fn parse_attribute(&mut self) -> Option<AttributeMeta> {
+        let (name, name_start, name_end) = self.read_name();
+        if name.is_empty() {
+            return None;
+        }
+        let mut attribute = AttributeMeta {
+            name,
+            value: None,
+            loc: self.loc(name_start, name_end),
+            name_loc: self.loc(name_start, name_end),
+            value_loc: None,
+            parts: vec![],
+        };
+
+        self.skip_whitespace();
+        if self.peek() != Some('=') {
+            // 没有值的属性，属性名之后的空白留给下一个属性
+            self.index = name_end;
+            return Some(attribute);
+        }
+        self.index += 1;
+        self.skip_whitespace();
+
+        let (value_start, value_end, end) = match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                let value_start = self.index + 1;
+                match self.quoted_value_end(value_start, quote) {
+                    ValueEnd::Closed(value_end) => (value_start, value_end, value_end + 1),
+                    ValueEnd::Unclosed(value_end) => {
+                        self.error(
+                            format!("unclosed value of attribute `{}`", attribute.name),
+                            self.index,
+                            value_end,
+                        );
+                        (value_start, value_end, value_end)
+                    }
+                }
+            }
+            _ => {
+                let value_end = self.unquoted_value_end(self.index);
+                (self.index, value_end, value_end)
+            }
+        };
+        self.index = end;
+        attribute.value = Some(self.text(value_start, value_end));
+        attribute.loc = self.loc(name_start, end);
+        attribute.value_loc = Some(self.loc(value_start, value_end));
+        attribute.parts = self.split_parts(value_start, value_end);
+        Some(attribute)
+    }
+
+    fn parse(mut self) -> Result<TemplateMeta, ParseError> {
+        let len = self.chars.len();
+        while self.index < len {
+            if self.starts_with(self.index, "<!") {
+                self.parse_comment();
+            } else if self.starts_with(self.index, "</") {
+                self.parse_end_tag();
+            } else if self.is_tag_start(self.index) {
+                self.parse_start_tag()?;
+            } else {
+                self.parse_text();
+            }
+        }
+        while let Some(element) = self.stack.pop() {
+            let loc = element.name_loc.clone();
+            self.diagnostics.push(Diagnostic::error(
+                format!("unclosed tag `<{}>`", element.name),
+                loc,
+            ));
+            self.close(element, len);
+        }
+        Ok(TemplateMeta {
+            nodes: self.nodes,
+            diagnostics: self.diagnostics,
+        })
+    }
+}
+
+#[cfg(test)]
+fn element(node: &TemplateNode) -> &ElementMeta {
+    match node {
+        TemplateNode::Element(element) => element,
+        node => panic!("expected element, found {:?}", node),
+    }
+}
+
+#[test]
+fn test_parse_template() {
+    let meta = parse_template("test/fixtures/component.swan").unwrap();
+    assert!(meta.diagnostics.is_empty());
+    assert_eq!(meta.nodes.len(), 1);
+
+    let root = element(&meta.nodes[0]);
+    assert_eq!(root.name, "view");
+    assert_eq!(root.loc, Location::from([1, 0], [5, 7]));
+    assert_eq!(root.end_name_loc, Some(Location::from([5, 2], [5, 6])));
+    let s_if = root.attribute("s-if").unwrap();
+    assert_eq!(s_if.value.as_deref(), Some("ifOpen"));
+    assert_eq!(s_if.value_loc, Some(Location::from([1, 12], [1, 18])));
+
+    // 插值中的 `<` 和 `>` 不影响标签
+    let child = element(&root.children[0]);
+    let class = child.attribute("class").unwrap();
+    assert_eq!(class.parts.len(), 2);
+    assert_eq!(
+        class.parts[0],
+        ValuePart::Text {
+            value: String::from("class-0 "),
+            loc: Location::from([2, 17], [2, 25]),
+        }
+    );
+    match &class.parts[1] {
+        ValuePart::Interpolation(interpolation) => {
+            assert!(interpolation
+                .expression
+                .starts_with("data1 < 10 ? \"data1\""));
+            assert!(!interpolation.two_way);
+            assert_eq!(
+                interpolation.expression_loc.start,
+                Position {
+                    line: 2,
+                    column: 27
+                }
+            );
+        }
+        part => panic!("unexpected part {:?}", part),
+    }
+    assert_eq!(
+        child.attribute("s-for").unwrap().value.as_deref(),
+        Some("list")
+    );
+    assert_eq!(
+        child.children,
+        vec![TemplateNode::Interpolation(InterpolationMeta {
+            expression: String::from("data2"),
+            two_way: false,
+            loc: Location::from([3, 8], [3, 17]),
+            expression_loc: Location::from([3, 10], [3, 15]),
+        })]
+    );
+}
+
+#[test]
+fn test_parse_template_tolerant() {
+    let text = "<!-- 注释 -->\n<input value=\"{= value =}\" disabled/>\n<view a={{b}}>{{ a < b }} text</view>\n</text>\n<filter module=\"f\">var a = x < y && '</view>';</filter>";
+    let meta = parse_template_source("a.swan", text).unwrap();
+    assert_eq!(meta.nodes.len(), 4);
+    assert_eq!(
+        meta.nodes[0],
+        TemplateNode::Comment {
+            value: String::from(" 注释 "),
+            loc: Location::from([1, 0], [1, 11]),
+        }
+    );
+    let input = element(&meta.nodes[1]);
+    assert!(input.self_closing);
+    assert_eq!(input.attributes[1].name, "disabled");
+    assert_eq!(input.attributes[1].value, None);
+    match &input.attributes[0].parts[0] {
+        ValuePart::Interpolation(interpolation) => {
+            assert!(interpolation.two_way);
+            assert_eq!(interpolation.expression, " value ");
+        }
+        part => panic!("unexpected part {:?}", part),
+    }
+    let view = element(&meta.nodes[2]);
+    assert_eq!(view.attribute("a").unwrap().value.as_deref(), Some("{{b}}"));
+    assert_eq!(view.children.len(), 2);
+    let filter = element(&meta.nodes[3]);
+    assert_eq!(
+        filter.children,
+        vec![TemplateNode::Text {
+            value: String::from("var a = x < y && '</view>';"),
+            loc: Location::from([5, 19], [5, 46]),
+        }]
+    );
+    // filter 中的 `</view>` 是脚本中的字符串
+    let messages: Vec<&str> = meta
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(messages, vec!["unexpected closing tag `</text>`"]);
+}
+
+#[test]
+fn test_parse_template_less_than() {
+    // 属性值和文本中的 `<` 不是标签
+    let text =
+        "<view s-if=\"a<b\" title=\"a<b\">x</view>\n<view s-if=\"{{a<b}}\" />\n<text>a<b</text>";
+    let meta = parse_template_source("a.swan", text).unwrap();
+    assert!(meta.diagnostics.is_empty());
+    assert_eq!(meta.nodes.len(), 3);
+    let view = element(&meta.nodes[0]);
+    assert_eq!(
+        view.attribute("s-if").unwrap().value.as_deref(),
+        Some("a<b")
+    );
+    assert_eq!(
+        view.attribute("title").unwrap().value.as_deref(),
+        Some("a<b")
+    );
+    let view = element(&meta.nodes[1]);
+    assert_eq!(
+        view.attribute("s-if").unwrap().value.as_deref(),
+        Some("{{a<b}}")
+    );
+    let text_element = element(&meta.nodes[2]);
+    assert_eq!(
+        text_element.children,
+        vec![TemplateNode::Text {
+            value: String::from("a<b"),
+            loc: Location::from([3, 6], [3, 9]),
+        }]
+    );
+}
+
+#[test]
+fn test_parse_template_string_delimiters() {
+    // 字符串中的 `}}` 不结束插值
+    let text = "<text>{{ '}}' + \"}}\" }}</text>\n<view title=\"{{ a ? '}}' : 'b' }}\" />";
+    let meta = parse_template_source("a.swan", text).unwrap();
+    assert!(meta.diagnostics.is_empty());
+    match &element(&meta.nodes[0]).children[0] {
+        TemplateNode::Interpolation(interpolation) => {
+            assert_eq!(interpolation.expression, " '}}' + \"}}\" ");
+        }
+        node => panic!("unexpected node {:?}", node),
+    }
+    let view = element(&meta.nodes[1]);
+    assert_eq!(
+        view.attribute("title").unwrap().value.as_deref(),
+        Some("{{ a ? '}}' : 'b' }}")
+    );
+    let bindings = crate::parser::extract_bindings(&meta);
+    assert!(bindings.diagnostics.is_empty());
+
+    // `{{{ }}}` 中为对象字面量
+    let text = "<template is=\"card\" data=\"{{{...person}}}\" />{{ {a: {b: 1}}.a }}";
+    let meta = parse_template_source("a.swan", text).unwrap();
+    assert!(meta.diagnostics.is_empty());
+    match &element(&meta.nodes[0]).attribute("data").unwrap().parts[..] {
+        [ValuePart::Interpolation(interpolation)] => {
+            assert_eq!(interpolation.expression, "{...person}");
+            assert_eq!(interpolation.loc, Location::from([1, 26], [1, 41]));
+        }
+        parts => panic!("unexpected parts {:?}", parts),
+    }
+    match &meta.nodes[1] {
+        TemplateNode::Interpolation(interpolation) => {
+            assert_eq!(interpolation.expression, " {a: {b: 1}}.a ");
+        }
+        node => panic!("unexpected node {:?}", node),
+    }
+}
+
+#[test]
+fn test_parse_template_typing() {
+    // 输入中的模板：未闭合的插值、属性值和标签
+    let text =
+        "<view class=\"a\">\n    <text>{{ti</text>\n    <image src=\"{{url\n    <view class=\"b";
+    let meta = parse_template_source("a.swan", text).unwrap();
+    let root = element(&meta.nodes[0]);
+    assert_eq!(
+        root.loc.end,
+        Position {
+            line: 4,
+            column: 18
+        }
+    );
+    let text_element = element(&root.children[0]);
+    assert_eq!(
+        text_element.end_name_loc,
+        Some(Location::from([2, 16], [2, 20]))
+    );
+    match &text_element.children[0] {
+        TemplateNode::Interpolation(interpolation) => {
+            assert_eq!(interpolation.expression, "ti");
+        }
+        node => panic!("unexpected node {:?}", node),
+    }
+    let image = element(&root.children[1]);
+    assert_eq!(
+        image.attribute("src").unwrap().value.as_deref(),
+        Some("{{url\n    ")
+    );
+    let view = element(&root.children[2]);
+    assert_eq!(view.attribute("class").unwrap().value.as_deref(), Some("b"));
+
+    let messages: Vec<&str> = meta
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "unclosed interpolation",
+            "unclosed value of attribute `src`",
+            "unclosed interpolation",
+            "unclosed start tag `<image>`",
+            "unclosed value of attribute `class`",
+            "unclosed start tag `<view>`",
+            "unclosed tag `<view>`",
+        ]
+    );
+
+    let text = "<view>".repeat(1000);
+    let error = parse_template_source("a.swan", &text).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+}
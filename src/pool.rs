@@ -0,0 +1,111 @@
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+static POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// 默认并发数，保留一个核给编辑器进程
+pub fn default_concurrency() -> usize {
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    if cores > 1 {
+        cores - 1
+    } else {
+        1
+    }
+}
+
+/// 解析线程的栈大小，足够 swc 递归解析 `MAX_NESTING_DEPTH` 层嵌套
+const STACK_SIZE: usize = 16 * 1024 * 1024;
+
+fn build_pool(concurrency: usize) -> Result<Arc<ThreadPool>, String> {
+    ThreadPoolBuilder::new()
+        .num_threads(concurrency)
+        .stack_size(STACK_SIZE)
+        .thread_name(|i| format!("component-parser-{}", i))
+        .build()
+        .map(Arc::new)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取共享线程池，无法创建线程时返回 `None`
+fn get_pool() -> Option<Arc<ThreadPool>> {
+    if let Some(pool) = POOL.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Some(pool.clone());
+    }
+    let mut pool = POOL.write().unwrap_or_else(|e| e.into_inner());
+    if pool.is_none() {
+        *pool = build_pool(default_concurrency()).ok();
+    }
+    pool.clone()
+}
+
+/// 设置解析线程池的并发数，`0` 表示使用默认并发数
+///
+/// 正在执行的任务会在原线程池中完成，之后的任务使用新线程池
+pub fn set_concurrency(concurrency: usize) -> Result<(), String> {
+    let concurrency = if concurrency == 0 {
+        default_concurrency()
+    } else {
+        concurrency
+    };
+    let pool = build_pool(concurrency)?;
+    *POOL.write().unwrap_or_else(|e| e.into_inner()) = Some(pool);
+    Ok(())
+}
+
+/// 在共享的解析线程池中执行任务，js 和 css 解析共用同一个线程池
+///
+/// 无法创建线程池时在当前线程执行，WebAssembly 中没有线程，总是在当前线程执行
+pub fn install<R, F>(op: F) -> R
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    if cfg!(target_arch = "wasm32") {
+        return op();
+    }
+    match get_pool() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// 在共享的解析线程池中异步执行任务
+///
+/// 无法创建线程池时在当前线程执行
+#[cfg(feature = "node")]
+pub fn spawn<F>(op: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    match get_pool() {
+        Some(pool) => pool.spawn(op),
+        None => op(),
+    }
+}
+
+#[test]
+fn test_install() {
+    use rayon::prelude::*;
+
+    let result: Vec<usize> = install(|| (0..100).into_par_iter().map(|i| i * 2).collect());
+    assert_eq!(result.len(), 100);
+    assert_eq!(result[99], 198);
+
+    let threads = install(rayon::current_num_threads);
+    assert!(threads >= 1);
+}
+
+#[test]
+fn test_stack_size() {
+    // 深层递归在解析线程中不会栈溢出
+    fn recurse(depth: usize) -> usize {
+        let buffer = [depth as u8; 1024];
+        if depth == 0 {
+            return buffer[0] as usize;
+        }
+        recurse(depth - 1) + std::hint::black_box(buffer)[0] as usize
+    }
+    let result = install(|| recurse(4 * 1024));
+    assert!(result > 0);
+}
@@ -0,0 +1,599 @@
+//! 从 `app.json` 出发查找项目中的页面和组件，解析同名的 js、swan、css、json 文件
+
+use crate::analyzer::{
+    check_classes, check_components, check_module_calls, check_template, check_template_imports,
+    check_template_modules, check_template_usages, template_classes, template_definitions,
+    template_imports, template_modules, template_usages, TemplateClassMeta, TemplateDefinitionMeta,
+    TemplateImportKind, TemplateImportMeta, TemplateModuleMeta,
+};
+use crate::batch::{BatchContext, FileResult};
+use crate::parser::{
+    parse_filter, parse_template, ComponentMeta, ConfigMeta, CssMeta, Diagnostic,
+    FilterFunctionMeta, ParseError, ParseErrorKind, TemplateMeta,
+};
+use crate::resolver::{find_files, ComponentTarget, ResolvedComponent, Resolver};
+use crate::Parser;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UnitKind {
+    Page,
+    Component,
+}
+
+/// 页面或组件的同名文件，不存在的文件为 `None`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UnitFiles {
+    /// `.js` 或 `.ts` 文件，同时存在时使用 `.js`
+    pub script: Option<String>,
+    /// `.swan` 模板文件
+    pub template: Option<String>,
+    /// `.css` 样式文件
+    pub style: Option<String>,
+    /// `.json` 配置文件
+    pub config: Option<String>,
+}
+
+/// 分包配置，`pages` 为加上分包根目录后的页面路径
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SubPackage {
+    pub root: String,
+    pub pages: Vec<String>,
+}
+
+/// `app.json` 中与页面和组件相关的配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AppMeta {
+    pub pages: Vec<String>,
+    #[serde(rename = "subPackages")]
+    pub sub_packages: Vec<SubPackage>,
+    /// `tabBar.list` 中的页面路径
+    #[serde(rename = "tabBar")]
+    pub tab_bar: Vec<String>,
+    /// 全局注册的组件，所有页面和组件都可以使用
+    #[serde(rename = "usingComponents")]
+    pub using_components: BTreeMap<String, String>,
+}
+
+/// 页面或组件，路径为相对项目根目录、不带扩展名的路径，例如 `pages/index/index`
+#[derive(Debug, Serialize)]
+pub struct ProjectUnit {
+    pub path: String,
+    pub kind: UnitKind,
+    /// 所属分包的根目录，主包中为 `None`
+    pub package: Option<String>,
+    pub files: UnitFiles,
+    pub config: Option<FileResult<ConfigMeta>>,
+    /// 配置中 `usingComponents` 的解析结果
+    pub components: BTreeMap<String, ResolvedComponent>,
+    /// 无法解析的组件，位置为配置中的 key
+    pub diagnostics: Vec<Diagnostic>,
+    pub script: Option<FileResult<ComponentMeta>>,
+    pub template: Option<FileResult<TemplateMeta>>,
+    pub style: Option<FileResult<CssMeta>>,
+    /// 模板中使用的类名及可见样式文件中的定义，页面可以使用 `app.css` 中的类
+    pub classes: Vec<TemplateClassMeta>,
+}
+
+/// 项目中所有页面和组件的解析结果
+#[derive(Debug, Serialize)]
+pub struct ProjectMeta {
+    pub root: String,
+    pub app: AppMeta,
+    /// 按路径索引的页面和组件
+    pub units: BTreeMap<String, ProjectUnit>,
+}
+
+pub(crate) fn read_json(file_path: &Path) -> Result<Value, ParseError> {
+    let file_path_str = file_path.to_string_lossy();
+    let text = fs::read_to_string(file_path).map_err(|_| ParseError::not_found(&file_path_str))?;
+    serde_json::from_str(&text).map_err(|e| {
+        ParseError::new(
+            ParseErrorKind::SyntaxError,
+            format!("failed to parse {}: {}", file_path_str, e),
+        )
+    })
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    match value.as_array() {
+        Some(list) => list
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        None => vec![],
+    }
+}
+
+fn string_map(value: &Value) -> BTreeMap<String, String> {
+    match value.as_object() {
+        Some(map) => map
+            .iter()
+            .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+            .collect(),
+        None => BTreeMap::new(),
+    }
+}
+
+/// 从 `entries` 出发，沿 `@import` 收集已解析的样式文件，每个文件只出现一次
+pub(crate) fn visible_styles<'a>(
+    files: &'a HashMap<String, FileResult<CssMeta>>,
+    entries: &[String],
+) -> Vec<(&'a str, &'a CssMeta)> {
+    let mut result: Vec<(&str, &CssMeta)> = vec![];
+    let mut queue: VecDeque<&str> = entries.iter().map(|entry| entry.as_str()).collect();
+    while let Some(path) = queue.pop_front() {
+        if result.iter().any(|(p, _)| *p == path) {
+            continue;
+        }
+        if let Some((path, meta)) = files
+            .get_key_value(path)
+            .and_then(|(path, file)| Some((path.as_str(), file.meta()?)))
+        {
+            queue.extend(meta.imports.iter().flatten().map(|import| import.as_str()));
+            result.push((path, meta));
+        }
+    }
+    result
+}
+
+/// 被 `<import>` 的模板和 `<filter>`、`<import-sjs>` 引用的模块，按相对项目根目录的路径缓存，
+/// 多个页面或组件引用同一个文件时只解析一次，无法解析的文件记为 `None`
+#[derive(Default)]
+pub(crate) struct LinkCache {
+    templates: HashMap<String, Option<TemplateMeta>>,
+    modules: HashMap<String, Option<Vec<FilterFunctionMeta>>>,
+}
+
+impl LinkCache {
+    fn template(&mut self, root: &Path, path: &str) -> Option<&TemplateMeta> {
+        self.templates
+            .entry(path.to_string())
+            .or_insert_with(|| parse_template(&root.join(path).to_string_lossy()).ok())
+            .as_ref()
+    }
+
+    fn module(&mut self, root: &Path, path: &str) -> Option<Vec<FilterFunctionMeta>> {
+        self.modules
+            .entry(path.to_string())
+            .or_insert_with(|| {
+                parse_filter(&root.join(path).to_string_lossy())
+                    .ok()
+                    .map(|meta| meta.functions)
+            })
+            .clone()
+    }
+}
+
+/// 解析模板的 `<import>`、`<include>`，返回引入的文件和模板中可以使用的命名模板，
+/// 即模板自身和直接 `<import>` 的文件中定义的模板，`path` 为模板相对项目根目录的路径
+pub(crate) fn link_template(
+    resolver: &Resolver,
+    root: &Path,
+    path: &str,
+    template: &TemplateMeta,
+    cache: &mut LinkCache,
+) -> (Vec<TemplateImportMeta>, Vec<TemplateDefinitionMeta>) {
+    let mut imports = template_imports(template);
+    let mut definitions = template_definitions(path, template);
+    for import in &mut imports {
+        import.path = resolver.resolve_template(path, &import.src);
+        if let (TemplateImportKind::Import, Some(import_path)) = (&import.kind, &import.path) {
+            if let Some(imported) = cache.template(root, import_path) {
+                definitions.extend(template_definitions(import_path, imported));
+            }
+        }
+    }
+    (imports, definitions)
+}
+
+/// 解析模板中 `<filter>`、`<import-sjs>` 声明的模块，`src` 引用的文件解析后填入导出的函数，
+/// `path` 为模板相对项目根目录的路径
+pub(crate) fn load_modules(
+    resolver: &Resolver,
+    root: &Path,
+    path: &str,
+    template: &TemplateMeta,
+    cache: &mut LinkCache,
+) -> Vec<TemplateModuleMeta> {
+    let mut modules = template_modules(path, template);
+    for module in &mut modules {
+        let src = match &module.src {
+            Some(src) => src,
+            None => continue,
+        };
+        module.path = resolver.resolve_module(path, src);
+        if let Some(module_path) = &module.path {
+            module.functions = cache.module(root, module_path);
+        }
+    }
+    modules
+}
+
+/// 拼接分包根目录和页面路径
+fn join_path(root: &str, path: &str) -> String {
+    let root = root.trim_matches('/');
+    let path = path.trim_start_matches('/');
+    if root.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", root, path)
+    }
+}
+
+pub(crate) fn parse_app(app: &Value) -> AppMeta {
+    let mut meta = AppMeta {
+        pages: string_list(&app["pages"]),
+        using_components: string_map(&app["usingComponents"]),
+        ..AppMeta::default()
+    };
+    // 兼容小写的 subpackages
+    let sub_packages = if app["subPackages"].is_array() {
+        &app["subPackages"]
+    } else {
+        &app["subpackages"]
+    };
+    for sub_package in sub_packages.as_array().into_iter().flatten() {
+        if let Some(root) = sub_package["root"].as_str() {
+            let pages = string_list(&sub_package["pages"])
+                .iter()
+                .map(|page| join_path(root, page))
+                .collect();
+            meta.sub_packages.push(SubPackage {
+                root: root.trim_matches('/').to_string(),
+                pages,
+            });
+        }
+    }
+    for item in app["tabBar"]["list"].as_array().into_iter().flatten() {
+        if let Some(page_path) = item["pagePath"].as_str() {
+            meta.tab_bar
+                .push(page_path.trim_start_matches('/').to_string());
+        }
+    }
+    meta
+}
+
+/// 读取 `app.json` 并解析项目中所有页面和组件，通过 `batch` 取消解析或接收进度通知，
+/// 取消后只返回已发现的页面和组件及其配置，不再检查模板
+pub fn parse_project_with_context(
+    parser: &Parser,
+    root: &str,
+    batch: &BatchContext,
+) -> Result<ProjectMeta, ParseError> {
+    let root_path = Path::new(root);
+    let resolver = Resolver::new(root);
+    let app = read_json(&root_path.join("app.json"))?;
+    let app = parse_app(&app);
+
+    let mut units: BTreeMap<String, ProjectUnit> = BTreeMap::new();
+    let mut queue: VecDeque<(String, UnitKind, Option<String>)> = VecDeque::new();
+    for page in &app.pages {
+        queue.push_back((page.clone(), UnitKind::Page, None));
+    }
+    for sub_package in &app.sub_packages {
+        for page in &sub_package.pages {
+            queue.push_back((page.clone(), UnitKind::Page, Some(sub_package.root.clone())));
+        }
+    }
+    // 全局组件相对项目根目录解析，所有页面和组件都可以使用
+    let global = resolver.resolve_global();
+    for component in global.components.values() {
+        if let Some(ComponentTarget::Local { path, .. }) = &component.target {
+            queue.push_back((path.clone(), UnitKind::Component, None));
+        }
+    }
+
+    while let Some((path, kind, package)) = queue.pop_front() {
+        if units.contains_key(&path) {
+            continue;
+        }
+        let files = find_files(&root_path.join(&path));
+        let config = files
+            .config
+            .as_ref()
+            .map(|config| FileResult::timed(|| parser.parse_config(config)));
+        let resolved = match config.as_ref().and_then(|c| c.meta()) {
+            Some(config) => resolver.resolve_components(&path, config),
+            None => Default::default(),
+        };
+        for component in resolved.components.values() {
+            if let Some(ComponentTarget::Local { path, .. }) = &component.target {
+                if !units.contains_key(path) {
+                    queue.push_back((path.clone(), UnitKind::Component, None));
+                }
+            }
+        }
+        units.insert(
+            path.clone(),
+            ProjectUnit {
+                path,
+                kind,
+                package,
+                files,
+                config,
+                components: resolved.components,
+                diagnostics: resolved.diagnostics,
+                script: None,
+                template: None,
+                style: None,
+                classes: vec![],
+            },
+        );
+    }
+
+    let script_files: Vec<String> = units
+        .values()
+        .filter_map(|u| u.files.script.clone())
+        .collect();
+    let mut style_files: Vec<String> = units
+        .values()
+        .filter_map(|u| u.files.style.clone())
+        .collect();
+    let app_style = root_path.join("app.css");
+    let app_style = app_style
+        .is_file()
+        .then(|| app_style.to_string_lossy().into_owned());
+    style_files.extend(app_style.clone());
+    let template_files: Vec<String> = units
+        .values()
+        .filter_map(|u| u.files.template.clone())
+        .collect();
+    let mut scripts: HashMap<_, _> = parser
+        .parse_script_files_with_context(&script_files, batch)
+        .files;
+    let mut styles: HashMap<_, _> = parser
+        .parse_css_files_with_context(&style_files, batch)
+        .files;
+    let mut templates: HashMap<_, _> = parser
+        .parse_template_files_with_context(&template_files, batch)
+        .files;
+    if batch.is_cancelled() {
+        return Ok(ProjectMeta {
+            root: root.to_string(),
+            app,
+            units,
+        });
+    }
+    let mut cache = LinkCache::default();
+    for unit in units.values_mut() {
+        if let Some(script) = &unit.files.script {
+            unit.script = scripts.remove(script);
+        }
+        if let Some(template) = &unit.files.template {
+            unit.template = templates.remove(template);
+        }
+        if let Some(FileResult::Ok { meta, .. }) = &mut unit.template {
+            // 未声明的变量与模板语法错误一起记录在模板的 diagnostics 中
+            if let Some(script) = unit.script.as_ref().and_then(|s| s.meta()) {
+                meta.diagnostics.extend(check_template(meta, script));
+            }
+            let template_path = format!("{}.swan", unit.path);
+            let (imports, definitions) =
+                link_template(&resolver, root_path, &template_path, meta, &mut cache);
+            let usages = template_usages(meta, &definitions);
+            meta.diagnostics.extend(check_template_imports(&imports));
+            meta.diagnostics
+                .extend(check_template_usages(&usages, &definitions));
+            let modules = load_modules(&resolver, root_path, &template_path, meta, &mut cache);
+            meta.diagnostics.extend(check_template_modules(&modules));
+            meta.diagnostics.extend(check_module_calls(meta, &modules));
+        }
+        // 组件的样式是隔离的，只有页面可以使用 `app.css`
+        let mut entries: Vec<String> = unit.files.style.iter().cloned().collect();
+        if unit.kind == UnitKind::Page {
+            entries.extend(app_style.clone());
+        }
+        let visible = visible_styles(&styles, &entries);
+        if let Some(FileResult::Ok { meta, .. }) = &mut unit.template {
+            unit.classes = template_classes(meta, &visible);
+            meta.diagnostics
+                .extend(check_classes(&unit.classes, &visible));
+        }
+    }
+    // 自定义组件的属性在所有组件的 js 解析完成后检查，页面或组件自身的配置覆盖同名的全局组件
+    let mut component_diagnostics: Vec<(String, Vec<Diagnostic>)> = vec![];
+    for unit in units.values() {
+        if let Some(template) = unit.template.as_ref().and_then(|t| t.meta()) {
+            let components: BTreeMap<&str, &ComponentMeta> = global
+                .components
+                .iter()
+                .chain(&unit.components)
+                .filter_map(|(name, component)| match &component.target {
+                    Some(ComponentTarget::Local { path, .. }) => {
+                        let script = units.get(path)?.script.as_ref()?.meta()?;
+                        Some((name.as_str(), script))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let diagnostics = check_components(template, &components);
+            if !diagnostics.is_empty() {
+                component_diagnostics.push((unit.path.clone(), diagnostics));
+            }
+        }
+    }
+    for (path, diagnostics) in component_diagnostics {
+        if let Some(FileResult::Ok { meta, .. }) =
+            units.get_mut(&path).and_then(|unit| unit.template.as_mut())
+        {
+            meta.diagnostics.extend(diagnostics);
+        }
+    }
+    // 样式文件可能被其他页面或组件 `@import`，所有页面和组件的类名匹配完成后再取出
+    for unit in units.values_mut() {
+        if let Some(style) = &unit.files.style {
+            unit.style = styles.remove(style);
+        }
+    }
+
+    Ok(ProjectMeta {
+        root: root.to_string(),
+        app,
+        units,
+    })
+}
+
+#[test]
+fn test_parse_project() {
+    let project = parse_project_with_context(
+        &Parser::new(),
+        "test/fixtures/project",
+        &BatchContext::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        project.app.pages,
+        vec!["pages/index/index", "pages/logs/logs"]
+    );
+    assert_eq!(
+        project.app.sub_packages[0].pages,
+        vec!["sub/pages/detail/detail"]
+    );
+    assert_eq!(project.app.tab_bar.len(), 2);
+
+    let paths: Vec<&str> = project.units.keys().map(|k| k.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "components/avatar/avatar",
+            "components/button/button",
+            "components/card/card",
+            "miniprogram_npm/ui-kit/cell/cell",
+            "node_modules/@demo/ui/miniprogram_dist/tag/tag",
+            "pages/index/index",
+            "pages/logs/logs",
+            "sub/pages/detail/detail",
+        ]
+    );
+
+    let index = &project.units["pages/index/index"];
+    assert_eq!(index.kind, UnitKind::Page);
+    assert!(index
+        .files
+        .template
+        .as_ref()
+        .unwrap()
+        .ends_with("index.swan"));
+    let config = index.config.as_ref().unwrap().meta().unwrap();
+    assert_eq!(config.using_components.len(), 5);
+    assert_eq!(index.components.len(), 5);
+    assert_eq!(index.diagnostics.len(), 1);
+    assert_eq!(config.navigation_bar_title_text.as_deref(), Some("首页"));
+    assert_eq!(index.script.as_ref().unwrap().meta().unwrap().data.len(), 2);
+    let style = index.style.as_ref().unwrap().meta().unwrap();
+    assert_eq!(style.imports.as_ref().unwrap().len(), 2);
+
+    let template = index.template.as_ref().unwrap().meta().unwrap();
+    assert_eq!(template.nodes.len(), 1);
+    // `g-button` 在 app.json 中全局注册，按 button 组件的属性检查
+    let messages: Vec<&str> = template
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["component `g-button` has no property `sise`"]
+    );
+
+    let logs = &project.units["pages/logs/logs"];
+    assert!(logs.files.script.as_ref().unwrap().ends_with("logs.ts"));
+    assert!(logs.script.as_ref().unwrap().meta().is_some());
+    assert!(logs.style.is_none());
+    // `/templates/log.swan` 中的命名模板读取的变量都在 data 中传入
+    let template = logs.template.as_ref().unwrap().meta().unwrap();
+    assert!(template.diagnostics.is_empty());
+
+    // npm 包中的组件与项目中的组件一样解析属性
+    let tag = &project.units["node_modules/@demo/ui/miniprogram_dist/tag/tag"];
+    let tag_meta = tag.script.as_ref().unwrap().meta().unwrap();
+    assert_eq!(tag_meta.properties.len(), 2);
+    assert_eq!(tag_meta.events.as_ref().unwrap()[0].name, "close");
+
+    let detail = &project.units["sub/pages/detail/detail"];
+    assert_eq!(detail.package.as_deref(), Some("sub"));
+
+    let card = &project.units["components/card/card"];
+    assert_eq!(card.kind, UnitKind::Component);
+    let template = card.template.as_ref().unwrap().meta().unwrap();
+    assert!(template.diagnostics.is_empty());
+    let class = &card.classes[1];
+    assert_eq!(class.name, "card-title");
+    assert!(class.definitions[0].file.ends_with("card.css"));
+    // 没有样式文件的组件不检查类名
+    let button = &project.units["components/button/button"];
+    assert_eq!(button.classes.len(), 1);
+    assert!(button.classes[0].definitions.is_empty());
+    let template = button.template.as_ref().unwrap().meta().unwrap();
+    assert!(template.diagnostics.is_empty());
+    assert!(card.config.as_ref().unwrap().meta().unwrap().component);
+    assert_eq!(
+        card.script
+            .as_ref()
+            .unwrap()
+            .meta()
+            .unwrap()
+            .properties
+            .len(),
+        1
+    );
+
+    let error = parse_project_with_context(
+        &Parser::new(),
+        "test/fixtures/notfound",
+        &BatchContext::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::NotFound);
+}
+
+#[test]
+fn test_parse_project_cancelled() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let root = "test/fixtures/project";
+    let reported = Arc::new(AtomicUsize::new(0));
+    let on_progress: crate::ProgressFn = {
+        let reported = reported.clone();
+        Box::new(move |_, _, _| {
+            reported.fetch_add(1, Ordering::Relaxed);
+        })
+    };
+    let batch = BatchContext::new(None, Some(on_progress));
+    let project = parse_project_with_context(&Parser::new(), root, &batch).unwrap();
+    let files = project
+        .units
+        .values()
+        .map(|u| u.files.script.iter().chain(&u.files.template).count())
+        .sum::<usize>();
+    assert!(reported.load(Ordering::Relaxed) >= files);
+
+    // 在第一个文件解析完成时取消，之后的模板不再解析
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let on_progress: crate::ProgressFn = {
+        let cancelled = cancelled.clone();
+        Box::new(move |_, _, _| cancelled.store(true, Ordering::Relaxed))
+    };
+    let batch = BatchContext::new(Some(cancelled), Some(on_progress));
+    let project = parse_project_with_context(&Parser::new(), root, &batch).unwrap();
+    assert_eq!(project.units.len(), 8);
+    assert!(project.units.values().all(|u| u.template.is_none()));
+    assert!(project.units.values().all(|u| u.classes.is_empty()));
+}
+
+#[test]
+fn test_link_cache() {
+    let root = Path::new("test/fixtures/project");
+    let mut cache = LinkCache::default();
+    assert!(cache.template(root, "templates/log.swan").is_some());
+    assert!(cache.template(root, "templates/notfound.swan").is_none());
+    assert!(cache.template(root, "templates/log.swan").is_some());
+    assert_eq!(cache.templates.len(), 2);
+    assert!(cache.module(root, "utils/log.filter.js").is_some());
+    assert!(cache.modules.contains_key("utils/log.filter.js"));
+}
@@ -0,0 +1,275 @@
+//! 将 `usingComponents` 中的组件路径解析为项目中的文件，或插件等外部组件，
+//! 以及模板中 `<import>`、`<include>` 的路径
+
+use crate::npm;
+use crate::parser::{normalize_path, parse_config, ConfigMeta, Diagnostic, Location, ParseError};
+use crate::project::UnitFiles;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 组件路径指向的目标
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum ComponentTarget {
+    /// 项目中的组件，`path` 为相对项目根目录、不带扩展名的路径
+    Local { path: String, files: UnitFiles },
+    /// `plugin://provider/name` 引用的插件组件
+    Plugin { provider: String, name: String },
+    /// `dynamicLib://provider/name` 引用的动态库组件
+    DynamicLib { provider: String, name: String },
+}
+
+/// `usingComponents` 中的一项，无法解析时 `target` 为 `None`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResolvedComponent {
+    pub name: String,
+    /// 配置中的原始路径
+    pub path: String,
+    /// 配置中 key 的位置
+    pub loc: Location,
+    pub target: Option<ComponentTarget>,
+}
+
+/// 页面或组件配置中所有 `usingComponents` 的解析结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResolvedComponents {
+    pub components: BTreeMap<String, ResolvedComponent>,
+    /// 无法解析的组件，位置为配置中的 key
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 查找同名文件，`base` 为不带扩展名的路径
+pub(crate) fn find_files(base: &Path) -> UnitFiles {
+    let find = |extensions: &[&str]| {
+        extensions.iter().find_map(|ext| {
+            // 不使用 `with_extension`，文件名中可能本身带有 `.`
+            let file_path = PathBuf::from(format!("{}.{}", base.to_string_lossy(), ext));
+            if file_path.is_file() {
+                Some(file_path.to_string_lossy().into_owned())
+            } else {
+                None
+            }
+        })
+    };
+    UnitFiles {
+        script: find(&["js", "ts"]),
+        template: find(&["swan"]),
+        style: find(&["css"]),
+        config: find(&["json"]),
+    }
+}
+
+/// 组件路径解析器，所有路径都相对项目根目录
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    root: PathBuf,
+}
+
+impl Resolver {
+    pub fn new(root: &str) -> Self {
+        Resolver {
+            root: PathBuf::from(root),
+        }
+    }
+
+    /// 解析 `from` 的配置中的组件路径，`from` 为页面或组件相对项目根目录、不带扩展名的路径，
+    /// 全局组件的 `from` 为 `app`
+    pub fn resolve(&self, from: &str, component_path: &str) -> Result<ComponentTarget, String> {
+        if let Some((scheme, rest)) = component_path.split_once("://") {
+            let (provider, name) = rest
+                .split_once('/')
+                .filter(|(provider, name)| !provider.is_empty() && !name.is_empty())
+                .ok_or_else(|| format!("invalid component path `{}`", component_path))?;
+            let (provider, name) = (provider.to_string(), name.to_string());
+            return match scheme {
+                "plugin" => Ok(ComponentTarget::Plugin { provider, name }),
+                "dynamicLib" => Ok(ComponentTarget::DynamicLib { provider, name }),
+                _ => Err(format!("unsupported component path `{}`", component_path)),
+            };
+        }
+
+        let dir = Path::new(from).parent().unwrap_or_else(|| Path::new(""));
+        let target = if let Some(path) = component_path.strip_prefix('/') {
+            self.resolve_local(Path::new(path))
+        } else if component_path.starts_with('.') {
+            self.resolve_local(&dir.join(component_path))
+        } else {
+            // 不以 `./` 开头的路径先按相对路径查找，再查找 npm 包
+            self.resolve_local(&dir.join(component_path))
+                .or_else(|| self.resolve_npm(dir, component_path))
+        };
+        target.ok_or_else(|| format!("cannot find component `{}`", component_path))
+    }
+
+    fn resolve_local(&self, path: &Path) -> Option<ComponentTarget> {
+        let path = normalize_path(path);
+        // 超出项目根目录的路径无法解析
+        if path.starts_with("..") || path.has_root() {
+            return None;
+        }
+        let path = path.to_string_lossy().replace('\\', "/");
+        let files = find_files(&self.root.join(&path));
+        if files.script.is_none() && files.template.is_none() && files.config.is_none() {
+            return None;
+        }
+        Some(ComponentTarget::Local { path, files })
+    }
+
+    /// 查找 npm 包中的组件，只有包名时使用包中的 `index`
+    fn resolve_npm(&self, dir: &Path, component_path: &str) -> Option<ComponentTarget> {
+        npm::candidates(&self.root, dir, component_path)
+            .iter()
+            .find_map(|base| {
+                self.resolve_local(base)
+                    .or_else(|| self.resolve_local(&base.join("index")))
+            })
+    }
+
+    /// 解析模板中 `<import>`、`<include>` 的 `src`，`from` 为模板相对项目根目录的路径，
+    /// 返回相对项目根目录的路径，省略扩展名时补全 `.swan`，文件不存在时返回 `None`
+    pub fn resolve_template(&self, from: &str, src: &str) -> Option<String> {
+        let mut path = self.template_relative(from, src)?;
+        if Path::new(&path).extension().is_none() {
+            path.push_str(".swan");
+        }
+        self.root.join(&path).is_file().then_some(path)
+    }
+
+    /// 解析模板中 `<filter>`、`<import-sjs>` 的 `src`，不补全扩展名，文件不存在时返回 `None`
+    pub fn resolve_module(&self, from: &str, src: &str) -> Option<String> {
+        let path = self.template_relative(from, src)?;
+        self.root.join(&path).is_file().then_some(path)
+    }
+
+    /// 模板中引用的路径相对项目根目录的形式，`/` 开头的路径相对项目根目录，超出项目根目录时返回 `None`
+    fn template_relative(&self, from: &str, src: &str) -> Option<String> {
+        let dir = Path::new(from).parent().unwrap_or_else(|| Path::new(""));
+        let path = match src.strip_prefix('/') {
+            Some(path) => normalize_path(Path::new(path)),
+            None => normalize_path(&dir.join(src)),
+        };
+        if path.starts_with("..") || path.has_root() {
+            return None;
+        }
+        Some(path.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// 解析配置中所有 `usingComponents`，无法解析的组件记录在 `diagnostics` 中
+    pub fn resolve_components(&self, from: &str, config: &ConfigMeta) -> ResolvedComponents {
+        let mut result = ResolvedComponents::default();
+        for (name, using_component) in &config.using_components {
+            let target = match self.resolve(from, &using_component.path) {
+                Ok(target) => Some(target),
+                Err(message) => {
+                    result.diagnostics.push(Diagnostic::error(
+                        format!("failed to resolve component `{}`: {}", name, message),
+                        using_component.loc.clone(),
+                    ));
+                    None
+                }
+            };
+            result.components.insert(
+                name.clone(),
+                ResolvedComponent {
+                    name: name.clone(),
+                    path: using_component.path.clone(),
+                    loc: using_component.loc.clone(),
+                    target,
+                },
+            );
+        }
+        result
+    }
+
+    /// 解析 `app.json` 中全局注册的 `usingComponents`，所有页面和组件都可以使用，没有 `app.json` 时为空
+    pub fn resolve_global(&self) -> ResolvedComponents {
+        let config_path = self.root.join("app.json");
+        match parse_config(&config_path.to_string_lossy()) {
+            Ok(config) => self.resolve_components("app", &config),
+            Err(_) => ResolvedComponents::default(),
+        }
+    }
+
+    /// 读取页面或组件的 json 配置并解析其中的 `usingComponents`
+    pub fn resolve_unit(&self, unit_path: &str) -> Result<ResolvedComponents, ParseError> {
+        let config_path = self.root.join(format!("{}.json", unit_path));
+        let config = parse_config(&config_path.to_string_lossy())?;
+        Ok(self.resolve_components(unit_path, &config))
+    }
+}
+
+#[test]
+fn test_resolve_components() {
+    let resolver = Resolver::new("test/fixtures/project");
+    let global = resolver.resolve_global();
+    assert!(matches!(
+        &global.components["g-button"].target,
+        Some(ComponentTarget::Local { path, .. }) if path == "components/button/button"
+    ));
+    let result = resolver.resolve_unit("pages/index/index").unwrap();
+
+    let card = &result.components["card"];
+    match card.target.as_ref().unwrap() {
+        ComponentTarget::Local { path, files } => {
+            assert_eq!(path, "components/card/card");
+            assert!(files.script.as_ref().unwrap().ends_with("card.js"));
+        }
+        target => panic!("unexpected target {:?}", target),
+    }
+    assert_eq!(
+        result.components["plugin-list"].target,
+        Some(ComponentTarget::Plugin {
+            provider: String::from("myPlugin"),
+            name: String::from("list"),
+        })
+    );
+    assert_eq!(
+        result.components["dyn-item"].target,
+        Some(ComponentTarget::DynamicLib {
+            provider: String::from("myLib"),
+            name: String::from("item"),
+        })
+    );
+    match result.components["cell"].target.as_ref().unwrap() {
+        ComponentTarget::Local { path, .. } => {
+            assert_eq!(path, "miniprogram_npm/ui-kit/cell/cell")
+        }
+        target => panic!("unexpected target {:?}", target),
+    }
+
+    assert!(result.components["missing"].target.is_none());
+    assert_eq!(result.diagnostics.len(), 1);
+    assert_eq!(
+        result.diagnostics[0].message,
+        "failed to resolve component `missing`: cannot find component `/components/missing/missing`"
+    );
+    assert_eq!(result.diagnostics[0].loc, Location::from([5, 8], [5, 17]));
+
+    let result = resolver.resolve_unit("pages/logs/logs").unwrap();
+    match result.components["ui-tag"].target.as_ref().unwrap() {
+        ComponentTarget::Local { path, .. } => {
+            assert_eq!(path, "node_modules/@demo/ui/miniprogram_dist/tag/tag")
+        }
+        target => panic!("unexpected target {:?}", target),
+    }
+
+    let result = resolver.resolve_unit("sub/pages/detail/detail").unwrap();
+    assert!(result.diagnostics.is_empty());
+    assert!(resolver.resolve("app", "../outside/outside").is_err());
+    assert!(resolver.resolve("app", "plugin://myPlugin").is_err());
+    assert!(resolver.resolve("app", "https://a/b").is_err());
+    assert!(resolver.resolve_unit("pages/notfound/notfound").is_err());
+
+    let from = "pages/logs/logs.swan";
+    let expected = Some(String::from("templates/log.swan"));
+    assert_eq!(
+        resolver.resolve_template(from, "/templates/log.swan"),
+        expected
+    );
+    assert_eq!(
+        resolver.resolve_template(from, "../../templates/log"),
+        expected
+    );
+    assert_eq!(resolver.resolve_template(from, "./missing.swan"), None);
+    assert_eq!(resolver.resolve_template(from, "../../../log.swan"), None);
+}
@@ -0,0 +1,301 @@
+//! WebAssembly 绑定，解析内存中的源码，解析结果以 JSON 字符串返回给 js
+//!
+//! 浏览器中没有文件系统，css 的 `@import` 地址只按字面拼接，不检查文件是否存在
+
+use crate::analyzer::{
+    self, Analysis, AnalysisSource, TemplateDefinitionMeta, TemplateImportMeta, TemplateModuleMeta,
+    UnitStyles,
+};
+use crate::batch::FileResult;
+use crate::catalog;
+use crate::parser::{
+    self, normalize_path, ComponentMeta, ParseError, ParseErrorKind, TemplateMeta,
+};
+use std::collections::{BTreeMap, HashMap};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = JSON, js_name = parse)]
+    fn json_parse(text: &str) -> JsValue;
+    #[wasm_bindgen(js_namespace = Object, js_name = assign)]
+    fn object_assign(target: &JsValue, source: &JsValue) -> JsValue;
+}
+
+/// 将解析错误转换为 js 的 `Error`，和 node 绑定批量解析结果中的 `error` 一样带有 `kind`、`message` 和 `loc`
+fn to_error(error: &ParseError) -> JsValue {
+    let value: JsValue = JsError::new(&error.message).into();
+    if let Ok(json) = serde_json::to_string(error) {
+        object_assign(&value, &json_parse(&json));
+    }
+    value
+}
+
+fn to_json(result: Result<impl serde::Serialize, ParseError>) -> Result<String, JsValue> {
+    match result {
+        Ok(meta) => serde_json::to_string(&meta).map_err(|e| JsValue::from_str(&e.to_string())),
+        Err(error) => Err(to_error(&error)),
+    }
+}
+
+/// 解析 js 源码，`file_path` 仅用于错误信息
+#[wasm_bindgen(js_name = parseScript)]
+pub fn parse_script(file_path: &str, source: &str) -> Result<String, JsValue> {
+    let result = parser::parse_component_source(file_path, source.to_string());
+    to_json(result)
+}
+
+/// 解析 css 源码，`file_path` 用于拼接 `@import` 的相对路径
+#[wasm_bindgen(js_name = parseCss)]
+pub fn parse_css(file_path: &str, source: &str) -> Result<String, JsValue> {
+    let result = parser::parse_css_source_with(file_path, source, |path| {
+        Some(normalize_path(path).to_string_lossy().into_owned())
+    });
+    to_json(result)
+}
+
+/// 解析 filter 或 sjs 模块源码，`file_path` 仅用于错误信息
+#[wasm_bindgen(js_name = parseFilter)]
+pub fn parse_filter(file_path: &str, source: &str) -> Result<String, JsValue> {
+    let result = parser::parse_filter_source(file_path, source.to_string());
+    to_json(result)
+}
+
+/// 解析页面或组件的 json 配置
+#[wasm_bindgen(js_name = parseConfig)]
+pub fn parse_config(file_path: &str, source: &str) -> Result<String, JsValue> {
+    let result = parser::parse_config_source(file_path, source);
+    to_json(result)
+}
+
+/// 解析 swan 模板
+#[wasm_bindgen(js_name = parseTemplate)]
+pub fn parse_template(file_path: &str, source: &str) -> Result<String, JsValue> {
+    let result = parser::parse_template_source(file_path, source);
+    to_json(result)
+}
+
+/// 解析 swan 模板中的绑定表达式
+#[wasm_bindgen(js_name = parseTemplateBindings)]
+pub fn parse_template_bindings(file_path: &str, source: &str) -> Result<String, JsValue> {
+    let result = parser::parse_template_source(file_path, source)
+        .map(|template| parser::extract_bindings(&template));
+    to_json(result)
+}
+
+/// 内存中的源码文件
+#[derive(Deserialize)]
+struct SourceFile {
+    path: String,
+    source: String,
+}
+
+/// `analyze` 的参数，除 `Analysis` 的字段外带有模板及分析需要的其他源码，
+/// 浏览器中无法读取 `<import>` 和模块引用的文件，由调用方解析后通过 `definitions`、`modules` 传入
+#[derive(Deserialize)]
+struct AnalysisRequest {
+    #[serde(flatten)]
+    analysis: Analysis,
+    template: SourceFile,
+    #[serde(default)]
+    script: Option<SourceFile>,
+    /// 样式源码，不解析 `@import` 的文件
+    #[serde(default)]
+    style: Option<SourceFile>,
+    /// 标签名到 `parseScript` 结果的 `usingComponents` 组件
+    #[serde(default)]
+    components: BTreeMap<String, ComponentMeta>,
+    /// `<import>` 文件中的命名模板，由 `templateDefinitions` 分析得到，与模板自身定义的命名模板合并
+    #[serde(default)]
+    definitions: Vec<TemplateDefinitionMeta>,
+    /// `templateModules` 分析返回并用 `parseFilter` 填入了函数的模块，不传时模块的 `path` 和 `functions` 均为 `null`
+    #[serde(default)]
+    modules: Option<Vec<TemplateModuleMeta>>,
+}
+
+impl AnalysisRequest {
+    fn required<'a>(
+        &self,
+        file: &'a Option<SourceFile>,
+        name: &str,
+    ) -> Result<&'a SourceFile, ParseError> {
+        file.as_ref().ok_or_else(|| {
+            ParseError::new(
+                ParseErrorKind::NotFound,
+                format!("{} source is required for this analysis", name),
+            )
+        })
+    }
+}
+
+impl AnalysisSource for AnalysisRequest {
+    fn template_path(&self) -> String {
+        self.template.path.clone()
+    }
+
+    fn template(&self) -> Result<TemplateMeta, ParseError> {
+        parser::parse_template_source(&self.template.path, &self.template.source)
+    }
+
+    fn component(&self) -> Result<ComponentMeta, ParseError> {
+        let script = self.required(&self.script, "script")?;
+        parser::parse_component_source(&script.path, script.source.clone())
+    }
+
+    fn styles(&self) -> Result<UnitStyles, ParseError> {
+        let style = self.required(&self.style, "style")?;
+        let css = parser::parse_css_source_with(&style.path, &style.source, |_| None)?;
+        let meta = FileResult::Ok {
+            meta: css,
+            elapsed: 0.0,
+        };
+        Ok((
+            vec![style.path.clone()],
+            HashMap::from([(style.path.clone(), meta)]),
+        ))
+    }
+
+    fn components(&self) -> Result<BTreeMap<String, ComponentMeta>, ParseError> {
+        Ok(self.components.clone())
+    }
+
+    /// 浏览器中无法解析路径，`<import>`、`<include>` 的 `path` 均为 `null`
+    fn link(
+        &self,
+        template: &TemplateMeta,
+    ) -> (Vec<TemplateImportMeta>, Vec<TemplateDefinitionMeta>) {
+        let mut definitions = analyzer::template_definitions(&self.template.path, template);
+        definitions.extend(self.definitions.iter().cloned());
+        (analyzer::template_imports(template), definitions)
+    }
+
+    fn modules(&self, template: &TemplateMeta) -> Vec<TemplateModuleMeta> {
+        match &self.modules {
+            Some(modules) => modules.clone(),
+            None => analyzer::template_modules(&self.template.path, template),
+        }
+    }
+}
+
+/// 分析模板，`request` 为 `AnalysisRequest` 的 JSON 字符串，例如
+/// `{"kind":"checkTemplate","template":{"path":"page.swan","source":"..."},"script":{"path":"page.js","source":"..."}}`
+#[wasm_bindgen]
+pub fn analyze(request: &str) -> Result<String, JsValue> {
+    let request: AnalysisRequest =
+        serde_json::from_str(request).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    to_json(analyzer::analyze(&request, &request.analysis))
+}
+
+/// 内置组件目录
+#[wasm_bindgen(js_name = builtinComponents)]
+pub fn builtin_components() -> Result<String, JsValue> {
+    serde_json::to_string(catalog::catalog()).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[test]
+fn test_parse_source() {
+    let result = parse_script("page.js", "Page({data: {a: 1}, onLoad() {}})").unwrap();
+    assert!(result.starts_with(r#"{"type":"Page","data":[{"name":"a""#));
+
+    let result = parse_css("/app/page.css", "@import '../common.css';\n.a {}").unwrap();
+    assert!(result.contains(r#""imports":["/common.css"]"#));
+
+    let result = parse_config("page.json", r#"{"component": true}"#).unwrap();
+    assert!(result.starts_with(r#"{"component":true"#));
+
+    let result = parse_template("page.swan", "<view>{{title}}</view>").unwrap();
+    assert!(result.starts_with(r#"{"nodes":[{"type":"Element","name":"view""#));
+
+    let result = parse_template_bindings("page.swan", "<view>{{title}}</view>").unwrap();
+    assert!(result.starts_with(r#"{"bindings":[{"kind":"Text","attribute":null"#));
+
+    let request = |analysis: &str, page: &str| {
+        format!(
+            r#"{{{},"template":{{"path":"page.swan","source":{}}},"script":{{"path":"page.js","source":"Page({{data: {{title: '', list: []}}, onTap() {{}}}})"}}}}"#,
+            analysis,
+            serde_json::to_string(page).unwrap()
+        )
+    };
+    let result = analyze(&request(
+        r#""kind":"checkTemplate""#,
+        "<view>{{titel}}</view>",
+    ))
+    .unwrap();
+    assert!(result.contains(r#""suggestion":"title""#));
+    let page = "<view s-for=\"list\">{{item}}</view>";
+    let analysis = r#""kind":"templateVariables","position":{"line":1,"column":22}"#;
+    let result = analyze(&request(analysis, page)).unwrap();
+    assert!(result.starts_with(r#"[{"name":"index","kind":"Index""#));
+    let page = "<view bindtap=\"onTap\" />";
+    let result = analyze(&request(r#""kind":"eventBindings""#, page)).unwrap();
+    assert!(result.contains(r#""event":"tap""#));
+    assert!(result.contains(r#""method":{"name":"onTap""#));
+
+    let request = |analysis: &str, page: &str, extra: &str| {
+        format!(
+            r#"{{{},"template":{{"path":"page.swan","source":{}}}{}}}"#,
+            analysis,
+            serde_json::to_string(page).unwrap(),
+            extra
+        )
+    };
+    let template = "<view class=\"card {{open ? 'card-open' : ''}} titel\" />";
+    let style = r#","style":{"path":"card.css","source":"\n.card {}\n.title {}"}"#;
+    let result = analyze(&request(r#""kind":"templateClasses""#, template, style)).unwrap();
+    assert!(result.contains(r#""definitions":[{"file":"card.css""#));
+    let result = analyze(&request(r#""kind":"checkClasses""#, template, style)).unwrap();
+    assert!(result.contains(r#""suggestion":"title""#));
+    assert!(result.contains("`card-open`"));
+
+    let card = parse_script(
+        "card.js",
+        "Component({properties: {title: String}, methods: {}})",
+    )
+    .unwrap();
+    let components = format!(r#","components":{{"card":{}}}"#, card);
+    let page = "<card titel=\"x\" />";
+    let result = analyze(&request(r#""kind":"checkComponents""#, page, &components)).unwrap();
+    assert!(result.contains(r#""suggestion":"title""#));
+    let analysis = r#""kind":"componentAttributes","tag":"card""#;
+    let result = analyze(&request(analysis, page, &components)).unwrap();
+    assert!(result.starts_with(r#"[{"name":"title","kind":"Property","type":"String""#));
+
+    let analysis = r#""kind":"checkVersions","version":"1.10.0""#;
+    let result = analyze(&request(analysis, "<animation-view />", "")).unwrap();
+    assert!(result.contains("requires base library 1.11.0"));
+    let library = "<template name=\"item\">{{title}}</template>";
+    let definitions = analyze(&format!(
+        r#"{{"kind":"templateDefinitions","template":{{"path":"item.swan","source":{}}}}}"#,
+        serde_json::to_string(library).unwrap()
+    ))
+    .unwrap();
+    assert!(definitions.contains(r#""params":["title"]"#));
+    let page = "<import src=\"./item.swan\" />\n<template is=\"item\" data=\"{{name}}\" />";
+    let result = analyze(&request(r#""kind":"templateImports""#, page, "")).unwrap();
+    assert!(result.contains(r#""kind":"Import","src":"./item.swan""#));
+    let definitions = format!(r#","definitions":{}"#, definitions);
+    let result = analyze(&request(r#""kind":"templateUsages""#, page, &definitions)).unwrap();
+    assert!(result.contains(r#""definition":{"name":"item","file":"item.swan""#));
+    let analysis = r#""kind":"checkTemplateImports""#;
+    let result = analyze(&request(analysis, page, &definitions)).unwrap();
+    assert!(result.contains("reads `title`, but it is not passed in data"));
+
+    let page = "<filter module=\"utils\">export default {upper: value => value};</filter>\n{{utils.uper(name)}}";
+    let modules = analyze(&request(r#""kind":"templateModules""#, page, "")).unwrap();
+    assert!(modules.contains(r#""functions":[{"name":"upper","params":["value"]"#));
+    let modules = format!(r#","modules":{}"#, modules);
+    let result = analyze(&request(r#""kind":"checkModuleCalls""#, page, &modules)).unwrap();
+    assert!(result.contains("did you mean `upper`?"));
+    let analysis = r#""kind":"moduleCompletions","position":{"line":2,"column":8}"#;
+    let result = analyze(&request(analysis, page, &modules)).unwrap();
+    assert!(result.starts_with(r#"[{"name":"upper""#));
+    let result = parse_filter(
+        "utils.filter.js",
+        "export function pad(value, length = 2) {}",
+    )
+    .unwrap();
+    assert!(result.contains(r#""required":1"#));
+
+    let result = builtin_components().unwrap();
+    assert!(result.contains(r#""name":"scroll-view""#));
+}
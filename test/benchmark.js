@@ -1,4 +1,7 @@
 const assert = require('assert');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
 
 const testFiles = [
     [`${__dirname}/fixtures/component.js`, 'component'],
@@ -41,7 +44,70 @@ async function parseByAcorn() {
     assert.strictEqual(Object.keys(result).length, 4, "解析成功个数错误！");
 }
 
+/**
+ * 直接加载原生模块，对比两种传输方式，可以通过 PARSER_ADDON 指定构建产物的路径
+ */
+function loadAddon() {
+    const arch = process.arch === 'arm64' ? 'aarch64' : process.arch;
+    return require(process.env.PARSER_ADDON || `../parser-${arch}-${process.platform}.node`);
+}
+
+/**
+ * 复制 complex-page.js 得到 `count` 个不同路径的文件，避免缓存按路径合并
+ */
+function copyFixtures(count) {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'component-parser-'));
+    const source = fs.readFileSync(`${__dirname}/fixtures/complex-page.js`);
+    return Array.from({length: count}, (_, i) => {
+        const filePath = path.join(dir, `page-${i}.js`);
+        fs.writeFileSync(filePath, source);
+        return filePath;
+    });
+}
+
+/**
+ * 去掉结果中的解析耗时，用于比较两种传输方式的结果
+ */
+function withoutElapsed(result) {
+    const files = Object.entries(result.files).map(([filePath, {elapsed, ...file}]) => [filePath, file]);
+    return Object.fromEntries(files);
+}
+
+function time(rounds, fn) {
+    const start = process.hrtime.bigint();
+    for (let i = 0; i < rounds; i++) {
+        fn();
+    }
+    return Number(process.hrtime.bigint() - start) / 1e6 / rounds;
+}
+
+// 缓存命中时解析耗时可忽略，主要为结果传输：
+// json 为 Rust 序列化 JSON 字符串、js 侧 JSON.parse，structured 为在 Rust 侧通过 N-API 逐个属性构造 js 对象
+async function transferBySwc() {
+    const addon = loadAddon();
+    const batches = [
+        ['fixtures', testFiles.map(([filePath]) => filePath), 200],
+        ['1000 files', copyFixtures(1000), 10],
+    ];
+    for (const [name, filePaths, rounds] of batches) {
+        const json = JSON.parse(addon.parseScriptFiles(filePaths));
+        const structured = addon.parseScriptFilesStructured(filePaths);
+        assert.deepStrictEqual(withoutElapsed(structured), withoutElapsed(json), '两种传输方式的结果不一致！');
+
+        const jsonTime = time(rounds, () => JSON.parse(addon.parseScriptFiles(filePaths)));
+        const structuredTime = time(rounds, () => addon.parseScriptFilesStructured(filePaths));
+        console.log(
+            `transfer:${name}: json ${jsonTime.toFixed(2)}ms, structured ${structuredTime.toFixed(2)}ms`
+        );
+    }
+}
+
 async function main() {
+    // `node test/benchmark.js transfer` 只对比传输方式
+    if (process.argv[2] === 'transfer') {
+        await transferBySwc();
+        return;
+    }
     const rounds = 10;
     console.time('parse:swc');
     for (let i = 0; i < rounds; i++) {
@@ -54,6 +120,8 @@ async function main() {
         await parseByAcorn();
     }
     console.timeEnd('parse:acorn');
+
+    await transferBySwc();
 }
 
 main()
@@ -0,0 +1,2 @@
+myPage({data: {a: 1}, onLoad() {}});
+Component(wrap(wrap()));
@@ -0,0 +1 @@
+Component({properties: {a: {type: String, value: }}});
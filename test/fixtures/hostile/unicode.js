@@ -0,0 +1 @@
+﻿Page({data: {中文: 1, 😀: 2}});
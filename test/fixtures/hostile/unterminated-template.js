@@ -0,0 +1 @@
+Page({data: {`${`${`
\ No newline at end of file
@@ -0,0 +1,8 @@
+import {format} from '../utils/format';
+
+export default Behavior({
+    data: {
+        expanded: false,
+        updated: format(new Date())
+    }
+});
@@ -0,0 +1,6 @@
+Component({
+    properties: {
+        src: String
+    },
+    data: {}
+});
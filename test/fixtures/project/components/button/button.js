@@ -0,0 +1,9 @@
+Component({
+    properties: {
+        type: {
+            type: String,
+            value: 'default'
+        }
+    },
+    methods: {}
+});
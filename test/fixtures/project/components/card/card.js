@@ -0,0 +1,20 @@
+import expandable from '../../behaviors/expandable';
+
+Component({
+    behaviors: [expandable, 'swan://form-field'],
+    properties: {
+        title: {
+            type: String,
+            value: ''
+        }
+    },
+    data: {
+        expanded: false
+    },
+    methods: {
+        toggle() {
+            this.setData({expanded: !this.data.expanded});
+            this.triggerEvent('toggle', {expanded: this.data.expanded});
+        }
+    }
+});
@@ -0,0 +1,8 @@
+Component({
+    properties: {
+        nodes: {
+            type: Array,
+            value: []
+        }
+    }
+});
@@ -0,0 +1,11 @@
+const {format} = require('../../utils/format');
+
+Page({
+    data: {
+        // 列表
+        list: [],
+        title: 'index'
+    },
+    onLoad() {},
+    onTapCard(e) {}
+});
@@ -0,0 +1,10 @@
+interface Log {
+    time: number;
+}
+
+Page({
+    data: {
+        logs: [] as Log[]
+    },
+    onLoad(): void {}
+});
@@ -0,0 +1,6 @@
+Page({
+    data: {
+        id: 0
+    },
+    onLoad(query) {}
+});
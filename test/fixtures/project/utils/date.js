@@ -0,0 +1 @@
+exports.pad = n => (n < 10 ? '0' + n : String(n));
@@ -0,0 +1,5 @@
+const {pad} = require('./date');
+
+export function format(date) {
+    return pad(date.getDate());
+}
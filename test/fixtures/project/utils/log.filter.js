@@ -0,0 +1,7 @@
+export default {
+    // 格式化日志时间
+    time: function (value, pattern) {
+        return value;
+    },
+    level: level => level.toUpperCase()
+};